@@ -93,6 +93,20 @@ fn main() {
     let build_from_source = env::var("CARGO_FEATURE_BUILD_SOURCE").is_ok();
     let static_link = env::var("CARGO_FEATURE_STATIC_LINK").is_ok();
 
+    // The two modes are mutually exclusive. Without this check `build-source` (a default
+    // feature) silently wins and `static-link` is ignored.
+    if build_from_source && static_link {
+        panic!(
+            "the `build-source` and `static-link` features are mutually exclusive.\n\
+\
+`build-source` is enabled by default, so disable default features to link against a\n\
+pre-built library:\n\
+  cargo build --no-default-features --features static-link\n\
+or in Cargo.toml:\n\
+  ccap = {{ package = \"ccap-rs\", version = \"...\", default-features = false, features = [\"static-link\"] }}"
+        );
+    }
+
     // Locate ccap root.
     // build-source path (distribution): prefer ./native for crates.io.
     // static-link path (development): prefer repo root / CCAP_SOURCE_DIR for build artifacts.
@@ -1,4 +1,4 @@
-use ccap::{LogLevel, PixelFormat, PropertyName, Provider, Result, Utils};
+use ccap::prelude::*;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -29,24 +29,19 @@ fn main() -> Result<()> {
     // Select camera device (automatically use first device for testing)
     let device_index = 0;
 
-    // Create provider with selected device
-    let mut provider = Provider::with_device(device_index)?;
-
     // Set camera properties
     let requested_width = 1920;
     let requested_height = 1080;
     let requested_fps = 60.0;
 
-    provider.set_property(PropertyName::Width, requested_width as f64)?;
-    provider.set_property(PropertyName::Height, requested_height as f64)?;
-    provider.set_property(
-        PropertyName::PixelFormatOutput,
-        PixelFormat::Bgra32 as u32 as f64,
-    )?;
-    provider.set_property(PropertyName::FrameRate, requested_fps)?;
+    // Open the selected device with the requested settings applied
+    let mut provider = Provider::builder()
+        .device_index(device_index)
+        .resolution(requested_width, requested_height)
+        .pixel_format(PixelFormat::Bgra32)
+        .frame_rate(requested_fps)
+        .open()?;
 
-    // Open and start camera
-    provider.open()?;
     provider.start()?;
 
     if !provider.is_started() {
@@ -63,8 +58,12 @@ fn main() -> Result<()> {
              requested_width, requested_height, real_width, real_height, requested_fps, real_fps);
 
     // Create directory for captures (using std::fs)
-    std::fs::create_dir_all("./image_capture")
-        .map_err(|e| ccap::CcapError::FileOperationFailed(e.to_string()))?;
+    std::fs::create_dir_all("./image_capture").map_err(|e| {
+        ccap::CcapError::FileOperationFailed(
+            "Failed to create ./image_capture".to_string(),
+            Some(e),
+        )
+    })?;
 
     // Statistics tracking
     let frame_count = Arc::new(Mutex::new(0u32));
@@ -1,4 +1,4 @@
-use ccap::{LogLevel, PropertyName, Provider, Result, Utils};
+use ccap::prelude::*;
 use std::fs;
 
 fn main() -> Result<()> {
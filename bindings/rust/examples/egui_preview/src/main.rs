@@ -0,0 +1,83 @@
+//! Live camera preview in an egui window.
+//!
+//! Frames are grabbed on a background thread and converted with
+//! `VideoFrame::to_rgba8_unpadded`, which yields exactly the buffer
+//! `egui::ColorImage::from_rgba_unmultiplied` expects.
+
+use ccap::{Provider, Result};
+use eframe::egui;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Latest converted frame, handed from the capture thread to the UI
+type LatestFrame = Arc<Mutex<Option<(Vec<u8>, u32, u32)>>>;
+
+struct PreviewApp {
+    latest: LatestFrame,
+    texture: Option<egui::TextureHandle>,
+}
+
+impl eframe::App for PreviewApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some((rgba, width, height)) = self.latest.lock().unwrap().take() {
+            let image =
+                egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &rgba);
+            match &mut self.texture {
+                Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+                None => {
+                    self.texture =
+                        Some(ctx.load_texture("camera", image, egui::TextureOptions::LINEAR))
+                }
+            }
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| match &self.texture {
+            Some(texture) => {
+                ui.add(egui::Image::new(texture).shrink_to_fit());
+            }
+            None => {
+                ui.label("Waiting for the first frame...");
+            }
+        });
+    }
+}
+
+fn spawn_capture(mut provider: Provider, latest: LatestFrame, ctx: egui::Context) {
+    thread::spawn(move || -> Result<()> {
+        provider.start_capture()?;
+        loop {
+            if let Some(frame) = provider.grab_frame(1000)? {
+                *latest.lock().unwrap() = Some(frame.to_rgba8_unpadded()?);
+                ctx.request_repaint();
+            }
+        }
+    });
+}
+
+fn main() -> Result<()> {
+    // Set error callback to receive error notifications
+    Provider::set_error_callback(|error_code, description| {
+        eprintln!(
+            "Camera Error - Code: {}, Description: {}",
+            error_code, description
+        );
+    });
+
+    // Open the default device
+    let mut provider = Provider::new()?;
+    provider.open()?;
+
+    let latest = LatestFrame::default();
+    eframe::run_native(
+        "ccap egui preview",
+        eframe::NativeOptions::default(),
+        Box::new(move |cc| {
+            spawn_capture(provider, Arc::clone(&latest), cc.egui_ctx.clone());
+            Ok(Box::new(PreviewApp {
+                latest,
+                texture: None,
+            }))
+        }),
+    )
+    .map_err(|e| ccap::CcapError::InternalError(format!("eframe failed: {}", e)))
+}
@@ -1,6 +1,9 @@
-use ccap::{CcapError, Provider, Result, Utils};
+use ccap::prelude::*;
 
 fn main() -> Result<()> {
+    // Pass `--ascii` to print a small ASCII preview of each frame (useful over SSH)
+    let ascii_preview = std::env::args().any(|arg| arg == "--ascii");
+
     // Set error callback to receive error notifications
     Provider::set_error_callback(|error_code, description| {
         eprintln!(
@@ -20,8 +23,8 @@ fn main() -> Result<()> {
             camera_index
         ))
     })?;
+    // `with_device` opens the device, so only starting is left
     let mut provider = Provider::with_device(camera_index_i32)?;
-    provider.open()?;
     provider.start()?;
 
     if !provider.is_started() {
@@ -43,6 +46,12 @@ fn main() -> Result<()> {
                     frame.data_size(),
                     frame.pixel_format()
                 );
+                if ascii_preview {
+                    match frame.ascii_preview(64) {
+                        Ok(preview) => println!("{}", preview),
+                        Err(e) => eprintln!("Failed to render preview: {}", e),
+                    }
+                }
             }
             Ok(None) => {
                 eprintln!("Failed to grab frame {}!", i);
@@ -0,0 +1,30 @@
+//! Stream the default camera to a browser tab.
+//!
+//! Run with `cargo run --example mjpeg_server --features mjpeg [addr]` and open
+//! `http://127.0.0.1:8080/` (or the address you passed).
+
+use ccap::prelude::*;
+use std::net::SocketAddr;
+
+fn main() -> Result<()> {
+    // Set error callback to receive error notifications
+    Provider::set_error_callback(|error_code, description| {
+        eprintln!(
+            "Camera Error - Code: {}, Description: {}",
+            error_code, description
+        );
+    });
+
+    let addr: SocketAddr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string())
+        .parse()
+        .map_err(|e| CcapError::InvalidParameter(format!("invalid address: {}", e)))?;
+
+    // Open the default device
+    let mut provider = Provider::new()?;
+    provider.open()?;
+
+    println!("Streaming MJPEG on http://{}/", addr);
+    ccap::serve_mjpeg(provider, addr, 15.0)
+}
@@ -1,4 +1,4 @@
-use ccap::{LogLevel, Provider, Result, Utils};
+use ccap::prelude::*;
 
 fn find_camera_names() -> Result<Vec<String>> {
     // Create a temporary provider to query devices
@@ -35,17 +35,7 @@ fn print_camera_info(device_name: &str) -> Result<()> {
     match provider.device_info() {
         Ok(device_info) => {
             println!("===== Info for device: {} =======", device_name);
-
-            println!("  Supported resolutions:");
-            for resolution in &device_info.supported_resolutions {
-                println!("    {}x{}", resolution.width, resolution.height);
-            }
-
-            println!("  Supported pixel formats:");
-            for format in &device_info.supported_pixel_formats {
-                println!("    {}", format.as_str());
-            }
-
+            print!("{}", device_info.capability_table());
             println!("===== Info end =======\n");
         }
         Err(e) => {
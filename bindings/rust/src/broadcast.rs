@@ -0,0 +1,314 @@
+//! Fan-out of captured frames to multiple consumers
+
+use crate::frame::OwnedFrame;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+struct SubscriberState {
+    frames: VecDeque<Arc<OwnedFrame>>,
+    lagged: u64,
+    closed: bool,
+}
+
+struct Subscriber {
+    state: Mutex<SubscriberState>,
+    ready: Condvar,
+}
+
+impl Subscriber {
+    fn lock(&self) -> MutexGuard<'_, SubscriberState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct BroadcastInner {
+    capacity: usize,
+    senders: AtomicUsize,
+    subscribers: Mutex<Vec<Weak<Subscriber>>>,
+}
+
+impl BroadcastInner {
+    fn subscribers(&self) -> MutexGuard<'_, Vec<Weak<Subscriber>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn subscribe(&self) -> FrameReceiver {
+        let mut subscribers = self.subscribers();
+        // Checked under the lock, so the last sender's drop either sees this subscriber or
+        // has already happened.
+        let closed = self.senders.load(Ordering::Acquire) == 0;
+        let subscriber = Arc::new(Subscriber {
+            state: Mutex::new(SubscriberState {
+                frames: VecDeque::with_capacity(self.capacity),
+                lagged: 0,
+                closed,
+            }),
+            ready: Condvar::new(),
+        });
+        if !closed {
+            subscribers.push(Arc::downgrade(&subscriber));
+        }
+        FrameReceiver { subscriber }
+    }
+
+    fn subscriber_count(&self) -> usize {
+        let mut subscribers = self.subscribers();
+        subscribers.retain(|subscriber| subscriber.strong_count() > 0);
+        subscribers.len()
+    }
+}
+
+/// Broadcast channel delivering every frame to every subscriber
+///
+/// Created with [`FrameBroadcast::new`]. [`Provider::broadcast`](crate::Provider::broadcast)
+/// keeps one inside its frame callback and hands out its [`FrameSubscriptions`] instead.
+/// Every `FrameBroadcast` handle, including clones, is a sender: receivers only see the end
+/// of the stream once all of them are dropped.
+///
+/// Each subscriber has its own queue holding at most `capacity` frames. Frames are shared
+/// as `Arc<OwnedFrame>`, so the pixel data is copied once regardless of the subscriber count.
+/// A subscriber that falls behind doesn't slow down the others: once its queue is full the
+/// oldest frame is dropped and counted in [`FrameReceiver::lagged`].
+pub struct FrameBroadcast {
+    inner: Arc<BroadcastInner>,
+}
+
+impl std::fmt::Debug for FrameBroadcast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameBroadcast")
+            .field("capacity", &self.inner.capacity)
+            .field("subscribers", &self.subscriber_count())
+            .finish()
+    }
+}
+
+impl FrameBroadcast {
+    /// Create a broadcast channel with room for `capacity` frames per subscriber (minimum 1)
+    pub fn new(capacity: usize) -> Self {
+        FrameBroadcast {
+            inner: Arc::new(BroadcastInner {
+                capacity: capacity.max(1),
+                senders: AtomicUsize::new(1),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Create a new receiver that gets every frame sent after this call
+    pub fn subscribe(&self) -> FrameReceiver {
+        self.inner.subscribe()
+    }
+
+    /// Number of live receivers
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.subscriber_count()
+    }
+
+    /// Handle that can subscribe to this channel but not send, so it doesn't keep it open
+    pub fn subscriptions(&self) -> FrameSubscriptions {
+        FrameSubscriptions {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Deliver a frame to all current subscribers
+    pub fn send(&self, frame: OwnedFrame) {
+        let frame = Arc::new(frame);
+        let mut subscribers = self.inner.subscribers();
+        subscribers.retain(|subscriber| match subscriber.upgrade() {
+            Some(subscriber) => {
+                let mut state = subscriber.lock();
+                if state.frames.len() >= self.inner.capacity {
+                    state.frames.pop_front();
+                    state.lagged += 1;
+                }
+                state.frames.push_back(Arc::clone(&frame));
+                drop(state);
+                subscriber.ready.notify_one();
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl Clone for FrameBroadcast {
+    fn clone(&self) -> Self {
+        self.inner.senders.fetch_add(1, Ordering::AcqRel);
+        FrameBroadcast {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl Drop for FrameBroadcast {
+    fn drop(&mut self) {
+        if self.inner.senders.fetch_sub(1, Ordering::AcqRel) != 1 {
+            return;
+        }
+        // Last sender gone: wake receivers so they can drain and observe the close.
+        for subscriber in self.inner.subscribers().drain(..) {
+            if let Some(subscriber) = subscriber.upgrade() {
+                subscriber.lock().closed = true;
+                subscriber.ready.notify_all();
+            }
+        }
+    }
+}
+
+/// Subscribing side of a [`FrameBroadcast`], without the ability to send
+///
+/// Returned by [`Provider::broadcast`](crate::Provider::broadcast) and
+/// [`FrameBroadcast::subscriptions`]. Unlike a `FrameBroadcast` it isn't a sender, so
+/// holding on to it doesn't keep receivers waiting: they see the end of the stream as soon
+/// as the last sender is dropped. Receivers subscribed after that are closed right away.
+#[derive(Clone)]
+pub struct FrameSubscriptions {
+    inner: Arc<BroadcastInner>,
+}
+
+impl std::fmt::Debug for FrameSubscriptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameSubscriptions")
+            .field("capacity", &self.inner.capacity)
+            .field("subscribers", &self.subscriber_count())
+            .finish()
+    }
+}
+
+impl FrameSubscriptions {
+    /// Create a new receiver that gets every frame sent after this call
+    pub fn subscribe(&self) -> FrameReceiver {
+        self.inner.subscribe()
+    }
+
+    /// Number of live receivers
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.subscriber_count()
+    }
+}
+
+/// Receiving half of a [`FrameBroadcast`]
+pub struct FrameReceiver {
+    subscriber: Arc<Subscriber>,
+}
+
+impl FrameReceiver {
+    /// Block until the next frame arrives.
+    ///
+    /// Returns `None` once all senders are gone and the queue is drained.
+    pub fn recv(&self) -> Option<Arc<OwnedFrame>> {
+        let mut state = self.subscriber.lock();
+        loop {
+            if let Some(frame) = state.frames.pop_front() {
+                return Some(frame);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self
+                .subscriber
+                .ready
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Wait up to `timeout` for the next frame
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Arc<OwnedFrame>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.subscriber.lock();
+        loop {
+            if let Some(frame) = state.frames.pop_front() {
+                return Some(frame);
+            }
+            let now = Instant::now();
+            if state.closed || now >= deadline {
+                return None;
+            }
+            state = self
+                .subscriber
+                .ready
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Take the next frame if one is queued, without blocking
+    pub fn try_recv(&self) -> Option<Arc<OwnedFrame>> {
+        self.subscriber.lock().frames.pop_front()
+    }
+
+    /// Total number of frames this receiver missed because its queue was full
+    pub fn lagged(&self) -> u64 {
+        self.subscriber.lock().lagged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PixelFormat;
+
+    fn gray_frame(index: u64) -> OwnedFrame {
+        OwnedFrame::from_planes(2, 2, PixelFormat::Rgb24, vec![vec![0u8; 12]], &[6])
+            .unwrap()
+            .with_frame_index(index)
+    }
+
+    #[test]
+    fn test_broadcast_delivers_to_all_subscribers() {
+        let broadcast = FrameBroadcast::new(8);
+        let first = broadcast.subscribe();
+        let second = broadcast.subscribe();
+
+        for index in 0..3 {
+            broadcast.send(gray_frame(index));
+        }
+        drop(broadcast);
+
+        let first: Vec<u64> = std::iter::from_fn(|| first.recv())
+            .map(|frame| frame.frame_index())
+            .collect();
+        let second: Vec<u64> = std::iter::from_fn(|| second.recv())
+            .map(|frame| frame.frame_index())
+            .collect();
+        assert_eq!(first, vec![0, 1, 2]);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_subscriptions_do_not_keep_the_channel_open() {
+        let broadcast = FrameBroadcast::new(4);
+        let subscriptions = broadcast.subscriptions();
+        let receiver = subscriptions.subscribe();
+        broadcast.send(gray_frame(0));
+        assert_eq!(subscriptions.subscriber_count(), 1);
+        drop(broadcast);
+
+        assert_eq!(receiver.recv().unwrap().frame_index(), 0);
+        assert!(receiver.recv().is_none());
+        // A late subscriber is closed rather than waiting forever
+        assert!(subscriptions.subscribe().recv().is_none());
+    }
+
+    #[test]
+    fn test_slow_subscriber_lags() {
+        let broadcast = FrameBroadcast::new(2);
+        let receiver = broadcast.subscribe();
+
+        for index in 0..5 {
+            broadcast.send(gray_frame(index));
+        }
+
+        assert_eq!(receiver.lagged(), 3);
+        assert_eq!(receiver.try_recv().unwrap().frame_index(), 3);
+        assert_eq!(receiver.try_recv().unwrap().frame_index(), 4);
+        assert!(receiver.try_recv().is_none());
+    }
+}
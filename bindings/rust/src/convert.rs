@@ -1,11 +1,66 @@
 use crate::error::{CcapError, Result};
 use crate::sys;
-use crate::types::ColorConversionBackend;
+use crate::types::{ColorConversionBackend, ColorRange, PixelFormat, Rotation};
+use std::cell::Cell;
 use std::os::raw::c_int;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
 
 /// Color conversion utilities
 pub struct Convert;
 
+/// Set while a backend chosen with [`Convert::set_backend`] is active. Otherwise the native
+/// layer keeps every SIMD backend enabled, and each conversion uses the best one it has a
+/// routine for.
+static BACKEND_CHOSEN: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static LAST_CONVERSION_BACKEND: Cell<Option<ColorConversionBackend>> = const { Cell::new(None) };
+}
+
+/// Remember which backend ran the native conversion that just finished on this thread.
+///
+/// `accelerate_path` tells whether the routine has an Apple Accelerate implementation;
+/// the packed YUV (YUYV/UYVY) converters only have AVX2 and NEON ones.
+fn record_conversion(accelerate_path: bool) {
+    let backend = executed_backend(
+        Convert::backend(),
+        accelerate_path,
+        BACKEND_CHOSEN.load(Ordering::Relaxed),
+        Convert::has_avx2(),
+        Convert::has_neon(),
+    );
+    LAST_CONVERSION_BACKEND.with(|last| last.set(Some(backend)));
+}
+
+/// Backend the native dispatcher picks for a routine, given the active backend.
+///
+/// The active backend reported by the native layer is the first enabled one in the order
+/// Accelerate, AVX2, NEON, which is also the order routines are dispatched in. It only
+/// differs from the executed one when Accelerate is active and the routine has no
+/// Accelerate implementation: by default the dispatcher then falls through to AVX2 or NEON,
+/// but after an explicit [`Convert::set_backend`] the other SIMD backends are disabled and
+/// the CPU code runs.
+fn executed_backend(
+    active: ColorConversionBackend,
+    accelerate_path: bool,
+    backend_chosen: bool,
+    has_avx2: bool,
+    has_neon: bool,
+) -> ColorConversionBackend {
+    if active != ColorConversionBackend::Accelerate || accelerate_path {
+        active
+    } else if backend_chosen {
+        ColorConversionBackend::Cpu
+    } else if has_avx2 {
+        ColorConversionBackend::Avx2
+    } else if has_neon {
+        ColorConversionBackend::Neon
+    } else {
+        ColorConversionBackend::Cpu
+    }
+}
+
 /// Validate that the input buffer has sufficient size
 fn validate_buffer_size(data: &[u8], required: usize, name: &str) -> Result<()> {
     if data.len() < required {
@@ -19,7 +74,212 @@ fn validate_buffer_size(data: &[u8], required: usize, name: &str) -> Result<()>
     Ok(())
 }
 
+/// Validate a destination buffer written with `dst_stride` bytes per row
+fn validate_destination(
+    dst_data: &[u8],
+    dst_stride: usize,
+    row_bytes: usize,
+    height: u32,
+) -> Result<()> {
+    if dst_stride < row_bytes {
+        return Err(CcapError::InvalidParameter(format!(
+            "destination stride {} is smaller than row size {}",
+            dst_stride, row_bytes
+        )));
+    }
+    let required = match height as usize {
+        0 => Some(0),
+        rows => dst_stride
+            .checked_mul(rows - 1)
+            .and_then(|bytes| bytes.checked_add(row_bytes)),
+    }
+    .ok_or_else(|| size_overflow("destination"))?;
+    validate_buffer_size(dst_data, required, "destination")
+}
+
+/// Error for a buffer whose required size does not fit in `usize`
+fn size_overflow(name: &str) -> CcapError {
+    CcapError::InvalidParameter(format!("{} size overflows usize", name))
+}
+
+/// Bytes in a tightly-packed row of `width` pixels of `bytes_per_pixel` bytes each
+fn packed_row_bytes(width: u32, bytes_per_pixel: usize) -> Result<usize> {
+    (width as usize)
+        .checked_mul(bytes_per_pixel)
+        .ok_or_else(|| size_overflow("row"))
+}
+
+/// Bytes in one row of `plane` of a `format` image, rejecting sizes that overflow `usize`
+fn plane_row_bytes(format: PixelFormat, width: u32, plane: usize) -> Result<usize> {
+    format
+        .checked_plane_row_bytes(width, plane)
+        .ok_or_else(|| size_overflow("row"))
+}
+
+/// Sample layout of a pixel format, used to reason about conversion fidelity
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SampleLayout {
+    Rgb {
+        alpha: bool,
+    },
+    /// `chroma_samples` is the number of chroma samples per 4 pixels (1 = 4:2:0, 2 = 4:2:2)
+    Yuv {
+        chroma_samples: u8,
+        full_range: bool,
+    },
+}
+
+fn sample_layout(format: PixelFormat) -> Option<SampleLayout> {
+    use PixelFormat::*;
+    match format {
+        Unknown => None,
+        Rgb24 | Bgr24 => Some(SampleLayout::Rgb { alpha: false }),
+        Rgba32 | Bgra32 => Some(SampleLayout::Rgb { alpha: true }),
+        Nv12 | I420 | Nv12F | I420F => Some(SampleLayout::Yuv {
+            chroma_samples: 1,
+            full_range: matches!(format, Nv12F | I420F),
+        }),
+        Yuyv | Uyvy | YuyvF | UyvyF => Some(SampleLayout::Yuv {
+            chroma_samples: 2,
+            full_range: matches!(format, YuyvF | UyvyF),
+        }),
+    }
+}
+
+/// Validate a source plane of `rows` rows holding `row_bytes` bytes each at `stride`
+fn validate_planar_source(
+    data: &[u8],
+    stride: usize,
+    row_bytes: usize,
+    rows: usize,
+    name: &str,
+) -> Result<()> {
+    if stride < row_bytes {
+        return Err(CcapError::InvalidParameter(format!(
+            "{} stride {} is smaller than row size {}",
+            name, stride, row_bytes
+        )));
+    }
+    let required = stride
+        .checked_mul(rows)
+        .ok_or_else(|| size_overflow(name))?;
+    validate_buffer_size(data, required, name)
+}
+
+/// Validate plane `plane` of a `format` source image of `width` x `height` pixels
+fn validate_source_plane(
+    format: PixelFormat,
+    plane: usize,
+    data: &[u8],
+    stride: usize,
+    width: u32,
+    height: u32,
+    name: &str,
+) -> Result<()> {
+    validate_planar_source(
+        data,
+        stride,
+        plane_row_bytes(format, width, plane)?,
+        format.plane_rows(height, plane),
+        name,
+    )
+}
+
+/// Copy `rows` rows of `row_bytes` bytes between strided buffers
+fn copy_plane(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    row_bytes: usize,
+    rows: usize,
+) {
+    for row in 0..rows {
+        dst[row * dst_stride..][..row_bytes].copy_from_slice(&src[row * src_stride..][..row_bytes]);
+    }
+}
+
+/// Area-average a strided plane of `channels`-byte pixels down to `dst_width` x `dst_height`
+///
+/// Each destination pixel is the rounded mean of the source pixels its box covers.
+pub(crate) fn area_downscale_plane(
+    src: &[u8],
+    stride: usize,
+    (width, height): (usize, usize),
+    channels: usize,
+    (dst_width, dst_height): (usize, usize),
+) -> Vec<u8> {
+    // Source span [start, end) covered by destination index `i`; never empty.
+    let span = |i: usize, src_len: usize, dst_len: usize| {
+        let start = i * src_len / dst_len;
+        let end = ((i + 1) * src_len / dst_len).max(start + 1);
+        start..end
+    };
+    let mut dst = Vec::with_capacity(dst_width * dst_height * channels);
+    for dy in 0..dst_height {
+        let rows = span(dy, height, dst_height);
+        for dx in 0..dst_width {
+            let cols = span(dx, width, dst_width);
+            let count = (rows.len() * cols.len()) as u32;
+            for channel in 0..channels {
+                let sum: u32 = rows
+                    .clone()
+                    .flat_map(|row| {
+                        cols.clone()
+                            .map(move |col| src[row * stride + col * channels + channel] as u32)
+                    })
+                    .sum();
+                dst.push(((sum + count / 2) / count) as u8);
+            }
+        }
+    }
+    dst
+}
+
+/// Validate a strided 4-byte-per-pixel buffer and apply `f` to every pixel
+fn for_each_rgba_pixel(
+    data: &mut [u8],
+    stride: usize,
+    width: u32,
+    height: u32,
+    mut f: impl FnMut(&mut [u8]),
+) -> Result<()> {
+    let row_bytes = packed_row_bytes(width, 4)?;
+    validate_destination(data, stride, row_bytes, height)?;
+    for row in 0..height as usize {
+        data[row * stride..][..row_bytes]
+            .chunks_exact_mut(4)
+            .for_each(&mut f);
+    }
+    Ok(())
+}
+
 impl Convert {
+    /// Check whether converting `from` into `to` can lose information.
+    ///
+    /// YUV <-> RGB conversions are lossy (colorspace math and rounding), as are
+    /// chroma downsampling (e.g. YUYV -> NV12), range changes (video <-> full range)
+    /// and dropping an alpha channel. Pure reorders such as RGB <-> BGR, RGBA <-> BGRA
+    /// or NV12 <-> I420 are lossless. Unknown formats are reported as lossy.
+    pub fn is_lossy(from: PixelFormat, to: PixelFormat) -> bool {
+        match (sample_layout(from), sample_layout(to)) {
+            (Some(SampleLayout::Rgb { alpha: from_alpha }), Some(SampleLayout::Rgb { alpha })) => {
+                from_alpha && !alpha
+            }
+            (
+                Some(SampleLayout::Yuv {
+                    chroma_samples: from_chroma,
+                    full_range: from_range,
+                }),
+                Some(SampleLayout::Yuv {
+                    chroma_samples,
+                    full_range,
+                }),
+            ) => from_chroma > chroma_samples || from_range != full_range,
+            _ => true,
+        }
+    }
+
     /// Get current color conversion backend
     pub fn backend() -> ColorConversionBackend {
         let backend = unsafe { sys::ccap_convert_get_backend() };
@@ -28,6 +288,8 @@ impl Convert {
 
     /// Set color conversion backend
     pub fn set_backend(backend: ColorConversionBackend) -> Result<()> {
+        // The native layer disables the other backends even when this fails.
+        BACKEND_CHOSEN.store(true, Ordering::Relaxed);
         let success = unsafe { sys::ccap_convert_set_backend(backend.to_c_enum()) };
 
         if success {
@@ -37,6 +299,103 @@ impl Convert {
         }
     }
 
+    /// Re-enable every available backend, undoing [`Convert::set_backend`]
+    pub(crate) fn enable_all_backends() -> Result<()> {
+        let success = unsafe {
+            sys::ccap_convert_set_backend(sys::CcapConvertBackend_CCAP_CONVERT_BACKEND_AUTO)
+        };
+
+        if success {
+            BACKEND_CHOSEN.store(false, Ordering::Relaxed);
+            Ok(())
+        } else {
+            Err(CcapError::BackendSetFailed)
+        }
+    }
+
+    /// Backend that executed the most recent native color conversion on this thread.
+    ///
+    /// [`Convert::backend`] only tells which backend is enabled; not every conversion has a
+    /// routine for it. YUYV and UYVY have no Apple Accelerate implementation, for instance,
+    /// so with Accelerate selected they run on NEON, AVX2 or the CPU. Returns `None` if this
+    /// thread hasn't converted anything yet. Conversions implemented in Rust, such as
+    /// [`Convert::i420_to_nv12`], don't update it.
+    pub fn last_conversion_backend() -> Option<ColorConversionBackend> {
+        LAST_CONVERSION_BACKEND.with(Cell::get)
+    }
+
+    /// Set color conversion backend, falling back to the best available one.
+    ///
+    /// Unlike [`set_backend`](Self::set_backend), requesting a backend this machine can't run
+    /// (e.g. AVX2 on an older x86 CPU, or NEON on x86) is not an error: the fastest available
+    /// backend is selected instead (Accelerate, then NEON, then AVX2, then CPU).
+    ///
+    /// Returns the backend actually in use; compare it with `backend` to detect a
+    /// substitution.
+    pub fn set_backend_or_fallback(backend: ColorConversionBackend) -> ColorConversionBackend {
+        if Self::set_backend(backend).is_ok() {
+            return Self::backend();
+        }
+
+        let mut fallback = Self::best_available_backend();
+        if Self::set_backend(fallback).is_err() {
+            // The CPU backend is always available
+            fallback = ColorConversionBackend::Cpu;
+            let _ = Self::set_backend(fallback);
+        }
+        Self::backend()
+    }
+
+    /// Fastest backend supported on this machine: Accelerate, then NEON, then AVX2, then CPU
+    pub fn best_available_backend() -> ColorConversionBackend {
+        [
+            ColorConversionBackend::Accelerate,
+            ColorConversionBackend::Neon,
+            ColorConversionBackend::Avx2,
+        ]
+        .into_iter()
+        .find(|&backend| Self::is_backend_available(backend))
+        .unwrap_or(ColorConversionBackend::Cpu)
+    }
+
+    /// Enable every SIMD backend this CPU supports, once per process.
+    ///
+    /// The first call detects the CPU features and lets the native dispatcher use all of
+    /// them, so each conversion runs on the fastest backend that has a routine for it (e.g.
+    /// YUYV on NEON while Accelerate handles NV12 on Apple). Selecting a single backend,
+    /// even [`Convert::best_available_backend`], would disable the others. Later calls do
+    /// nothing, so this is safe to call from every entry point of an application and from
+    /// multiple threads. A backend chosen explicitly with [`Convert::set_backend`]
+    /// afterwards is left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::BackendSetFailed` if the native library rejected the request.
+    pub fn auto_init() -> Result<()> {
+        static INIT: Once = Once::new();
+        static INIT_OK: AtomicBool = AtomicBool::new(false);
+
+        INIT.call_once(|| {
+            let ok = Self::enable_all_backends().is_ok();
+            INIT_OK.store(ok, Ordering::Release);
+        });
+        if INIT_OK.load(Ordering::Acquire) {
+            Ok(())
+        } else {
+            Err(CcapError::BackendSetFailed)
+        }
+    }
+
+    /// Whether `backend` is supported on this machine
+    fn is_backend_available(backend: ColorConversionBackend) -> bool {
+        match backend {
+            ColorConversionBackend::Cpu => true,
+            ColorConversionBackend::Avx2 => Self::has_avx2(),
+            ColorConversionBackend::Neon => Self::has_neon(),
+            ColorConversionBackend::Accelerate => Self::has_apple_accelerate(),
+        }
+    }
+
     /// Check if AVX2 is available
     pub fn has_avx2() -> bool {
         unsafe { sys::ccap_convert_has_avx2() }
@@ -63,12 +422,94 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let required = src_stride * height as usize;
-        validate_buffer_size(src_data, required, "YUYV source")?;
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Rgb24.checked_buffer_size(width, height)?];
+        Self::yuyv_to_rgb24_into(
+            src_data,
+            src_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
 
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+    /// Convert YUYV to RGB24 into a caller-provided buffer, assuming limited-range input
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched, so `dst_stride` can match e.g. a GPU row alignment.
+    ///
+    /// See [`Convert::yuyv_to_rgb24_into_with_range`] to choose the input [`ColorRange`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or either buffer is
+    /// too small for the given dimensions.
+    pub fn yuyv_to_rgb24_into(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        Self::yuyv_to_rgb24_into_with_range(
+            src_data,
+            src_stride,
+            dst_data,
+            dst_stride,
+            width,
+            height,
+            ColorRange::Limited,
+        )
+    }
+
+    /// Convert YUYV to RGB24 into a caller-provided buffer, interpreting the input as `range`
+    ///
+    /// Same as [`Convert::yuyv_to_rgb24_into`] with an explicit input range, e.g.
+    /// [`ColorRange::Full`] for [`PixelFormat::YuyvF`] frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or either buffer is
+    /// too small for the given dimensions.
+    pub fn yuyv_to_rgb24_into_with_range(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+    ) -> Result<()> {
+        Self::yuyv_to_rgb24_into_with_range_oriented(
+            src_data, src_stride, dst_data, dst_stride, width, height, range, false,
+        )
+    }
+
+    /// [`Convert::yuyv_to_rgb24_into_with_range`] writing the rows bottom-up when `flip` is set
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn yuyv_to_rgb24_into_with_range_oriented(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+        flip: bool,
+    ) -> Result<()> {
+        validate_source_plane(
+            PixelFormat::Yuyv,
+            0,
+            src_data,
+            src_stride,
+            width,
+            height,
+            "YUYV source",
+        )?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_yuyv_to_rgb24(
@@ -77,12 +518,17 @@ impl Convert {
                 dst_data.as_mut_ptr(),
                 dst_stride as c_int,
                 width as c_int,
-                height as c_int,
-                sys::CcapConvertFlag_CCAP_CONVERT_FLAG_DEFAULT,
+                if flip {
+                    -(height as c_int)
+                } else {
+                    height as c_int
+                },
+                range.to_convert_flag(),
             )
         };
+        record_conversion(false);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert YUYV to BGR24
@@ -96,12 +542,77 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let required = src_stride * height as usize;
-        validate_buffer_size(src_data, required, "YUYV source")?;
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Bgr24.checked_buffer_size(width, height)?];
+        Self::yuyv_to_bgr24_into(
+            src_data,
+            src_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
+
+    /// Convert YUYV to BGR24 into a caller-provided buffer, assuming limited-range input
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched, so `dst_stride` can match e.g. a GPU row alignment.
+    ///
+    /// See [`Convert::yuyv_to_bgr24_into_with_range`] to choose the input [`ColorRange`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or either buffer is
+    /// too small for the given dimensions.
+    pub fn yuyv_to_bgr24_into(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        Self::yuyv_to_bgr24_into_with_range(
+            src_data,
+            src_stride,
+            dst_data,
+            dst_stride,
+            width,
+            height,
+            ColorRange::Limited,
+        )
+    }
 
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+    /// Convert YUYV to BGR24 into a caller-provided buffer, interpreting the input as `range`
+    ///
+    /// Same as [`Convert::yuyv_to_bgr24_into`] with an explicit input range, e.g.
+    /// [`ColorRange::Full`] for [`PixelFormat::YuyvF`] frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or either buffer is
+    /// too small for the given dimensions.
+    pub fn yuyv_to_bgr24_into_with_range(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+    ) -> Result<()> {
+        validate_source_plane(
+            PixelFormat::Yuyv,
+            0,
+            src_data,
+            src_stride,
+            width,
+            height,
+            "YUYV source",
+        )?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_yuyv_to_bgr24(
@@ -111,11 +622,12 @@ impl Convert {
                 dst_stride as c_int,
                 width as c_int,
                 height as c_int,
-                sys::CcapConvertFlag_CCAP_CONVERT_FLAG_DEFAULT,
+                range.to_convert_flag(),
             )
         };
+        record_conversion(false);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert RGB to BGR
@@ -129,12 +641,46 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let required = src_stride * height as usize;
-        validate_buffer_size(src_data, required, "RGB source")?;
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Bgr24.checked_buffer_size(width, height)?];
+        Self::rgb_to_bgr_into(
+            src_data,
+            src_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
 
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+    /// Convert RGB to BGR into a caller-provided buffer
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched, so `dst_stride` can match e.g. a GPU row alignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or either buffer is
+    /// too small for the given dimensions.
+    pub fn rgb_to_bgr_into(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        validate_source_plane(
+            PixelFormat::Rgb24,
+            0,
+            src_data,
+            src_stride,
+            width,
+            height,
+            "RGB source",
+        )?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_rgb_to_bgr(
@@ -146,8 +692,9 @@ impl Convert {
                 height as c_int,
             )
         };
+        record_conversion(true);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert BGR to RGB
@@ -161,12 +708,46 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let required = src_stride * height as usize;
-        validate_buffer_size(src_data, required, "BGR source")?;
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Rgb24.checked_buffer_size(width, height)?];
+        Self::bgr_to_rgb_into(
+            src_data,
+            src_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
 
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+    /// Convert BGR to RGB into a caller-provided buffer
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched, so `dst_stride` can match e.g. a GPU row alignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or either buffer is
+    /// too small for the given dimensions.
+    pub fn bgr_to_rgb_into(
+        src_data: &[u8],
+        src_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        validate_source_plane(
+            PixelFormat::Bgr24,
+            0,
+            src_data,
+            src_stride,
+            width,
+            height,
+            "BGR source",
+        )?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_bgr_to_rgb(
@@ -178,8 +759,9 @@ impl Convert {
                 height as c_int,
             )
         };
+        record_conversion(true);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert NV12 to RGB24
@@ -195,14 +777,108 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let y_required = y_stride * height as usize;
-        let uv_required = uv_stride * ((height as usize + 1) / 2);
-        validate_buffer_size(y_data, y_required, "NV12 Y plane")?;
-        validate_buffer_size(uv_data, uv_required, "NV12 UV plane")?;
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Rgb24.checked_buffer_size(width, height)?];
+        Self::nv12_to_rgb24_into(
+            y_data,
+            y_stride,
+            uv_data,
+            uv_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
+
+    /// Convert NV12 to RGB24 into a caller-provided buffer, assuming limited-range input
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched.
+    ///
+    /// See [`Convert::nv12_to_rgb24_into_with_range`] to choose the input [`ColorRange`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv12_to_rgb24_into(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        Self::nv12_to_rgb24_into_with_range(
+            y_data,
+            y_stride,
+            uv_data,
+            uv_stride,
+            dst_data,
+            dst_stride,
+            width,
+            height,
+            ColorRange::Limited,
+        )
+    }
 
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+    /// Convert NV12 to RGB24 into a caller-provided buffer, interpreting the input as `range`
+    ///
+    /// Same as [`Convert::nv12_to_rgb24_into`] with an explicit input range, e.g.
+    /// [`ColorRange::Full`] for [`PixelFormat::Nv12F`] frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv12_to_rgb24_into_with_range(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+    ) -> Result<()> {
+        Self::nv12_to_rgb24_into_with_range_oriented(
+            y_data, y_stride, uv_data, uv_stride, dst_data, dst_stride, width, height, range, false,
+        )
+    }
+
+    /// [`Convert::nv12_to_rgb24_into_with_range`] writing the rows bottom-up when `flip` is set
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn nv12_to_rgb24_into_with_range_oriented(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+        flip: bool,
+    ) -> Result<()> {
+        let format = PixelFormat::Nv12;
+        validate_source_plane(format, 0, y_data, y_stride, width, height, "NV12 Y plane")?;
+        validate_source_plane(
+            format,
+            1,
+            uv_data,
+            uv_stride,
+            width,
+            height,
+            "NV12 UV plane",
+        )?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_nv12_to_rgb24(
@@ -213,12 +889,17 @@ impl Convert {
                 dst_data.as_mut_ptr(),
                 dst_stride as c_int,
                 width as c_int,
-                height as c_int,
-                sys::CcapConvertFlag_CCAP_CONVERT_FLAG_DEFAULT,
+                if flip {
+                    -(height as c_int)
+                } else {
+                    height as c_int
+                },
+                range.to_convert_flag(),
             )
         };
+        record_conversion(true);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert NV12 to BGR24
@@ -234,14 +915,89 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let y_required = y_stride * height as usize;
-        let uv_required = uv_stride * ((height as usize + 1) / 2);
-        validate_buffer_size(y_data, y_required, "NV12 Y plane")?;
-        validate_buffer_size(uv_data, uv_required, "NV12 UV plane")?;
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Bgr24.checked_buffer_size(width, height)?];
+        Self::nv12_to_bgr24_into(
+            y_data,
+            y_stride,
+            uv_data,
+            uv_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
 
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+    /// Convert NV12 to BGR24 into a caller-provided buffer, assuming limited-range input
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched.
+    ///
+    /// See [`Convert::nv12_to_bgr24_into_with_range`] to choose the input [`ColorRange`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv12_to_bgr24_into(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        Self::nv12_to_bgr24_into_with_range(
+            y_data,
+            y_stride,
+            uv_data,
+            uv_stride,
+            dst_data,
+            dst_stride,
+            width,
+            height,
+            ColorRange::Limited,
+        )
+    }
+
+    /// Convert NV12 to BGR24 into a caller-provided buffer, interpreting the input as `range`
+    ///
+    /// Same as [`Convert::nv12_to_bgr24_into`] with an explicit input range, e.g.
+    /// [`ColorRange::Full`] for [`PixelFormat::Nv12F`] frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv12_to_bgr24_into_with_range(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+    ) -> Result<()> {
+        let format = PixelFormat::Nv12;
+        validate_source_plane(format, 0, y_data, y_stride, width, height, "NV12 Y plane")?;
+        validate_source_plane(
+            format,
+            1,
+            uv_data,
+            uv_stride,
+            width,
+            height,
+            "NV12 UV plane",
+        )?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_nv12_to_bgr24(
@@ -253,11 +1009,12 @@ impl Convert {
                 dst_stride as c_int,
                 width as c_int,
                 height as c_int,
-                sys::CcapConvertFlag_CCAP_CONVERT_FLAG_DEFAULT,
+                range.to_convert_flag(),
             )
         };
+        record_conversion(true);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert I420 to RGB24
@@ -276,17 +1033,112 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let y_required = y_stride * height as usize;
-        let uv_height = (height as usize + 1) / 2;
-        let u_required = u_stride * uv_height;
-        let v_required = v_stride * uv_height;
-        validate_buffer_size(y_data, y_required, "I420 Y plane")?;
-        validate_buffer_size(u_data, u_required, "I420 U plane")?;
-        validate_buffer_size(v_data, v_required, "I420 V plane")?;
-
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Rgb24.checked_buffer_size(width, height)?];
+        Self::i420_to_rgb24_into(
+            y_data,
+            y_stride,
+            u_data,
+            u_stride,
+            v_data,
+            v_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
+
+    /// Convert I420 to RGB24 into a caller-provided buffer, assuming limited-range input
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched.
+    ///
+    /// See [`Convert::i420_to_rgb24_into_with_range`] to choose the input [`ColorRange`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn i420_to_rgb24_into(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        Self::i420_to_rgb24_into_with_range(
+            y_data,
+            y_stride,
+            u_data,
+            u_stride,
+            v_data,
+            v_stride,
+            dst_data,
+            dst_stride,
+            width,
+            height,
+            ColorRange::Limited,
+        )
+    }
+
+    /// Convert I420 to RGB24 into a caller-provided buffer, interpreting the input as `range`
+    ///
+    /// Same as [`Convert::i420_to_rgb24_into`] with an explicit input range, e.g.
+    /// [`ColorRange::Full`] for [`PixelFormat::I420F`] frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn i420_to_rgb24_into_with_range(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+    ) -> Result<()> {
+        Self::i420_to_rgb24_into_with_range_oriented(
+            y_data, y_stride, u_data, u_stride, v_data, v_stride, dst_data, dst_stride, width,
+            height, range, false,
+        )
+    }
+
+    /// [`Convert::i420_to_rgb24_into_with_range`] writing the rows bottom-up when `flip` is set
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn i420_to_rgb24_into_with_range_oriented(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+        flip: bool,
+    ) -> Result<()> {
+        let format = PixelFormat::I420;
+        validate_source_plane(format, 0, y_data, y_stride, width, height, "I420 Y plane")?;
+        validate_source_plane(format, 1, u_data, u_stride, width, height, "I420 U plane")?;
+        validate_source_plane(format, 2, v_data, v_stride, width, height, "I420 V plane")?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_i420_to_rgb24(
@@ -299,12 +1151,17 @@ impl Convert {
                 dst_data.as_mut_ptr(),
                 dst_stride as c_int,
                 width as c_int,
-                height as c_int,
-                sys::CcapConvertFlag_CCAP_CONVERT_FLAG_DEFAULT,
+                if flip {
+                    -(height as c_int)
+                } else {
+                    height as c_int
+                },
+                range.to_convert_flag(),
             )
         };
+        record_conversion(true);
 
-        Ok(dst_data)
+        Ok(())
     }
 
     /// Convert I420 to BGR24
@@ -323,17 +1180,90 @@ impl Convert {
         width: u32,
         height: u32,
     ) -> Result<Vec<u8>> {
-        let y_required = y_stride * height as usize;
-        let uv_height = (height as usize + 1) / 2;
-        let u_required = u_stride * uv_height;
-        let v_required = v_stride * uv_height;
-        validate_buffer_size(y_data, y_required, "I420 Y plane")?;
-        validate_buffer_size(u_data, u_required, "I420 U plane")?;
-        validate_buffer_size(v_data, v_required, "I420 V plane")?;
-
-        let dst_stride = (width * 3) as usize;
-        let dst_size = dst_stride * height as usize;
-        let mut dst_data = vec![0u8; dst_size];
+        let dst_stride = packed_row_bytes(width, 3)?;
+        let mut dst_data = vec![0u8; PixelFormat::Bgr24.checked_buffer_size(width, height)?];
+        Self::i420_to_bgr24_into(
+            y_data,
+            y_stride,
+            u_data,
+            u_stride,
+            v_data,
+            v_stride,
+            &mut dst_data,
+            dst_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
+
+    /// Convert I420 to BGR24 into a caller-provided buffer, assuming limited-range input
+    ///
+    /// Each output row is written at `row * dst_stride`; any padding past `width * 3`
+    /// bytes is left untouched.
+    ///
+    /// See [`Convert::i420_to_bgr24_into_with_range`] to choose the input [`ColorRange`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn i420_to_bgr24_into(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        Self::i420_to_bgr24_into_with_range(
+            y_data,
+            y_stride,
+            u_data,
+            u_stride,
+            v_data,
+            v_stride,
+            dst_data,
+            dst_stride,
+            width,
+            height,
+            ColorRange::Limited,
+        )
+    }
+
+    /// Convert I420 to BGR24 into a caller-provided buffer, interpreting the input as `range`
+    ///
+    /// Same as [`Convert::i420_to_bgr24_into`] with an explicit input range, e.g.
+    /// [`ColorRange::Full`] for [`PixelFormat::I420F`] frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `dst_stride < width * 3` or any buffer is
+    /// too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn i420_to_bgr24_into_with_range(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        dst_data: &mut [u8],
+        dst_stride: usize,
+        width: u32,
+        height: u32,
+        range: ColorRange,
+    ) -> Result<()> {
+        let format = PixelFormat::I420;
+        validate_source_plane(format, 0, y_data, y_stride, width, height, "I420 Y plane")?;
+        validate_source_plane(format, 1, u_data, u_stride, width, height, "I420 U plane")?;
+        validate_source_plane(format, 2, v_data, v_stride, width, height, "I420 V plane")?;
+        validate_destination(dst_data, dst_stride, packed_row_bytes(width, 3)?, height)?;
 
         unsafe {
             sys::ccap_convert_i420_to_bgr24(
@@ -347,17 +1277,421 @@ impl Convert {
                 dst_stride as c_int,
                 width as c_int,
                 height as c_int,
-                sys::CcapConvertFlag_CCAP_CONVERT_FLAG_DEFAULT,
+                range.to_convert_flag(),
             )
         };
+        record_conversion(true);
+
+        Ok(())
+    }
 
+    /// Convert I420 to NV12 by interleaving the U and V planes
+    ///
+    /// Returns a tightly-packed NV12 buffer: the `width`-stride Y plane followed by the
+    /// interleaved UV plane. No colorspace math is involved, so the conversion is exact.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if buffers are too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn i420_to_nv12(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>> {
+        let mut dst_data = vec![0u8; PixelFormat::Nv12.checked_buffer_size(width, height)?];
+        let (dst_y, dst_uv) = dst_data.split_at_mut(width as usize * height as usize);
+        Self::i420_to_nv12_into(
+            y_data,
+            y_stride,
+            u_data,
+            u_stride,
+            v_data,
+            v_stride,
+            dst_y,
+            width as usize,
+            dst_uv,
+            plane_row_bytes(PixelFormat::Nv12, width, 1)?,
+            width,
+            height,
+        )?;
         Ok(dst_data)
     }
+
+    /// Convert I420 to NV12 into caller-provided Y and UV planes
+    ///
+    /// Rows are written at the given destination strides; padding is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if a destination stride is too small or any
+    /// buffer is too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn i420_to_nv12_into(
+        y_data: &[u8],
+        y_stride: usize,
+        u_data: &[u8],
+        u_stride: usize,
+        v_data: &[u8],
+        v_stride: usize,
+        dst_y: &mut [u8],
+        dst_y_stride: usize,
+        dst_uv: &mut [u8],
+        dst_uv_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        let format = PixelFormat::I420;
+        let (chroma_width, chroma_height) = (
+            format.plane_row_bytes(width, 1),
+            format.plane_rows(height, 1),
+        );
+        let uv_row_bytes = plane_row_bytes(PixelFormat::Nv12, width, 1)?;
+        validate_planar_source(
+            y_data,
+            y_stride,
+            width as usize,
+            height as usize,
+            "I420 Y plane",
+        )?;
+        validate_planar_source(
+            u_data,
+            u_stride,
+            chroma_width,
+            chroma_height,
+            "I420 U plane",
+        )?;
+        validate_planar_source(
+            v_data,
+            v_stride,
+            chroma_width,
+            chroma_height,
+            "I420 V plane",
+        )?;
+        validate_destination(dst_y, dst_y_stride, width as usize, height)?;
+        validate_destination(dst_uv, dst_uv_stride, uv_row_bytes, chroma_height as u32)?;
+
+        copy_plane(
+            y_data,
+            y_stride,
+            dst_y,
+            dst_y_stride,
+            width as usize,
+            height as usize,
+        );
+        for row in 0..chroma_height {
+            let u_row = &u_data[row * u_stride..][..chroma_width];
+            let v_row = &v_data[row * v_stride..][..chroma_width];
+            let uv_row = &mut dst_uv[row * dst_uv_stride..][..uv_row_bytes];
+            for ((uv, &u), &v) in uv_row.chunks_exact_mut(2).zip(u_row).zip(v_row) {
+                uv[0] = u;
+                uv[1] = v;
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert NV12 to I420 by de-interleaving the UV plane
+    ///
+    /// Returns a tightly-packed I420 buffer: Y, then U, then V. The conversion is exact.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if buffers are too small for the given dimensions.
+    pub fn nv12_to_i420(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>> {
+        let mut dst_data = vec![0u8; PixelFormat::I420.checked_buffer_size(width, height)?];
+        let sizes = PixelFormat::I420.plane_sizes(width, height);
+        let (dst_y, dst_chroma) = dst_data.split_at_mut(sizes[0]);
+        let (dst_u, dst_v) = dst_chroma.split_at_mut(sizes[1]);
+        let chroma_stride = PixelFormat::I420.plane_row_bytes(width, 1);
+        Self::nv12_to_i420_into(
+            y_data,
+            y_stride,
+            uv_data,
+            uv_stride,
+            dst_y,
+            width as usize,
+            dst_u,
+            chroma_stride,
+            dst_v,
+            chroma_stride,
+            width,
+            height,
+        )?;
+        Ok(dst_data)
+    }
+
+    /// Convert NV12 to I420 into caller-provided Y, U and V planes
+    ///
+    /// Rows are written at the given destination strides; padding is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if a destination stride is too small or any
+    /// buffer is too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv12_to_i420_into(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        dst_y: &mut [u8],
+        dst_y_stride: usize,
+        dst_u: &mut [u8],
+        dst_u_stride: usize,
+        dst_v: &mut [u8],
+        dst_v_stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        let format = PixelFormat::I420;
+        let (chroma_width, chroma_height) = (
+            format.plane_row_bytes(width, 1),
+            format.plane_rows(height, 1),
+        );
+        let uv_row_bytes = plane_row_bytes(PixelFormat::Nv12, width, 1)?;
+        validate_planar_source(
+            y_data,
+            y_stride,
+            width as usize,
+            height as usize,
+            "NV12 Y plane",
+        )?;
+        validate_planar_source(
+            uv_data,
+            uv_stride,
+            uv_row_bytes,
+            chroma_height,
+            "NV12 UV plane",
+        )?;
+        validate_destination(dst_y, dst_y_stride, width as usize, height)?;
+        validate_destination(dst_u, dst_u_stride, chroma_width, chroma_height as u32)?;
+        validate_destination(dst_v, dst_v_stride, chroma_width, chroma_height as u32)?;
+
+        copy_plane(
+            y_data,
+            y_stride,
+            dst_y,
+            dst_y_stride,
+            width as usize,
+            height as usize,
+        );
+        for row in 0..chroma_height {
+            let uv_row = &uv_data[row * uv_stride..][..uv_row_bytes];
+            let u_row = &mut dst_u[row * dst_u_stride..][..chroma_width];
+            let v_row = &mut dst_v[row * dst_v_stride..][..chroma_width];
+            for ((uv, u), v) in uv_row.chunks_exact(2).zip(u_row).zip(v_row) {
+                *u = uv[0];
+                *v = uv[1];
+            }
+        }
+        Ok(())
+    }
+
+    /// Downscale an NV12 image with an area (box) filter, staying in NV12
+    ///
+    /// The Y plane is reduced from `src_width` x `src_height` to `dst_width` x `dst_height`
+    /// and the interleaved UV plane to the matching chroma size, each destination sample
+    /// being the mean of the source samples it covers. This avoids a round trip through
+    /// RGB when a smaller preview or analysis image is all that is needed.
+    ///
+    /// Returns the tightly-packed `(y, uv)` planes; their strides are `dst_width` and
+    /// `PixelFormat::Nv12.plane_row_bytes(dst_width, 1)` respectively.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if a destination dimension is zero or larger
+    /// than the source, or if the source buffers are too small for the given dimensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv12_downscale(
+        y_data: &[u8],
+        y_stride: usize,
+        uv_data: &[u8],
+        uv_stride: usize,
+        src_width: u32,
+        src_height: u32,
+        dst_width: u32,
+        dst_height: u32,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        if dst_width == 0 || dst_height == 0 || dst_width > src_width || dst_height > src_height {
+            return Err(CcapError::InvalidParameter(format!(
+                "cannot downscale {}x{} to {}x{}",
+                src_width, src_height, dst_width, dst_height
+            )));
+        }
+        let format = PixelFormat::Nv12;
+        validate_source_plane(
+            format,
+            0,
+            y_data,
+            y_stride,
+            src_width,
+            src_height,
+            "NV12 Y plane",
+        )?;
+        validate_source_plane(
+            format,
+            1,
+            uv_data,
+            uv_stride,
+            src_width,
+            src_height,
+            "NV12 UV plane",
+        )?;
+
+        let chroma_size = |width: u32, height: u32| {
+            (
+                format.plane_row_bytes(width, 1) / 2,
+                format.plane_rows(height, 1),
+            )
+        };
+        let y = area_downscale_plane(
+            y_data,
+            y_stride,
+            (src_width as usize, src_height as usize),
+            1,
+            (dst_width as usize, dst_height as usize),
+        );
+        let uv = area_downscale_plane(
+            uv_data,
+            uv_stride,
+            chroma_size(src_width, src_height),
+            2,
+            chroma_size(dst_width, dst_height),
+        );
+        Ok((y, uv))
+    }
+
+    /// Scale the color channels of an RGBA/BGRA image by its alpha channel in place
+    ///
+    /// Converts straight alpha to [`AlphaMode::Premultiplied`](crate::AlphaMode). Opaque pixels are unchanged,
+    /// so this is a no-op for frames converted from a camera without an alpha channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `stride < width * 4` or `rgba` is too small
+    /// for the given dimensions.
+    pub fn premultiply_alpha_in_place(
+        rgba: &mut [u8],
+        stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        for_each_rgba_pixel(rgba, stride, width, height, |pixel| {
+            let alpha = pixel[3] as u32;
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+            }
+        })
+    }
+
+    /// Undo [`Convert::premultiply_alpha_in_place`], restoring straight alpha in place
+    ///
+    /// Fully transparent pixels become black, since their color can't be recovered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `stride < width * 4` or `rgba` is too small
+    /// for the given dimensions.
+    pub fn unpremultiply_alpha_in_place(
+        rgba: &mut [u8],
+        stride: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        for_each_rgba_pixel(rgba, stride, width, height, |pixel| {
+            let alpha = pixel[3] as u32;
+            for channel in &mut pixel[..3] {
+                *channel = match alpha {
+                    0 => 0,
+                    _ => ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+                };
+            }
+        })
+    }
+
+    /// Rotate an RGB24 image clockwise by `rotation`
+    ///
+    /// Returns the tightly-packed rotated image with its dimensions, which are swapped for
+    /// [`Rotation::Cw90`] and [`Rotation::Cw270`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `src_stride < width * 3` or `src_data` is too
+    /// small for the given dimensions.
+    pub fn rotate_rgb24(
+        src_data: &[u8],
+        src_stride: usize,
+        width: u32,
+        height: u32,
+        rotation: Rotation,
+    ) -> Result<(Vec<u8>, u32, u32)> {
+        let row_bytes = packed_row_bytes(width, 3)?;
+        validate_planar_source(
+            src_data,
+            src_stride,
+            row_bytes,
+            height as usize,
+            "RGB24 source",
+        )?;
+
+        let (dst_width, dst_height) = rotation.rotated_size(width, height);
+        let (w, h) = (width as usize, height as usize);
+        let dst_stride = packed_row_bytes(dst_width, 3)?;
+        let mut dst_data =
+            vec![0u8; PixelFormat::Rgb24.checked_buffer_size(dst_width, dst_height)?];
+        for y in 0..h {
+            let src_row = &src_data[y * src_stride..][..row_bytes];
+            for (x, pixel) in src_row.chunks_exact(3).enumerate() {
+                let (dst_x, dst_y) = match rotation {
+                    Rotation::None => (x, y),
+                    Rotation::Cw90 => (h - 1 - y, x),
+                    Rotation::Rot180 => (w - 1 - x, h - 1 - y),
+                    Rotation::Cw270 => (y, w - 1 - x),
+                };
+                dst_data[dst_y * dst_stride + dst_x * 3..][..3].copy_from_slice(pixel);
+            }
+        }
+        Ok((dst_data, dst_width, dst_height))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    /// Holds [`backend_lock`] and re-enables every backend when dropped
+    struct BackendGuard {
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl Drop for BackendGuard {
+        fn drop(&mut self) {
+            let _ = Convert::enable_all_backends();
+        }
+    }
+
+    /// Serializes tests that switch the process-wide conversion backend, restoring the
+    /// default all-backends state when the test ends (even if it panics)
+    fn backend_lock() -> BackendGuard {
+        static LOCK: Mutex<()> = Mutex::new(());
+        BackendGuard {
+            _lock: LOCK.lock().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
 
     #[test]
     fn test_backend_detection() {
@@ -384,6 +1718,155 @@ mod tests {
         // On macOS: Accelerate may be available
     }
 
+    #[test]
+    fn test_auto_init_keeps_all_backends_enabled() {
+        let _guard = backend_lock();
+        Convert::auto_init().unwrap();
+        assert!(!BACKEND_CHOSEN.load(Ordering::Relaxed));
+        // The native layer reports the first enabled backend in dispatch order.
+        let first_enabled = [
+            ColorConversionBackend::Accelerate,
+            ColorConversionBackend::Avx2,
+            ColorConversionBackend::Neon,
+        ]
+        .into_iter()
+        .find(|&backend| Convert::is_backend_available(backend))
+        .unwrap_or(ColorConversionBackend::Cpu);
+        assert_eq!(Convert::backend(), first_enabled);
+        // Idempotent
+        Convert::auto_init().unwrap();
+    }
+
+    #[test]
+    fn test_last_conversion_backend_reports_executing_backend() {
+        let _guard = backend_lock();
+
+        // NV12 to RGB24 has an AVX2 routine; a failed switch leaves only the CPU enabled.
+        let expected = if Convert::set_backend(ColorConversionBackend::Avx2).is_ok() {
+            ColorConversionBackend::Avx2
+        } else {
+            ColorConversionBackend::Cpu
+        };
+        let (width, height) = (16u32, 4u32);
+        let y = vec![128u8; (width * height) as usize];
+        let uv = vec![128u8; (width * height / 2) as usize];
+        Convert::nv12_to_rgb24(&y, width as usize, &uv, width as usize, width, height).unwrap();
+        assert_eq!(Convert::last_conversion_backend(), Some(expected));
+
+        // Another thread's conversions are tracked separately.
+        let other = std::thread::spawn(Convert::last_conversion_backend)
+            .join()
+            .unwrap();
+        assert_eq!(other, None);
+    }
+
+    #[test]
+    fn test_executed_backend_for_routines_without_accelerate() {
+        use ColorConversionBackend::*;
+
+        assert_eq!(executed_backend(Avx2, false, true, true, false), Avx2);
+        assert_eq!(
+            executed_backend(Accelerate, true, true, false, true),
+            Accelerate
+        );
+        // Default state: YUYV falls through to the next enabled SIMD backend.
+        assert_eq!(
+            executed_backend(Accelerate, false, false, false, true),
+            Neon
+        );
+        assert_eq!(
+            executed_backend(Accelerate, false, false, true, false),
+            Avx2
+        );
+        // Accelerate chosen explicitly disables NEON, leaving the CPU code.
+        assert_eq!(executed_backend(Accelerate, false, true, false, true), Cpu);
+    }
+
+    #[test]
+    fn test_set_backend_or_fallback_picks_available_backend() {
+        let _guard = backend_lock();
+        let unavailable = [
+            ColorConversionBackend::Avx2,
+            ColorConversionBackend::Neon,
+            ColorConversionBackend::Accelerate,
+        ]
+        .into_iter()
+        .find(|&backend| !Convert::is_backend_available(backend));
+        let Some(unavailable) = unavailable else {
+            return;
+        };
+
+        let selected = Convert::set_backend_or_fallback(unavailable);
+        assert_ne!(selected, unavailable);
+        assert!(Convert::is_backend_available(selected));
+    }
+
+    #[test]
+    fn test_short_source_slices_are_rejected() {
+        fn assert_invalid<T: std::fmt::Debug>(result: Result<T>) {
+            match result {
+                Err(CcapError::InvalidParameter(msg)) => {
+                    assert!(msg.contains("too small"), "unexpected message: {}", msg)
+                }
+                other => panic!("Expected InvalidParameter error, got {:?}", other),
+            }
+        }
+
+        let (w, h) = (4u32, 4u32);
+        let short = [0u8; 7];
+        let y = [0u8; 16];
+        let uv = [0u8; 8];
+        let chroma = [0u8; 4];
+
+        assert_invalid(Convert::yuyv_to_rgb24(&short, 8, w, h));
+        assert_invalid(Convert::yuyv_to_bgr24(&short, 8, w, h));
+        assert_invalid(Convert::rgb_to_bgr(&short, 12, w, h));
+        assert_invalid(Convert::bgr_to_rgb(&short, 12, w, h));
+        assert_invalid(Convert::nv12_to_rgb24(&short, 4, &uv, 4, w, h));
+        assert_invalid(Convert::nv12_to_bgr24(&y, 4, &short[..3], 4, w, h));
+        assert_invalid(Convert::nv12_to_i420(&y, 4, &short[..3], 4, w, h));
+        assert_invalid(Convert::i420_to_rgb24(
+            &short, 4, &chroma, 2, &chroma, 2, w, h,
+        ));
+        assert_invalid(Convert::i420_to_bgr24(
+            &y,
+            4,
+            &chroma[..3],
+            2,
+            &chroma,
+            2,
+            w,
+            h,
+        ));
+        assert_invalid(Convert::i420_to_nv12(
+            &y,
+            4,
+            &chroma,
+            2,
+            &chroma[..3],
+            2,
+            w,
+            h,
+        ));
+        assert_invalid(Convert::rotate_rgb24(&short, 12, w, h, Rotation::Cw90));
+        assert_invalid(Convert::premultiply_alpha_in_place(&mut [0u8; 7], 16, w, h));
+        assert_invalid(Convert::unpremultiply_alpha_in_place(
+            &mut [0u8; 7],
+            16,
+            w,
+            h,
+        ));
+    }
+
+    #[test]
+    fn test_source_stride_smaller_than_row_is_rejected() {
+        // A zero stride would make the native routine read whole rows past the slice.
+        let result = Convert::yuyv_to_rgb24(&[], 0, 4, 4);
+        assert!(matches!(result, Err(CcapError::InvalidParameter(msg)) if msg.contains("stride")));
+        let result = Convert::nv12_to_rgb24(&[0u8; 16], 4, &[0u8; 2], 1, 4, 4);
+        assert!(matches!(result, Err(CcapError::InvalidParameter(msg)) if msg.contains("stride")));
+    }
+
     #[test]
     fn test_rgb_bgr_conversion() {
         let width = 4u32;
@@ -597,4 +2080,191 @@ mod tests {
         let result = Convert::nv12_to_rgb24(&y_data, y_stride, &small_uv, uv_stride, width, height);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_convert_into_padded_destination() {
+        let width = 2u32;
+        let height = 2u32;
+        let src = vec![1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let dst_stride = 16;
+        let mut dst = vec![0xAAu8; dst_stride * height as usize];
+
+        Convert::rgb_to_bgr_into(&src, 6, &mut dst, dst_stride, width, height).unwrap();
+
+        assert_eq!(&dst[..6], &[3, 2, 1, 6, 5, 4]);
+        assert_eq!(&dst[dst_stride..dst_stride + 6], &[9, 8, 7, 12, 11, 10]);
+        // Row padding must be left untouched
+        assert!(dst[6..dst_stride].iter().all(|&b| b == 0xAA));
+        assert!(dst[dst_stride + 6..].iter().all(|&b| b == 0xAA));
+    }
+
+    #[test]
+    fn test_convert_into_rejects_short_stride() {
+        let src = vec![0u8; 12];
+        let mut dst = vec![0u8; 12];
+        let result = Convert::rgb_to_bgr_into(&src, 6, &mut dst, 4, 2, 2);
+        assert!(matches!(result, Err(CcapError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_is_lossy() {
+        use PixelFormat::*;
+
+        // Pure channel/plane reorders
+        assert!(!Convert::is_lossy(Rgb24, Bgr24));
+        assert!(!Convert::is_lossy(Bgra32, Rgba32));
+        assert!(!Convert::is_lossy(Rgb24, Rgba32));
+        assert!(!Convert::is_lossy(Nv12, I420));
+        assert!(!Convert::is_lossy(Nv12, Yuyv));
+
+        // Colorspace math, subsampling, range and alpha loss
+        assert!(Convert::is_lossy(Nv12, Rgb24));
+        assert!(Convert::is_lossy(Bgr24, I420));
+        assert!(Convert::is_lossy(Yuyv, Nv12));
+        assert!(Convert::is_lossy(Nv12, Nv12F));
+        assert!(Convert::is_lossy(Rgba32, Rgb24));
+        assert!(Convert::is_lossy(Unknown, Unknown));
+    }
+
+    #[test]
+    fn test_i420_nv12_round_trip() {
+        let (width, height) = (4u32, 2u32);
+        let y: Vec<u8> = (0..8).collect();
+        let u = vec![100u8, 101];
+        let v = vec![200u8, 201];
+
+        let nv12 = Convert::i420_to_nv12(&y, 4, &u, 2, &v, 2, width, height).unwrap();
+        assert_eq!(nv12.len(), 12);
+        assert_eq!(&nv12[8..], &[100, 200, 101, 201]);
+
+        let i420 = Convert::nv12_to_i420(&nv12[..8], 4, &nv12[8..], 4, width, height).unwrap();
+        assert_eq!(&i420[..8], &y[..]);
+        assert_eq!(&i420[8..10], &u[..]);
+        assert_eq!(&i420[10..], &v[..]);
+    }
+
+    #[test]
+    fn test_nv12_to_i420_into_strided_output() {
+        let y = vec![7u8; 4];
+        let uv = vec![1u8, 2];
+        let mut dst_y = vec![0xAAu8; 8];
+        let mut dst_u = vec![0xAAu8; 4];
+        let mut dst_v = vec![0xAAu8; 4];
+
+        Convert::nv12_to_i420_into(
+            &y, 2, &uv, 2, &mut dst_y, 4, &mut dst_u, 4, &mut dst_v, 4, 2, 2,
+        )
+        .unwrap();
+        assert_eq!(dst_y, vec![7, 7, 0xAA, 0xAA, 7, 7, 0xAA, 0xAA]);
+        assert_eq!(dst_u, vec![1, 0xAA, 0xAA, 0xAA]);
+        assert_eq!(dst_v, vec![2, 0xAA, 0xAA, 0xAA]);
+    }
+
+    #[test]
+    fn test_oversized_dimensions_are_rejected() {
+        let is_invalid =
+            |result: Result<Vec<u8>>| matches!(result, Err(CcapError::InvalidParameter(_)));
+        assert!(is_invalid(Convert::yuyv_to_rgb24(
+            &[],
+            0,
+            u32::MAX,
+            u32::MAX
+        )));
+        assert!(is_invalid(Convert::nv12_to_bgr24(
+            &[],
+            0,
+            &[],
+            0,
+            u32::MAX,
+            u32::MAX
+        )));
+        assert!(is_invalid(Convert::i420_to_nv12(
+            &[],
+            0,
+            &[],
+            0,
+            &[],
+            0,
+            u32::MAX,
+            u32::MAX
+        )));
+
+        // A destination stride whose row offsets overflow is rejected, not wrapped.
+        let mut dst = [0u8; 9];
+        let result = Convert::rgb_to_bgr_into(&[0u8; 9], 3, &mut dst, usize::MAX, 1, 3);
+        assert!(matches!(result, Err(CcapError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_nv12_downscale_averages_planes() {
+        // 4x4 Y plane with a distinct value per 2x2 block; 2x2 UV plane.
+        let y = [
+            10u8, 20, 100, 100, //
+            30, 40, 100, 100, //
+            0, 0, 255, 255, //
+            0, 0, 255, 251, //
+        ];
+        let uv = [10u8, 20, 30, 40, 50, 60, 70, 80];
+
+        let (dst_y, dst_uv) = Convert::nv12_downscale(&y, 4, &uv, 4, 4, 4, 2, 2).unwrap();
+        assert_eq!(dst_y, vec![25, 100, 0, 254]);
+        assert_eq!(dst_uv, vec![40, 50]);
+        let sizes = PixelFormat::Nv12.plane_sizes(2, 2);
+        assert_eq!((dst_y.len(), dst_uv.len()), (sizes[0], sizes[1]));
+
+        assert!(Convert::nv12_downscale(&y, 4, &uv, 4, 4, 4, 8, 2).is_err());
+        assert!(Convert::nv12_downscale(&y, 4, &uv, 4, 4, 4, 0, 2).is_err());
+        assert!(Convert::nv12_downscale(&y[..8], 4, &uv, 4, 4, 4, 2, 2).is_err());
+    }
+
+    #[test]
+    fn test_color_range_changes_decoded_black_level() {
+        // Y=16 is black in limited range but dark gray in full range.
+        let (y, u, v) = ([16u8; 4], [128u8], [128u8]);
+        let decode = |range| {
+            let mut rgb = [0u8; 12];
+            Convert::i420_to_rgb24_into_with_range(&y, 2, &u, 1, &v, 1, &mut rgb, 6, 2, 2, range)
+                .unwrap();
+            rgb
+        };
+
+        let limited = decode(ColorRange::Limited);
+        let full = decode(ColorRange::Full);
+        assert!(limited.iter().all(|&c| c <= 1), "{:?}", limited);
+        assert!(full.iter().all(|&c| (15..=17).contains(&c)), "{:?}", full);
+        assert_eq!(PixelFormat::I420F.color_range(), Some(ColorRange::Full));
+        assert_eq!(PixelFormat::Rgb24.color_range(), None);
+    }
+
+    #[test]
+    fn test_rotate_rgb24_corners() {
+        // 3x2 image with one stride padding byte per row; pixel value = index + 1
+        let src = [
+            1, 1, 1, 2, 2, 2, 3, 3, 3, 0, //
+            4, 4, 4, 5, 5, 5, 6, 6, 6, 0,
+        ];
+        let corners = |rotation| {
+            let (rgb, w, h) = Convert::rotate_rgb24(&src, 10, 3, 2, rotation).unwrap();
+            let at = |x: u32, y: u32| rgb[((y * w + x) * 3) as usize];
+            (
+                (w, h),
+                [at(0, 0), at(w - 1, 0), at(0, h - 1), at(w - 1, h - 1)],
+            )
+        };
+
+        assert_eq!(corners(Rotation::None), ((3, 2), [1, 3, 4, 6]));
+        assert_eq!(corners(Rotation::Cw90), ((2, 3), [4, 1, 6, 3]));
+        assert_eq!(corners(Rotation::Rot180), ((3, 2), [6, 4, 3, 1]));
+        assert_eq!(corners(Rotation::Cw270), ((2, 3), [3, 6, 1, 4]));
+    }
+
+    #[test]
+    fn test_premultiply_alpha_half_transparent_pixel() {
+        let mut rgba = [200u8, 100, 50, 128, 10, 20, 30, 255];
+        Convert::premultiply_alpha_in_place(&mut rgba, 8, 2, 1).unwrap();
+        assert_eq!(rgba, [100, 50, 25, 128, 10, 20, 30, 255]);
+
+        Convert::unpremultiply_alpha_in_place(&mut rgba, 8, 2, 1).unwrap();
+        assert_eq!(rgba, [199, 100, 50, 128, 10, 20, 30, 255]);
+    }
 }
@@ -29,6 +29,10 @@ pub enum CcapError {
     #[error("Device not opened")]
     DeviceNotOpened,
 
+    /// Capture was not started before grabbing frames
+    #[error("Capture not started; call start_capture() before grabbing frames")]
+    CaptureNotStarted,
+
     /// Capture start failed
     #[error("Capture start failed")]
     CaptureStartFailed,
@@ -61,9 +65,11 @@ pub enum CcapError {
     #[error("String conversion error: {0}")]
     StringConversionError(String),
 
-    /// File operation failed
+    /// File operation failed, with the OS error behind it when one could be identified
+    /// (e.g. a missing directory or missing permissions rather than an invalid frame).
+    /// The OS error is only exposed through `source()`, not repeated in the message.
     #[error("File operation failed: {0}")]
-    FileOperationFailed(String),
+    FileOperationFailed(String, #[source] Option<std::io::Error>),
 
     /// Device not found (alias for NoDeviceFound for compatibility)
     #[error("Device not found")]
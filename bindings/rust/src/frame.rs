@@ -1,27 +1,38 @@
 use crate::{error::CcapError, sys, types::*};
-use std::ffi::CStr;
+use std::cell::Cell;
+use std::ffi::OsString;
 
 /// Device information structure
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceInfo {
-    /// Device name
+    /// Device name, with any bytes that aren't valid UTF-8 replaced by U+FFFD
     pub name: String,
     /// Supported pixel formats
     pub supported_pixel_formats: Vec<PixelFormat>,
     /// Supported resolutions
     pub supported_resolutions: Vec<Resolution>,
+    /// Raw name bytes from the driver; [`DeviceInfo::name_bytes`] falls back to `name` if empty.
+    /// Not serialized, so a deserialized entry only has `name`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) name_bytes: Vec<u8>,
 }
 
 impl DeviceInfo {
     /// Create DeviceInfo from C structure
     pub fn from_c_struct(info: &sys::CcapDeviceInfo) -> Result<Self, CcapError> {
-        let name_cstr = unsafe { CStr::from_ptr(info.deviceName.as_ptr()) };
-        let name = name_cstr
-            .to_str()
-            .map_err(|e| CcapError::StringConversionError(e.to_string()))?
-            .to_string();
+        // `name` replaces non-UTF-8 bytes; the raw bytes are kept so the device can still be
+        // reopened. The name is read up to the end of the array if the driver didn't
+        // NUL-terminate it.
+        let name_bytes: Vec<u8> = info
+            .deviceName
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect();
+        let name = String::from_utf8_lossy(&name_bytes).into_owned();
 
-        // Ensure we don't exceed array bounds
+        // Clamp the counts so a corrupt count from the driver can't index past the arrays
         let format_count = (info.pixelFormatCount).min(info.supportedPixelFormats.len());
         let supported_pixel_formats = info.supportedPixelFormats[..format_count]
             .iter()
@@ -38,14 +49,174 @@ impl DeviceInfo {
             name,
             supported_pixel_formats,
             supported_resolutions,
+            name_bytes,
         })
     }
+
+    /// Device name exactly as the driver reported it, including bytes that aren't UTF-8.
+    pub fn name_bytes(&self) -> &[u8] {
+        if self.name_bytes.is_empty() {
+            self.name.as_bytes()
+        } else {
+            &self.name_bytes
+        }
+    }
+
+    /// Device name as an `OsString`, for [`crate::Provider::with_device_name_os`].
+    ///
+    /// Unlike `name`, this keeps non-UTF-8 bytes on Unix so the device can be reopened by
+    /// name. Windows names are reported as UTF-8, so this matches `name` there.
+    pub fn name_os(&self) -> OsString {
+        #[cfg(windows)]
+        {
+            OsString::from(self.name.clone())
+        }
+
+        #[cfg(not(windows))]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            OsString::from_vec(self.name_bytes().to_vec())
+        }
+    }
+}
+
+/// Lookup table for the reflected IEEE CRC-32 polynomial used by [`OwnedFrame::crc32`]
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+/// Check that a successfully queried frame actually carries pixel data.
+///
+/// Some drivers occasionally deliver a frame with `sizeInBytes == 0` or a null `data[0]`.
+/// Such a frame is valid from the native layer's point of view but must not be read from.
+fn check_frame_not_empty(info: &sys::CcapVideoFrameInfo) -> crate::error::Result<()> {
+    if info.sizeInBytes == 0 || info.data[0].is_null() {
+        return Err(CcapError::InternalError("empty frame".to_string()));
+    }
+    Ok(())
+}
+
+impl DeviceInfo {
+    /// Pick the supported pixel format best suited to `use_case`.
+    ///
+    /// Walks [`FormatUseCase::preferred_formats`] and returns the first format the device
+    /// advertises in `supported_pixel_formats`, e.g. NV12 for encoding or BGRA32 for direct
+    /// display on Windows. Returns `None` if the device lists no known format.
+    pub fn best_format_for(&self, use_case: FormatUseCase) -> Option<PixelFormat> {
+        use_case
+            .preferred_formats()
+            .iter()
+            .copied()
+            .find(|format| self.supported_pixel_formats.contains(format))
+    }
+
+    /// Resolutions the device can capture in `format`.
+    ///
+    /// The C API reports formats and resolutions independently, so every advertised
+    /// resolution is valid for every supported format. Returns an empty list if the device
+    /// doesn't support `format` at all.
+    pub fn resolutions_for_format(&self, format: PixelFormat) -> Vec<Resolution> {
+        if format == PixelFormat::Unknown || !self.supported_pixel_formats.contains(&format) {
+            return Vec::new();
+        }
+        self.supported_resolutions.clone()
+    }
+
+    /// Format the device capabilities as an aligned text table.
+    ///
+    /// Lists every supported resolution with the supported pixel formats. The C API
+    /// reports formats and resolutions independently and doesn't expose per-mode frame
+    /// rates, so each row shows the full format list. Intended for bug reports.
+    pub fn capability_table(&self) -> String {
+        const RESOLUTION_HEADER: &str = "Resolution";
+
+        let formats = if self.supported_pixel_formats.is_empty() {
+            "(none)".to_string()
+        } else {
+            self.supported_pixel_formats
+                .iter()
+                .map(|format| format.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let resolutions: Vec<String> = self
+            .supported_resolutions
+            .iter()
+            .map(|resolution| format!("{}x{}", resolution.width, resolution.height))
+            .collect();
+        let width = resolutions
+            .iter()
+            .map(String::len)
+            .chain(std::iter::once(RESOLUTION_HEADER.len()))
+            .max()
+            .unwrap_or(0);
+
+        let mut table = format!("Device: {}\n", self.name);
+        table.push_str(&format!(
+            "  {:<width$} | Pixel formats\n",
+            RESOLUTION_HEADER,
+            width = width
+        ));
+        table.push_str(&format!("  {}-+-{}\n", "-".repeat(width), "-".repeat(13)));
+        if resolutions.is_empty() {
+            table.push_str(&format!(
+                "  {:<width$} | {}\n",
+                "(none)",
+                formats,
+                width = width
+            ));
+        }
+        for resolution in &resolutions {
+            table.push_str(&format!(
+                "  {:<width$} | {}\n",
+                resolution,
+                formats,
+                width = width
+            ));
+        }
+        table
+    }
+}
+
+/// Reverse the order of the `row_bytes`-sized rows of a tightly packed buffer in place
+fn flip_rows(data: &mut [u8], row_bytes: usize) {
+    if row_bytes == 0 {
+        return;
+    }
+    let rows = data.len() / row_bytes;
+    for row in 0..rows / 2 {
+        let (top, bottom) = data.split_at_mut((rows - 1 - row) * row_bytes);
+        top[row * row_bytes..][..row_bytes].swap_with_slice(&mut bottom[..row_bytes]);
+    }
 }
 
 /// Video frame wrapper
 pub struct VideoFrame {
     frame: *mut sys::CcapVideoFrame,
     owns_frame: bool, // Whether we own the frame and should release it
+    // Top-down copy made by `flip_upright`. The native buffers belong to the library's frame
+    // pool and may be shared, so they are never written to.
+    upright: Option<OwnedFrame>,
+    // Native frame info, queried on first use. A wrapper never outlives the native frame it
+    // was created for (callback frames are only lent for the duration of the call), and the
+    // info of a native frame doesn't change while it is held, so the cache needs no reset.
+    info: Cell<Option<sys::CcapVideoFrameInfo>>,
 }
 
 impl VideoFrame {
@@ -53,6 +224,8 @@ impl VideoFrame {
         VideoFrame {
             frame,
             owns_frame: true,
+            upright: None,
+            info: Cell::new(None),
         }
     }
 
@@ -61,7 +234,51 @@ impl VideoFrame {
         VideoFrame {
             frame,
             owns_frame: false,
+            upright: None,
+            info: Cell::new(None),
+        }
+    }
+
+    /// Native frame info, fetched through FFI once and cached for later calls
+    fn raw_info(&self) -> Option<sys::CcapVideoFrameInfo> {
+        if let Some(info) = self.info.get() {
+            return Some(info);
+        }
+        let mut info = sys::CcapVideoFrameInfo::default();
+        if !unsafe { sys::ccap_video_frame_get_info(self.frame, &mut info) } {
+            return None;
+        }
+        self.info.set(Some(info));
+        Some(info)
+    }
+
+    /// Frame wrapping no native frame, with `info` standing in for the native frame info
+    #[cfg(test)]
+    pub(crate) fn from_info_for_test(info: sys::CcapVideoFrameInfo) -> Self {
+        let frame = VideoFrame::from_c_ptr_ref(std::ptr::null_mut());
+        frame.info.set(Some(info));
+        frame
+    }
+
+    /// Make a bottom-to-top frame read upright, returning whether it was flipped.
+    ///
+    /// The planes are copied into a top-down [`OwnedFrame`] that [`VideoFrame::info`] and
+    /// [`VideoFrame::data`] read from afterwards; the native buffers are left untouched.
+    pub(crate) fn flip_upright(&mut self) -> bool {
+        if self.upright.is_some()
+            || self.info().map(|info| info.orientation).ok() != Some(FrameOrientation::BottomToTop)
+        {
+            return false;
+        }
+        let Ok(mut upright) = self.to_owned_frame() else {
+            return false;
+        };
+        for (plane, &stride) in upright.planes.iter_mut().zip(&upright.strides) {
+            flip_rows(plane, stride as usize);
         }
+        upright.orientation = FrameOrientation::TopToBottom;
+        self.upright = Some(upright);
+        true
     }
 
     /// Get the internal C pointer (for internal use)
@@ -76,60 +293,61 @@ impl VideoFrame {
         if frame.is_null() {
             None
         } else {
-            Some(VideoFrame {
-                frame,
-                owns_frame: true,
-            })
+            Some(VideoFrame::from_c_ptr(frame))
         }
     }
 
     /// Get frame information
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::FrameGrabFailed` if the native frame info can't be queried, and
+    /// `CcapError::InternalError("empty frame")` if the frame carries no pixel data.
     pub fn info<'a>(&'a self) -> crate::error::Result<VideoFrameInfo<'a>> {
-        let mut info = sys::CcapVideoFrameInfo::default();
+        if let Some(info) = self.raw_info() {
+            check_frame_not_empty(&info)?;
 
-        let success = unsafe { sys::ccap_video_frame_get_info(self.frame, &mut info) };
-
-        if success {
-            // Calculate proper plane sizes based on pixel format
-            // For plane 0 (Y or main): stride * height
-            // For chroma planes (UV): stride * height/2 for most formats
-            let plane0_size = (info.stride[0] as usize) * (info.height as usize);
-            let plane1_size = if info.stride[1] > 0 {
-                (info.stride[1] as usize) * ((info.height as usize + 1) / 2)
-            } else {
-                0
-            };
-            let plane2_size = if info.stride[2] > 0 {
-                (info.stride[2] as usize) * ((info.height as usize + 1) / 2)
-            } else {
-                0
+            // Each plane spans `stride * rows` bytes: the full height for the Y (or only) plane,
+            // half the height, rounded up, for the NV12/I420 chroma planes.
+            let pixel_format = PixelFormat::from(info.pixelFormat);
+            if let Some(upright) = &self.upright {
+                return Ok(VideoFrameInfo {
+                    width: upright.width,
+                    height: upright.height,
+                    pixel_format,
+                    size_in_bytes: upright.planes.iter().map(Vec::len).sum::<usize>() as u32,
+                    timestamp: info.timestamp,
+                    frame_index: info.frameIndex,
+                    orientation: FrameOrientation::TopToBottom,
+                    data_planes: [0, 1, 2].map(|index| upright.plane(index)),
+                    strides: upright.strides,
+                });
+            }
+            let plane = |index: usize| {
+                let rows = if index == 0 {
+                    info.height as usize
+                } else {
+                    pixel_format.plane_rows(info.height, index)
+                };
+                let len = (info.stride[index] as usize) * rows;
+                if info.data[index].is_null() {
+                    None
+                } else {
+                    // SAFETY: the native frame holds `stride * rows` bytes for each plane and
+                    // outlives the returned info, which borrows `self`.
+                    Some(unsafe { std::slice::from_raw_parts(info.data[index], len) })
+                }
             };
 
             Ok(VideoFrameInfo {
                 width: info.width,
                 height: info.height,
-                pixel_format: PixelFormat::from(info.pixelFormat),
+                pixel_format,
                 size_in_bytes: info.sizeInBytes,
                 timestamp: info.timestamp,
                 frame_index: info.frameIndex,
                 orientation: FrameOrientation::from(info.orientation),
-                data_planes: [
-                    if info.data[0].is_null() {
-                        None
-                    } else {
-                        Some(unsafe { std::slice::from_raw_parts(info.data[0], plane0_size) })
-                    },
-                    if info.data[1].is_null() {
-                        None
-                    } else {
-                        Some(unsafe { std::slice::from_raw_parts(info.data[1], plane1_size) })
-                    },
-                    if info.data[2].is_null() {
-                        None
-                    } else {
-                        Some(unsafe { std::slice::from_raw_parts(info.data[2], plane2_size) })
-                    },
-                ],
+                data_planes: [plane(0), plane(1), plane(2)],
                 strides: [info.stride[0], info.stride[1], info.stride[2]],
             })
         } else {
@@ -137,44 +355,176 @@ impl VideoFrame {
         }
     }
 
+    /// Copy the whole frame into one tightly-packed buffer.
+    ///
+    /// See [`VideoFrameInfo::to_contiguous`] for the layout and errors.
+    pub fn to_contiguous(&self) -> crate::error::Result<(Vec<u8>, VideoFrameInfo<'static>)> {
+        self.info()?.to_contiguous()
+    }
+
+    /// Copy each row of plane `index` into its own `Vec`, with stride padding stripped.
+    ///
+    /// See [`VideoFrameInfo::rows_vec`] for the copy cost and errors.
+    pub fn rows_vec(&self, index: usize) -> crate::error::Result<Vec<Vec<u8>>> {
+        self.info()?.rows_vec(index)
+    }
+
     /// Get all frame data as a slice
+    ///
+    /// The slice starts at the first plane and spans `size_in_bytes`; for planar formats
+    /// the other planes aren't guaranteed to follow it in memory. Use
+    /// [`VideoFrame::to_contiguous`] to get every plane in one buffer. Frames flipped by
+    /// [`crate::Provider::set_auto_orient`] return the flipped copy of the first plane.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::FrameGrabFailed` if the native frame info can't be queried, and
+    /// `CcapError::InternalError("empty frame")` if the frame carries no pixel data.
     pub fn data(&self) -> crate::error::Result<&[u8]> {
-        let mut info = sys::CcapVideoFrameInfo::default();
+        let info = self.raw_info().ok_or(CcapError::FrameGrabFailed)?;
+        check_frame_not_empty(&info)?;
+        if let Some(upright) = &self.upright {
+            return Ok(&upright.planes[0]);
+        }
 
-        let success = unsafe { sys::ccap_video_frame_get_info(self.frame, &mut info) };
+        Ok(unsafe { std::slice::from_raw_parts(info.data[0], info.sizeInBytes as usize) })
+    }
 
-        if success && !info.data[0].is_null() {
-            Ok(unsafe { std::slice::from_raw_parts(info.data[0], info.sizeInBytes as usize) })
-        } else {
-            Err(CcapError::FrameGrabFailed)
+    /// Copy the frame into a tightly-packed [`OwnedFrame`] (see [`OwnedFrameLayout::Packed`])
+    pub fn to_owned_frame(&self) -> crate::error::Result<OwnedFrame> {
+        self.to_owned_frame_with_layout(OwnedFrameLayout::Packed)
+    }
+
+    /// Copy the frame into an [`OwnedFrame`] using the given plane layout
+    pub fn to_owned_frame_with_layout(
+        &self,
+        layout: OwnedFrameLayout,
+    ) -> crate::error::Result<OwnedFrame> {
+        let info = self.info()?;
+        let num_planes = info.pixel_format.num_planes();
+
+        let mut planes = Vec::with_capacity(num_planes);
+        for index in 0..num_planes {
+            planes.push(info.require_plane(index)?.data);
+        }
+
+        Ok(OwnedFrame::copy_from_planes(
+            info.width,
+            info.height,
+            info.pixel_format,
+            &planes,
+            &info.strides[..num_planes],
+            layout,
+        )?
+        .with_timestamp(info.timestamp)
+        .with_frame_index(info.frame_index)
+        .with_orientation(info.orientation))
+    }
+
+    /// Render a small ASCII-art preview of the frame, `cols` characters wide.
+    ///
+    /// Handy for checking that a camera produces sensible images over SSH.
+    /// See [`OwnedFrame::ascii_preview`].
+    pub fn ascii_preview(&self, cols: usize) -> crate::error::Result<String> {
+        self.to_owned_frame()?.ascii_preview(cols)
+    }
+
+    /// Convert the frame to tightly packed, top-down RGBA8 for building GUI textures.
+    ///
+    /// See [`OwnedFrame::to_rgba8_unpadded`].
+    pub fn to_rgba8_unpadded(&self) -> crate::error::Result<(Vec<u8>, u32, u32)> {
+        self.to_owned_frame()?.to_rgba8_unpadded()
+    }
+
+    /// Like [`VideoFrame::to_rgba8_unpadded`], with the color channels in the given alpha mode.
+    pub fn to_rgba8_unpadded_with_alpha(
+        &self,
+        alpha: AlphaMode,
+    ) -> crate::error::Result<(Vec<u8>, u32, u32)> {
+        self.to_owned_frame()?.to_rgba8_unpadded_with_alpha(alpha)
+    }
+
+    /// Convert the frame into `target`, reusing its allocation across frames.
+    ///
+    /// See [`OwnedFrame::copy_into_image`]; the pixels are read straight from the frame's
+    /// planes without copying them into an [`OwnedFrame`] first.
+    #[cfg(feature = "image")]
+    pub fn copy_into_image(&self, target: &mut image::RgbaImage) -> crate::error::Result<()> {
+        let info = self.info()?;
+        let view = info.view()?;
+        if target.dimensions() != (info.width, info.height) {
+            *target = image::RgbaImage::new(info.width, info.height);
         }
+        view.write_rgba8(target)
+    }
+
+    /// Encode the frame as an in-memory JPEG image.
+    ///
+    /// See [`OwnedFrame::to_jpeg`].
+    #[cfg(feature = "image")]
+    pub fn to_jpeg(&self, quality: u8) -> crate::error::Result<Vec<u8>> {
+        self.to_owned_frame()?.to_jpeg(quality)
+    }
+
+    /// Rotate the frame clockwise into a new top-down RGB24 frame.
+    ///
+    /// See [`OwnedFrame::rotate`].
+    pub fn rotate(&self, rotation: Rotation) -> crate::error::Result<OwnedFrame> {
+        self.to_owned_frame()?.rotate(rotation)
+    }
+
+    /// Check whether the frame is valid but carries no pixel data
+    /// (`sizeInBytes == 0` or a null first plane).
+    pub fn is_empty(&self) -> bool {
+        self.raw_info()
+            .map_or(false, |info| check_frame_not_empty(&info).is_err())
+    }
+
+    /// Native frame info for the convenience getters, `None` for frames [`VideoFrame::info`]
+    /// rejects
+    fn getter_info(&self) -> Option<sys::CcapVideoFrameInfo> {
+        self.raw_info()
+            .filter(|info| check_frame_not_empty(info).is_ok())
     }
 
     /// Get frame width (convenience method)
     pub fn width(&self) -> u32 {
-        self.info().map(|info| info.width).unwrap_or(0)
+        self.getter_info().map_or(0, |info| info.width)
     }
 
     /// Get frame height (convenience method)
     pub fn height(&self) -> u32 {
-        self.info().map(|info| info.height).unwrap_or(0)
+        self.getter_info().map_or(0, |info| info.height)
     }
 
     /// Get pixel format (convenience method)
     pub fn pixel_format(&self) -> PixelFormat {
-        self.info()
-            .map(|info| info.pixel_format)
-            .unwrap_or(PixelFormat::Unknown)
+        self.getter_info().map_or(PixelFormat::Unknown, |info| {
+            PixelFormat::from(info.pixelFormat)
+        })
     }
 
     /// Get data size in bytes (convenience method)
     pub fn data_size(&self) -> u32 {
-        self.info().map(|info| info.size_in_bytes).unwrap_or(0)
+        self.getter_info().map_or(0, |info| info.sizeInBytes)
     }
 
     /// Get frame index (convenience method)
     pub fn index(&self) -> u64 {
-        self.info().map(|info| info.frame_index).unwrap_or(0)
+        self.getter_info().map_or(0, |info| info.frameIndex)
+    }
+}
+
+impl std::fmt::Debug for VideoFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VideoFrame")
+            .field("index", &self.index())
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("pixel_format", &self.pixel_format())
+            .field("data_size", &self.data_size())
+            .field("owns_frame", &self.owns_frame)
+            .finish()
     }
 }
 
@@ -221,6 +571,905 @@ impl Drop for VideoFrame {
 // https://github.com/wysaid/CameraCapture/issues
 unsafe impl Send for VideoFrame {}
 
+/// A [`VideoFrame`] borrowed from the [`Provider`](crate::Provider) that grabbed it
+///
+/// Returned by [`Provider::grab_frame_borrowed`](crate::Provider::grab_frame_borrowed).
+/// It keeps the provider mutably borrowed, so it can't outlive the next grab or be stored
+/// alongside the provider. Like [`VideoFrame`] it is `Send`, so it can be handed to a scoped
+/// thread for the duration of the borrow. Dropping it releases the native frame exactly as
+/// dropping a `VideoFrame` does; the type only adds the borrow. Dereferences to
+/// [`VideoFrame`]; copy what you need out with e.g. [`VideoFrame::to_owned_frame`].
+pub struct FrameRef<'a> {
+    frame: VideoFrame,
+    _provider: std::marker::PhantomData<&'a mut ()>,
+}
+
+impl<'a> FrameRef<'a> {
+    pub(crate) fn new(frame: VideoFrame) -> Self {
+        FrameRef {
+            frame,
+            _provider: std::marker::PhantomData,
+        }
+    }
+
+    /// Hand the native frame back to the library's frame pool right away.
+    ///
+    /// Optional: dropping the `FrameRef` does the same. Calling this documents the intent
+    /// when processing finishes early and the buffer can be recycled sooner. The ref is
+    /// consumed, so the frame can't be used afterwards:
+    ///
+    /// ```compile_fail,E0382
+    /// # fn check(mut provider: ccap::Provider) -> ccap::Result<()> {
+    /// if let Some(frame) = provider.grab_frame_borrowed(1000)? {
+    ///     frame.release_early();
+    ///     let _ = frame.width();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Frames passed to a new-frame callback are owned by the native layer and are always
+    /// released when the callback returns; the C API has no way to release them earlier.
+    pub fn release_early(self) {
+        drop(self);
+    }
+}
+
+impl std::ops::Deref for FrameRef<'_> {
+    type Target = VideoFrame;
+
+    fn deref(&self) -> &VideoFrame {
+        &self.frame
+    }
+}
+
+/// Plane layout used when copying a native frame into an [`OwnedFrame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OwnedFrameLayout {
+    /// Keep the source strides, including any row padding (exact layout round-trip)
+    Native,
+    /// Strip row padding so each stride equals the unpadded row size
+    #[default]
+    Packed,
+}
+
+/// An owned copy of a video frame
+///
+/// Unlike [`VideoFrame`], which wraps a native frame, `OwnedFrame` stores its planes in
+/// `Vec<u8>` buffers. It is `Send + Sync`, can be cloned, and stays valid independently of the
+/// provider that captured it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedFrame {
+    width: u32,
+    height: u32,
+    pixel_format: PixelFormat,
+    timestamp: u64,
+    frame_index: u64,
+    orientation: FrameOrientation,
+    planes: Vec<Vec<u8>>,
+    strides: [u32; 3],
+}
+
+impl OwnedFrame {
+    /// Create an owned frame from plane buffers and their strides.
+    ///
+    /// `planes` and `strides` must contain exactly [`PixelFormat::num_planes`] entries. Each
+    /// stride must cover a full unpadded row and each plane must hold `stride * rows` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if the planes don't match the pixel format layout.
+    pub fn from_planes(
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+        planes: Vec<Vec<u8>>,
+        strides: &[u32],
+    ) -> crate::error::Result<Self> {
+        let num_planes = pixel_format.num_planes();
+        if num_planes == 0 {
+            return Err(CcapError::InvalidParameter(format!(
+                "unsupported pixel format {:?}",
+                pixel_format
+            )));
+        }
+        if planes.len() != num_planes || strides.len() != num_planes {
+            return Err(CcapError::InvalidParameter(format!(
+                "{:?} needs {} planes, got {} planes and {} strides",
+                pixel_format,
+                num_planes,
+                planes.len(),
+                strides.len()
+            )));
+        }
+
+        let mut plane_strides = [0u32; 3];
+        for (index, (plane, &stride)) in planes.iter().zip(strides).enumerate() {
+            let row_bytes = pixel_format.plane_row_bytes(width, index);
+            let required = stride as usize * pixel_format.plane_rows(height, index);
+            if (stride as usize) < row_bytes || plane.len() < required {
+                return Err(CcapError::InvalidParameter(format!(
+                    "plane {} too small: stride {} (need >= {}), {} bytes (need >= {})",
+                    index,
+                    stride,
+                    row_bytes,
+                    plane.len(),
+                    required
+                )));
+            }
+            plane_strides[index] = stride;
+        }
+
+        Ok(OwnedFrame {
+            width,
+            height,
+            pixel_format,
+            timestamp: 0,
+            frame_index: 0,
+            orientation: FrameOrientation::TopToBottom,
+            planes,
+            strides: plane_strides,
+        })
+    }
+
+    /// Copy borrowed planes into a new frame with the requested layout
+    pub(crate) fn copy_from_planes(
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+        planes: &[&[u8]],
+        strides: &[u32],
+        layout: OwnedFrameLayout,
+    ) -> crate::error::Result<Self> {
+        match layout {
+            OwnedFrameLayout::Native => Self::from_planes(
+                width,
+                height,
+                pixel_format,
+                planes.iter().map(|plane| plane.to_vec()).collect(),
+                strides,
+            ),
+            OwnedFrameLayout::Packed => {
+                let mut packed_planes = Vec::with_capacity(planes.len());
+                let mut packed_strides = Vec::with_capacity(planes.len());
+                for (index, (plane, &stride)) in planes.iter().zip(strides).enumerate() {
+                    let row_bytes = pixel_format.plane_row_bytes(width, index);
+                    let rows = pixel_format.plane_rows(height, index);
+                    let mut packed = Vec::with_capacity(row_bytes * rows);
+                    for row in 0..rows {
+                        let start = row * stride as usize;
+                        let data = plane
+                            .get(start..start + row_bytes)
+                            .filter(|_| stride as usize >= row_bytes)
+                            .ok_or_else(|| {
+                                CcapError::InvalidParameter(format!(
+                                    "plane {} too small for {} rows of stride {}",
+                                    index, rows, stride
+                                ))
+                            })?;
+                        packed.extend_from_slice(data);
+                    }
+                    packed_planes.push(packed);
+                    packed_strides.push(row_bytes as u32);
+                }
+                Self::from_planes(width, height, pixel_format, packed_planes, &packed_strides)
+            }
+        }
+    }
+
+    /// Set the frame timestamp
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the frame sequence index
+    pub fn with_frame_index(mut self, frame_index: u64) -> Self {
+        self.frame_index = frame_index;
+        self
+    }
+
+    /// Set the frame orientation
+    pub fn with_orientation(mut self, orientation: FrameOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Frame width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Frame height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pixel format of the frame
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    /// Frame timestamp in nanoseconds (see [`VideoFrameInfo::timestamp`] for the clock used)
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Frame sequence index
+    pub fn frame_index(&self) -> u64 {
+        self.frame_index
+    }
+
+    /// Frame orientation
+    pub fn orientation(&self) -> FrameOrientation {
+        self.orientation
+    }
+
+    /// Get the data of plane `index`, or `None` if the format has no such plane
+    pub fn plane(&self, index: usize) -> Option<&[u8]> {
+        self.planes.get(index).map(Vec::as_slice)
+    }
+
+    /// Stride values for each plane (0 for planes the format doesn't use)
+    pub fn strides(&self) -> [u32; 3] {
+        self.strides
+    }
+
+    /// Iterate over the unpadded rows of plane `index`
+    fn plane_rows(&self, index: usize) -> impl Iterator<Item = &[u8]> {
+        self.view().plane_rows(index)
+    }
+
+    /// Borrow the planes for the conversions shared with [`VideoFrame`]
+    fn view(&self) -> FramePlanes<'_> {
+        let mut planes: [&[u8]; 3] = [&[]; 3];
+        for (plane, data) in planes.iter_mut().zip(&self.planes) {
+            *plane = data;
+        }
+        FramePlanes {
+            width: self.width,
+            height: self.height,
+            pixel_format: self.pixel_format,
+            orientation: self.orientation,
+            planes,
+            strides: self.strides,
+        }
+    }
+
+    /// Compare dimensions, pixel format and pixel data with another frame.
+    ///
+    /// Stride padding is ignored, so the same image stored with different strides compares
+    /// equal. Metadata (timestamp, index, orientation) is not compared.
+    pub fn content_eq(&self, other: &OwnedFrame) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.pixel_format == other.pixel_format
+            && (0..self.planes.len())
+                .all(|index| self.plane_rows(index).eq(other.plane_rows(index)))
+    }
+
+    /// Hash of the frame content, consistent with [`OwnedFrame::content_eq`].
+    ///
+    /// Uses 64-bit FNV-1a so the value is stable across runs, platforms and Rust versions
+    /// and can be stored alongside golden snapshots.
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let header = [
+            self.width as u64,
+            self.height as u64,
+            sys::CcapPixelFormat::from(self.pixel_format) as u64,
+        ];
+        let header_bytes = header.iter().flat_map(|value| value.to_le_bytes());
+        let pixel_bytes = (0..self.planes.len())
+            .flat_map(|index| self.plane_rows(index))
+            .flatten()
+            .copied();
+
+        header_bytes
+            .chain(pixel_bytes)
+            .fold(FNV_OFFSET, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// CRC-32 of the packed pixel data, for detecting corruption after storage or transport.
+    ///
+    /// Uses the IEEE polynomial (as in zlib and PNG) over the unpadded rows of each plane
+    /// in order, so stride padding doesn't affect the result. Unlike
+    /// [`OwnedFrame::content_hash`], dimensions and pixel format are not included.
+    pub fn crc32(&self) -> u32 {
+        let crc = (0..self.planes.len())
+            .flat_map(|index| self.plane_rows(index))
+            .flatten()
+            .fold(!0u32, |crc, &byte| {
+                CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+            });
+        !crc
+    }
+
+    /// Repack the frame into a tightly-strided I420 buffer (Y, then U, then V).
+    ///
+    /// The output is the layout video encoders such as x264/openh264 expect: a `width` stride
+    /// Y plane followed by `(width + 1) / 2` stride U and V planes, i.e. `w * h * 3 / 2` bytes
+    /// for even dimensions. Source stride padding is dropped.
+    ///
+    /// NV12 chroma is de-interleaved; packed 4:2:2 (YUYV/UYVY) chroma is averaged vertically.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for non-YUV sources.
+    pub fn to_contiguous_i420(&self) -> crate::error::Result<Vec<u8>> {
+        let (y, u, v) = self.yuv420_planes()?;
+        let mut out = y;
+        out.extend_from_slice(&u);
+        out.extend_from_slice(&v);
+        Ok(out)
+    }
+
+    /// Repack the frame into a tightly-strided NV12 buffer (Y, then interleaved UV).
+    ///
+    /// See [`OwnedFrame::to_contiguous_i420`] for the handling of source layouts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for non-YUV sources.
+    pub fn to_contiguous_nv12(&self) -> crate::error::Result<Vec<u8>> {
+        let (y, u, v) = self.yuv420_planes()?;
+        let mut out = y;
+        out.reserve(u.len() * 2);
+        for (&u, &v) in u.iter().zip(&v) {
+            out.push(u);
+            out.push(v);
+        }
+        Ok(out)
+    }
+
+    /// Convert the frame to a tightly-packed RGB24 buffer (`width * height * 3` bytes).
+    ///
+    /// YUV sources go through the accelerated converters in [`Convert`](crate::Convert),
+    /// decoded with the range given by [`PixelFormat::color_range`]; BGR and alpha formats
+    /// are reordered directly. Rows are returned in memory order
+    /// regardless of [`OwnedFrame::orientation`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for formats without a known layout.
+    pub fn to_rgb24(&self) -> crate::error::Result<Vec<u8>> {
+        self.convert_rgb24(false)
+    }
+
+    /// Convert the frame to a tightly-packed RGB24 buffer with rows in `output_orientation`.
+    ///
+    /// When it differs from [`OwnedFrame::orientation`], YUV sources are flipped as part of
+    /// the conversion instead of in a separate pass; e.g. pass
+    /// [`FrameOrientation::TopToBottom`] to get an upright image from any frame. Passing the
+    /// frame's own orientation is the same as [`OwnedFrame::to_rgb24`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for formats without a known layout.
+    pub fn to_rgb24_oriented(
+        &self,
+        output_orientation: FrameOrientation,
+    ) -> crate::error::Result<Vec<u8>> {
+        self.convert_rgb24(output_orientation != self.orientation)
+    }
+
+    /// RGB24 conversion behind [`OwnedFrame::to_rgb24`], reversing the rows if `flip` is set
+    fn convert_rgb24(&self, flip: bool) -> crate::error::Result<Vec<u8>> {
+        self.view().convert_rgb24(flip)
+    }
+
+    /// Convert the frame to tightly packed, top-down RGBA8 for building GUI textures.
+    ///
+    /// Returns `(rgba, width, height)` with `rgba.len() == width * height * 4` and an opaque
+    /// alpha channel unless the source has one. Unlike [`OwnedFrame::to_rgb24`], bottom-to-top
+    /// frames are flipped, so the buffer can be handed directly to e.g. egui's
+    /// `ColorImage::from_rgba_unmultiplied` or iced's `image::Handle::from_pixels`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for formats without a known layout.
+    pub fn to_rgba8_unpadded(&self) -> crate::error::Result<(Vec<u8>, u32, u32)> {
+        let mut rgba = vec![0u8; PixelFormat::Rgba32.buffer_size(self.width, self.height)];
+        self.write_rgba8(&mut rgba)?;
+        Ok((rgba, self.width, self.height))
+    }
+
+    /// Write the frame as tightly packed, top-down RGBA8 into `rgba`, sized `width * height * 4`
+    fn write_rgba8(&self, rgba: &mut [u8]) -> crate::error::Result<()> {
+        self.view().write_rgba8(rgba)
+    }
+
+    /// Like [`OwnedFrame::to_rgba8_unpadded`], with the color channels in the given alpha mode.
+    ///
+    /// Only frames with a real alpha channel (RGBA32/BGRA32) are affected by
+    /// [`AlphaMode::Premultiplied`]; everything else is opaque.
+    pub fn to_rgba8_unpadded_with_alpha(
+        &self,
+        alpha: AlphaMode,
+    ) -> crate::error::Result<(Vec<u8>, u32, u32)> {
+        let (mut rgba, width, height) = self.to_rgba8_unpadded()?;
+        let has_alpha = matches!(self.pixel_format, PixelFormat::Rgba32 | PixelFormat::Bgra32);
+        if alpha == AlphaMode::Premultiplied && has_alpha {
+            let stride = PixelFormat::Rgba32.plane_row_bytes(width, 0);
+            crate::convert::Convert::premultiply_alpha_in_place(&mut rgba, stride, width, height)?;
+        }
+        Ok((rgba, width, height))
+    }
+
+    /// Downscale into a top-down RGBA32 thumbnail whose longest side is at most `max_dim`.
+    ///
+    /// The aspect ratio is kept and pixels are area-averaged. Frames that already fit are
+    /// converted without scaling. Timestamp and frame index are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `max_dim` is zero, or
+    /// `CcapError::NotSupported` for formats without a known layout.
+    pub fn thumbnail(&self, max_dim: u32) -> crate::error::Result<OwnedFrame> {
+        if max_dim == 0 {
+            return Err(CcapError::InvalidParameter(
+                "thumbnail size must be non-zero".to_string(),
+            ));
+        }
+        let (width, height) = (self.width, self.height);
+        let longest = width.max(height);
+        let scale = |side: u32| {
+            ((side as u64 * max_dim as u64 + longest as u64 / 2) / longest as u64).max(1) as u32
+        };
+        let (thumb_width, thumb_height) = if longest <= max_dim {
+            (width, height)
+        } else {
+            (scale(width), scale(height))
+        };
+
+        let (rgba, _, _) = self.to_rgba8_unpadded()?;
+        let rgba = if (thumb_width, thumb_height) == (width, height) {
+            rgba
+        } else {
+            crate::convert::area_downscale_plane(
+                &rgba,
+                PixelFormat::Rgba32.plane_row_bytes(width, 0),
+                (width as usize, height as usize),
+                4,
+                (thumb_width as usize, thumb_height as usize),
+            )
+        };
+        let stride = PixelFormat::Rgba32.plane_row_bytes(thumb_width, 0) as u32;
+        Ok(OwnedFrame::from_planes(
+            thumb_width,
+            thumb_height,
+            PixelFormat::Rgba32,
+            vec![rgba],
+            &[stride],
+        )?
+        .with_timestamp(self.timestamp)
+        .with_frame_index(self.frame_index))
+    }
+
+    /// Convert into a new top-down, tightly packed frame in one of the RGB formats
+    /// (RGB24, BGR24, RGBA32 or BGRA32), keeping the timestamp and frame index.
+    pub(crate) fn convert_to(&self, format: PixelFormat) -> crate::error::Result<OwnedFrame> {
+        let swap_red_blue = |data: &mut [u8], bytes_per_pixel: usize| {
+            for pixel in data.chunks_exact_mut(bytes_per_pixel) {
+                pixel.swap(0, 2);
+            }
+        };
+        let data = match format {
+            PixelFormat::Rgb24 | PixelFormat::Bgr24 => {
+                let mut rgb = self.to_rgb24_oriented(FrameOrientation::TopToBottom)?;
+                if format == PixelFormat::Bgr24 {
+                    swap_red_blue(&mut rgb, 3);
+                }
+                rgb
+            }
+            PixelFormat::Rgba32 | PixelFormat::Bgra32 => {
+                let (mut rgba, _, _) = self.to_rgba8_unpadded()?;
+                if format == PixelFormat::Bgra32 {
+                    swap_red_blue(&mut rgba, 4);
+                }
+                rgba
+            }
+            _ => return Err(CcapError::NotSupported),
+        };
+        let stride = format.plane_row_bytes(self.width, 0) as u32;
+        Ok(
+            OwnedFrame::from_planes(self.width, self.height, format, vec![data], &[stride])?
+                .with_timestamp(self.timestamp)
+                .with_frame_index(self.frame_index),
+        )
+    }
+
+    /// Rotate the frame clockwise into a new top-down RGB24 frame.
+    ///
+    /// Bottom-to-top frames are flipped upright before rotating. Timestamp and frame index
+    /// are kept; width and height are swapped for 90 and 270 degree rotations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for formats without a known layout.
+    pub fn rotate(&self, rotation: Rotation) -> crate::error::Result<OwnedFrame> {
+        let row_bytes = PixelFormat::Rgb24.plane_row_bytes(self.width, 0);
+        let rgb = self.to_rgb24_oriented(FrameOrientation::TopToBottom)?;
+        let (rotated, width, height) = crate::convert::Convert::rotate_rgb24(
+            &rgb,
+            row_bytes,
+            self.width,
+            self.height,
+            rotation,
+        )?;
+        let stride = PixelFormat::Rgb24.plane_row_bytes(width, 0) as u32;
+        Ok(
+            OwnedFrame::from_planes(width, height, PixelFormat::Rgb24, vec![rotated], &[stride])?
+                .with_timestamp(self.timestamp)
+                .with_frame_index(self.frame_index),
+        )
+    }
+
+    /// Convert the frame to an [`image::RgbImage`]
+    #[cfg(feature = "image")]
+    pub fn to_rgb_image(&self) -> crate::error::Result<image::RgbImage> {
+        let rgb = self.to_rgb24()?;
+        image::RgbImage::from_raw(self.width, self.height, rgb).ok_or_else(|| {
+            CcapError::InternalError("RGB buffer does not match frame size".to_string())
+        })
+    }
+
+    /// Convert the frame into `target`, reusing its allocation across frames.
+    ///
+    /// `target` is only reallocated when its dimensions differ from the frame's, so a preview
+    /// that keeps one [`image::RgbaImage`] around doesn't allocate a new image per frame. The
+    /// pixels are the same as [`OwnedFrame::to_rgba8_unpadded`]: top-down RGBA8. YUV and
+    /// 24-bit RGB frames still go through a temporary RGB24 buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for formats without a known layout.
+    #[cfg(feature = "image")]
+    pub fn copy_into_image(&self, target: &mut image::RgbaImage) -> crate::error::Result<()> {
+        if target.dimensions() != (self.width, self.height) {
+            *target = image::RgbaImage::new(self.width, self.height);
+        }
+        self.write_rgba8(target)
+    }
+
+    /// Encode the frame as an in-memory JPEG image.
+    ///
+    /// The frame is converted to RGB and bottom-to-top frames are flipped upright before
+    /// encoding. `quality` ranges from 1 (smallest) to 100 (best).
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` for a quality outside 1-100,
+    /// `CcapError::NotSupported` for formats without a known layout, and
+    /// `CcapError::InternalError` if encoding fails.
+    #[cfg(feature = "image")]
+    pub fn to_jpeg(&self, quality: u8) -> crate::error::Result<Vec<u8>> {
+        if !(1..=100).contains(&quality) {
+            return Err(CcapError::InvalidParameter(format!(
+                "JPEG quality must be between 1 and 100, got {}",
+                quality
+            )));
+        }
+        let upright = self.rotate(Rotation::None)?;
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, quality)
+            .encode(
+                upright.plane(0).unwrap_or_default(),
+                upright.width,
+                upright.height,
+                image::ColorType::Rgb8,
+            )
+            .map_err(|e| CcapError::InternalError(format!("JPEG encoding failed: {}", e)))?;
+        Ok(jpeg)
+    }
+
+    /// Convert the frame to a tightly-packed 8-bit grayscale buffer (`width * height` bytes).
+    ///
+    /// YUV sources use the Y plane directly; RGB sources use BT.601 luma weights.
+    /// Rows are returned in memory order regardless of [`OwnedFrame::orientation`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for formats without a known layout.
+    pub fn to_gray8(&self) -> crate::error::Result<Vec<u8>> {
+        let (channels, r, g, b) = match self.pixel_format {
+            PixelFormat::Rgb24 => (3, 0, 1, 2),
+            PixelFormat::Bgr24 => (3, 2, 1, 0),
+            PixelFormat::Rgba32 => (4, 0, 1, 2),
+            PixelFormat::Bgra32 => (4, 2, 1, 0),
+            _ => return self.yuv420_planes().map(|(y, _, _)| y),
+        };
+
+        let mut gray = Vec::with_capacity(self.width as usize * self.height as usize);
+        for row in self.plane_rows(0) {
+            gray.extend(row.chunks_exact(channels).map(|pixel| {
+                let luma = 77 * pixel[r] as u32 + 150 * pixel[g] as u32 + 29 * pixel[b] as u32;
+                ((luma + 128) >> 8) as u8
+            }));
+        }
+        Ok(gray)
+    }
+
+    /// Render a small ASCII-art preview of the frame, `cols` characters wide.
+    ///
+    /// The row count is chosen to preserve the aspect ratio, assuming terminal cells are
+    /// roughly twice as tall as they are wide. Each line ends with `'\n'`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `cols` is zero, or any error from
+    /// [`OwnedFrame::to_gray8`].
+    pub fn ascii_preview(&self, cols: usize) -> crate::error::Result<String> {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+
+        if cols == 0 {
+            return Err(CcapError::InvalidParameter(
+                "preview width must be greater than 0".to_string(),
+            ));
+        }
+        let gray = self.to_gray8()?;
+        let width = self.width as usize;
+        let height = self.height as usize;
+        if width == 0 || height == 0 {
+            return Ok(String::new());
+        }
+
+        let cols = cols.min(width);
+        let rows = ((cols * height) / (width * 2)).clamp(1, height);
+        let mut preview = String::with_capacity((cols + 1) * rows);
+        for cell_row in 0..rows {
+            let cell_row = match self.orientation {
+                FrameOrientation::TopToBottom => cell_row,
+                FrameOrientation::BottomToTop => rows - 1 - cell_row,
+            };
+            let (y0, y1) = (cell_row * height / rows, (cell_row + 1) * height / rows);
+            for cell_col in 0..cols {
+                let (x0, x1) = (cell_col * width / cols, (cell_col + 1) * width / cols);
+                let sum: u64 = (y0..y1)
+                    .flat_map(|y| &gray[y * width + x0..y * width + x1])
+                    .map(|&value| value as u64)
+                    .sum();
+                let mean = sum / ((y1 - y0) * (x1 - x0)) as u64;
+                preview.push(RAMP[mean as usize * (RAMP.len() - 1) / 255] as char);
+            }
+            preview.push('\n');
+        }
+        Ok(preview)
+    }
+
+    /// Extract unpadded Y, U and V 4:2:0 planes from any YUV source format
+    fn yuv420_planes(&self) -> crate::error::Result<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let chroma_width = (width + 1) / 2;
+        let chroma_height = (height + 1) / 2;
+        let chroma_size = chroma_width * chroma_height;
+
+        match self.pixel_format {
+            PixelFormat::I420 | PixelFormat::I420F => {
+                let y = self.plane_rows(0).flatten().copied().collect();
+                let u = self.plane_rows(1).flatten().copied().collect();
+                let v = self.plane_rows(2).flatten().copied().collect();
+                Ok((y, u, v))
+            }
+            PixelFormat::Nv12 | PixelFormat::Nv12F => {
+                let y = self.plane_rows(0).flatten().copied().collect();
+                let mut u = Vec::with_capacity(chroma_size);
+                let mut v = Vec::with_capacity(chroma_size);
+                for row in self.plane_rows(1) {
+                    for pair in row.chunks_exact(2) {
+                        u.push(pair[0]);
+                        v.push(pair[1]);
+                    }
+                }
+                Ok((y, u, v))
+            }
+            PixelFormat::Yuyv | PixelFormat::YuyvF | PixelFormat::Uyvy | PixelFormat::UyvyF => {
+                // Byte offsets of Y0, U, Y1, V inside each 4-byte macropixel.
+                let (y0, u0, y1, v0) = match self.pixel_format {
+                    PixelFormat::Yuyv | PixelFormat::YuyvF => (0, 1, 2, 3),
+                    _ => (1, 0, 3, 2),
+                };
+                let rows: Vec<&[u8]> = self.plane_rows(0).collect();
+                let mut y = Vec::with_capacity(width * height);
+                for row in &rows {
+                    for (x, pixel) in row.chunks_exact(4).enumerate() {
+                        y.push(pixel[y0]);
+                        if x * 2 + 1 < width {
+                            y.push(pixel[y1]);
+                        }
+                    }
+                }
+                let mut u = Vec::with_capacity(chroma_size);
+                let mut v = Vec::with_capacity(chroma_size);
+                for pair in rows.chunks(2) {
+                    let top = pair[0];
+                    let bottom = pair.get(1).copied().unwrap_or(top);
+                    for (a, b) in top.chunks_exact(4).zip(bottom.chunks_exact(4)) {
+                        u.push(((a[u0] as u16 + b[u0] as u16 + 1) / 2) as u8);
+                        v.push(((a[v0] as u16 + b[v0] as u16 + 1) / 2) as u8);
+                    }
+                }
+                Ok((y, u, v))
+            }
+            _ => Err(CcapError::NotSupported),
+        }
+    }
+}
+
+/// Borrowed planes of a frame, shared by the conversions of [`OwnedFrame`] and [`VideoFrame`].
+///
+/// Each plane must hold `stride * rows` bytes with `stride` covering a full row; both
+/// constructors check this.
+#[derive(Clone, Copy)]
+struct FramePlanes<'a> {
+    width: u32,
+    height: u32,
+    pixel_format: PixelFormat,
+    orientation: FrameOrientation,
+    planes: [&'a [u8]; 3],
+    strides: [u32; 3],
+}
+
+impl<'a> FramePlanes<'a> {
+    /// Rows of plane `index` without their stride padding
+    fn plane_rows(self, index: usize) -> impl Iterator<Item = &'a [u8]> {
+        let row_bytes = self.pixel_format.plane_row_bytes(self.width, index);
+        let rows = self.pixel_format.plane_rows(self.height, index);
+        let stride = (self.strides[index] as usize).max(1);
+        self.planes[index]
+            .chunks(stride)
+            .take(rows)
+            .map(move |row| &row[..row_bytes])
+    }
+
+    /// Tightly packed RGB24, reversing the rows if `flip` is set
+    fn convert_rgb24(&self, flip: bool) -> crate::error::Result<Vec<u8>> {
+        use crate::convert::Convert;
+
+        let (width, height) = (self.width, self.height);
+        let stride = |index: usize| self.strides[index] as usize;
+        // YUV sources are decoded with the range implied by the format (`F` = full range).
+        let range = self.pixel_format.color_range().unwrap_or_default();
+        let rgb_stride = PixelFormat::Rgb24.plane_row_bytes(width, 0);
+        let new_rgb = || vec![0u8; PixelFormat::Rgb24.buffer_size(width, height)];
+        match self.pixel_format {
+            PixelFormat::Rgb24 => {
+                let mut rgb: Vec<u8> = self.plane_rows(0).flatten().copied().collect();
+                if flip {
+                    flip_rows(&mut rgb, rgb_stride);
+                }
+                Ok(rgb)
+            }
+            PixelFormat::Bgr24 => {
+                let mut rgb = Convert::bgr_to_rgb(self.planes[0], stride(0), width, height)?;
+                if flip {
+                    flip_rows(&mut rgb, rgb_stride);
+                }
+                Ok(rgb)
+            }
+            PixelFormat::Rgba32 | PixelFormat::Bgra32 => {
+                let (r, b) = if self.pixel_format == PixelFormat::Rgba32 {
+                    (0, 2)
+                } else {
+                    (2, 0)
+                };
+                let mut rgb = Vec::with_capacity(PixelFormat::Rgb24.buffer_size(width, height));
+                for row in self.plane_rows(0) {
+                    for pixel in row.chunks_exact(4) {
+                        rgb.extend_from_slice(&[pixel[r], pixel[1], pixel[b]]);
+                    }
+                }
+                if flip {
+                    flip_rows(&mut rgb, rgb_stride);
+                }
+                Ok(rgb)
+            }
+            PixelFormat::Nv12 | PixelFormat::Nv12F => {
+                let mut rgb = new_rgb();
+                Convert::nv12_to_rgb24_into_with_range_oriented(
+                    self.planes[0],
+                    stride(0),
+                    self.planes[1],
+                    stride(1),
+                    &mut rgb,
+                    rgb_stride,
+                    width,
+                    height,
+                    range,
+                    flip,
+                )?;
+                Ok(rgb)
+            }
+            PixelFormat::I420 | PixelFormat::I420F => {
+                let mut rgb = new_rgb();
+                Convert::i420_to_rgb24_into_with_range_oriented(
+                    self.planes[0],
+                    stride(0),
+                    self.planes[1],
+                    stride(1),
+                    self.planes[2],
+                    stride(2),
+                    &mut rgb,
+                    rgb_stride,
+                    width,
+                    height,
+                    range,
+                    flip,
+                )?;
+                Ok(rgb)
+            }
+            PixelFormat::Yuyv | PixelFormat::YuyvF => {
+                let mut rgb = new_rgb();
+                Convert::yuyv_to_rgb24_into_with_range_oriented(
+                    self.planes[0],
+                    stride(0),
+                    &mut rgb,
+                    rgb_stride,
+                    width,
+                    height,
+                    range,
+                    flip,
+                )?;
+                Ok(rgb)
+            }
+            PixelFormat::Uyvy | PixelFormat::UyvyF => {
+                // Swap each (U/V, Y) byte pair into YUYV order for the converter.
+                let mut yuyv: Vec<u8> = self.plane_rows(0).flatten().copied().collect();
+                for pair in yuyv.chunks_exact_mut(2) {
+                    pair.swap(0, 1);
+                }
+                let row_bytes = self.pixel_format.plane_row_bytes(width, 0);
+                let mut rgb = new_rgb();
+                Convert::yuyv_to_rgb24_into_with_range_oriented(
+                    &yuyv, row_bytes, &mut rgb, rgb_stride, width, height, range, flip,
+                )?;
+                Ok(rgb)
+            }
+            PixelFormat::Unknown => Err(CcapError::NotSupported),
+        }
+    }
+
+    /// Write tightly packed, top-down RGBA8 into `rgba`, sized `width * height * 4`
+    fn write_rgba8(&self, rgba: &mut [u8]) -> crate::error::Result<()> {
+        let row_bytes = PixelFormat::Rgba32.plane_row_bytes(self.width, 0);
+        if row_bytes == 0 {
+            return Ok(());
+        }
+        match self.pixel_format {
+            PixelFormat::Rgba32 => {
+                for (src, dst) in self.plane_rows(0).zip(rgba.chunks_exact_mut(row_bytes)) {
+                    dst.copy_from_slice(src);
+                }
+            }
+            PixelFormat::Bgra32 => {
+                for (src, dst) in self.plane_rows(0).zip(rgba.chunks_exact_mut(row_bytes)) {
+                    for (pixel, out) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+                        out.copy_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+                    }
+                }
+            }
+            _ => {
+                let upright =
+                    self.convert_rgb24(self.orientation != FrameOrientation::TopToBottom)?;
+                for (pixel, out) in upright.chunks_exact(3).zip(rgba.chunks_exact_mut(4)) {
+                    out.copy_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+                }
+                return Ok(());
+            }
+        }
+
+        if self.orientation == FrameOrientation::BottomToTop {
+            flip_rows(rgba, row_bytes);
+        }
+        Ok(())
+    }
+}
+
 /// High-level video frame information
 #[derive(Debug)]
 pub struct VideoFrameInfo<'a> {
@@ -232,7 +1481,13 @@ pub struct VideoFrameInfo<'a> {
     pub pixel_format: PixelFormat,
     /// Size of frame data in bytes
     pub size_in_bytes: u32,
-    /// Frame timestamp
+    /// Frame timestamp in nanoseconds.
+    ///
+    /// The clock is chosen by the platform backend and can't be configured: V4L2 uses
+    /// a monotonic clock started when capture starts, macOS and Media Foundation use the
+    /// sample presentation time, and DirectShow uses the sample time (falling back to a
+    /// monotonic clock from capture start when the driver reports bogus times, as some
+    /// virtual cameras do). Timestamps are therefore only comparable within one provider.
     pub timestamp: u64,
     /// Frame sequence index
     pub frame_index: u64,
@@ -243,3 +1498,923 @@ pub struct VideoFrameInfo<'a> {
     /// Stride values for each plane
     pub strides: [u32; 3],
 }
+
+/// One data plane of a video frame
+#[derive(Debug, Clone, Copy)]
+pub struct Plane<'a> {
+    /// Plane bytes
+    pub data: &'a [u8],
+    /// Bytes per row, including any padding
+    pub stride: u32,
+}
+
+impl<'a> VideoFrameInfo<'a> {
+    /// Get a plane that the frame's pixel format requires.
+    ///
+    /// Unlike indexing [`VideoFrameInfo::data_planes`], a plane the format needs but the
+    /// driver left null is an error rather than `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if the pixel format has no plane `index`, or
+    /// `CcapError::InternalError` if the plane should exist but is missing.
+    pub fn require_plane(&self, index: usize) -> crate::error::Result<Plane<'a>> {
+        let num_planes = self.pixel_format.num_planes();
+        if index >= num_planes {
+            return Err(CcapError::InvalidParameter(format!(
+                "{} has {} plane(s), no plane {}",
+                self.pixel_format.as_str(),
+                num_planes,
+                index
+            )));
+        }
+        let data = self.data_planes[index].ok_or_else(|| {
+            CcapError::InternalError(format!(
+                "missing data plane {} for {} frame",
+                index,
+                self.pixel_format.as_str()
+            ))
+        })?;
+        Ok(Plane {
+            data,
+            stride: self.strides[index],
+        })
+    }
+
+    /// Raw pointer, stride and length in bytes of plane `index`, for zero-copy GPU import.
+    ///
+    /// This is an escape hatch for custom uploads (e.g. importing into Vulkan or Metal)
+    /// that want to avoid the copy made by [`VideoFrame::to_rgba8_unpadded`]. The length is
+    /// the same as that of the [`VideoFrameInfo::data_planes`] slice. Returns `None` if the
+    /// plane is absent.
+    ///
+    /// The pointer is only valid while the frame this info was read from is alive; it is
+    /// up to the caller to keep the frame around for as long as the GPU reads from it.
+    /// Prefer the safe slice accessors whenever possible.
+    pub fn plane_ptr(&self, index: usize) -> Option<(*const u8, usize, usize)> {
+        let data = (*self.data_planes.get(index)?)?;
+        Some((data.as_ptr(), self.strides[index] as usize, data.len()))
+    }
+
+    /// Copy all planes into one tightly-packed buffer.
+    ///
+    /// Planes are concatenated in format order (Y then UV for NV12; Y, U, V for I420; the
+    /// single plane for packed formats) with row padding stripped. The returned info
+    /// describes that layout: strides are the packed row sizes and `size_in_bytes` is the
+    /// buffer length. It has no `data_planes`, since the bytes live in the returned `Vec`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::NotSupported` for [`PixelFormat::Unknown`], and the error from
+    /// [`VideoFrameInfo::require_plane`] if a plane is missing.
+    pub fn to_contiguous(&self) -> crate::error::Result<(Vec<u8>, VideoFrameInfo<'static>)> {
+        let format = self.pixel_format;
+        if format.num_planes() == 0 {
+            return Err(CcapError::NotSupported);
+        }
+
+        let mut data = Vec::new();
+        let mut strides = [0u32; 3];
+        for (index, packed_stride) in strides.iter_mut().enumerate().take(format.num_planes()) {
+            let row_bytes = format.plane_row_bytes(self.width, index);
+            data.reserve(row_bytes * format.plane_rows(self.height, index));
+            for row in self.packed_rows(index)? {
+                data.extend_from_slice(row);
+            }
+            *packed_stride = row_bytes as u32;
+        }
+
+        let info = VideoFrameInfo {
+            width: self.width,
+            height: self.height,
+            pixel_format: format,
+            size_in_bytes: data.len() as u32,
+            timestamp: self.timestamp,
+            frame_index: self.frame_index,
+            orientation: self.orientation,
+            data_planes: [None; 3],
+            strides,
+        };
+        Ok((data, info))
+    }
+
+    /// Copy each row of plane `index` into its own `Vec`, with stride padding stripped.
+    ///
+    /// Every row holds exactly the plane's packed row size (e.g. `width * 3` bytes for
+    /// RGB24, `width` for the NV12 Y plane). This allocates one `Vec` per row on top of
+    /// copying the plane, so prefer [`VideoFrameInfo::to_contiguous`] or
+    /// [`VideoFrameInfo::require_plane`] unless owned rows are needed, e.g. to hand them
+    /// to worker threads.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`VideoFrameInfo::require_plane`], plus `CcapError::InternalError` if the
+    /// plane is too small for the frame size.
+    pub fn rows_vec(&self, index: usize) -> crate::error::Result<Vec<Vec<u8>>> {
+        Ok(self.packed_rows(index)?.map(<[u8]>::to_vec).collect())
+    }
+
+    /// Rows of plane `index` without their stride padding
+    fn packed_rows(&self, index: usize) -> crate::error::Result<impl Iterator<Item = &'a [u8]>> {
+        let plane = self.checked_plane(index)?;
+        let row_bytes = self.pixel_format.plane_row_bytes(self.width, index);
+        let rows = self.pixel_format.plane_rows(self.height, index);
+        Ok(plane
+            .data
+            .chunks((plane.stride as usize).max(1))
+            .take(rows)
+            .map(move |row| &row[..row_bytes]))
+    }
+
+    /// [`VideoFrameInfo::require_plane`], also checking the plane holds all its rows
+    fn checked_plane(&self, index: usize) -> crate::error::Result<Plane<'a>> {
+        let format = self.pixel_format;
+        let plane = self.require_plane(index)?;
+        let row_bytes = format.plane_row_bytes(self.width, index);
+        let rows = format.plane_rows(self.height, index);
+        let stride = plane.stride as usize;
+        if stride < row_bytes || plane.data.len() < stride.saturating_mul(rows) {
+            return Err(CcapError::InternalError(format!(
+                "plane {} of {} frame is smaller than its {} rows of {} bytes",
+                index,
+                format.as_str(),
+                rows,
+                row_bytes
+            )));
+        }
+        Ok(plane)
+    }
+
+    /// Borrow the planes for the conversions shared with [`OwnedFrame`]
+    #[cfg(feature = "image")]
+    fn view(&self) -> crate::error::Result<FramePlanes<'a>> {
+        let format = self.pixel_format;
+        if format.num_planes() == 0 {
+            return Err(CcapError::NotSupported);
+        }
+        let mut planes: [&'a [u8]; 3] = [&[]; 3];
+        for (index, plane) in planes.iter_mut().enumerate().take(format.num_planes()) {
+            *plane = self.checked_plane(index)?.data;
+        }
+        Ok(FramePlanes {
+            width: self.width,
+            height: self.height,
+            pixel_format: format,
+            orientation: self.orientation,
+            planes,
+            strides: self.strides,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_info_clamps_oversized_counts() {
+        let mut info = sys::CcapDeviceInfo {
+            pixelFormatCount: usize::MAX,
+            resolutionCount: 1000,
+            ..Default::default()
+        };
+        // No NUL terminator: the name must stop at the end of the array
+        for c in info.deviceName.iter_mut() {
+            *c = b'A' as _;
+        }
+
+        let device = DeviceInfo::from_c_struct(&info).unwrap();
+        assert_eq!(device.name.len(), info.deviceName.len());
+        assert_eq!(
+            device.supported_pixel_formats.len(),
+            info.supportedPixelFormats.len()
+        );
+        assert_eq!(
+            device.supported_resolutions.len(),
+            info.supportedResolutions.len()
+        );
+    }
+
+    #[test]
+    fn test_device_info_keeps_non_utf8_name_bytes() {
+        let mut info = sys::CcapDeviceInfo::default();
+        let raw = [b'C', b'a', b'm', 0xFF, b'1'];
+        for (dst, &src) in info.deviceName.iter_mut().zip(raw.iter()) {
+            *dst = src as _;
+        }
+
+        let device = DeviceInfo::from_c_struct(&info).unwrap();
+        assert_eq!(device.name, "Cam\u{FFFD}1");
+        assert_eq!(device.name_bytes(), &raw[..]);
+        #[cfg(not(windows))]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            assert_eq!(device.name_os().as_bytes(), &raw[..]);
+        }
+
+        let manual = DeviceInfo {
+            name: "Mock Camera".to_string(),
+            supported_pixel_formats: Vec::new(),
+            supported_resolutions: Vec::new(),
+            name_bytes: Vec::new(),
+        };
+        assert_eq!(manual.name_bytes(), b"Mock Camera");
+        assert_eq!(manual.name_os(), "Mock Camera");
+    }
+
+    #[test]
+    fn test_zero_byte_frame_is_reported_as_empty() {
+        let mut pixels = vec![0u8; 16];
+        let mut info = sys::CcapVideoFrameInfo {
+            width: 4,
+            height: 4,
+            sizeInBytes: 0,
+            ..Default::default()
+        };
+        info.data[0] = pixels.as_mut_ptr();
+        info.stride[0] = 4;
+
+        match check_frame_not_empty(&info) {
+            Err(CcapError::InternalError(msg)) => assert_eq!(msg, "empty frame"),
+            other => panic!("Expected empty frame error, got {:?}", other),
+        }
+
+        info.sizeInBytes = pixels.len() as u32;
+        assert!(check_frame_not_empty(&info).is_ok());
+    }
+
+    #[test]
+    fn test_getters_use_cached_frame_info() {
+        let mut pixels = vec![0u8; 16];
+        let mut info = sys::CcapVideoFrameInfo {
+            width: 4,
+            height: 4,
+            pixelFormat: PixelFormat::Rgba32.to_c_enum(),
+            sizeInBytes: 16,
+            frameIndex: 7,
+            ..Default::default()
+        };
+        info.data[0] = pixels.as_mut_ptr();
+        info.stride[0] = 4;
+
+        // A null native frame fails every FFI query, so the getters only see the cached info.
+        let frame = VideoFrame::from_c_ptr_ref(std::ptr::null_mut());
+        assert_eq!(frame.width(), 0);
+        frame.info.set(Some(info));
+
+        assert_eq!(frame.width(), 4);
+        assert_eq!(frame.height(), 4);
+        assert_eq!(frame.pixel_format(), PixelFormat::Rgba32);
+        assert_eq!(frame.data_size(), 16);
+        assert_eq!(frame.index(), 7);
+        assert_eq!(frame.data().unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_info_planes_cover_whole_nv12_planes() {
+        const WIDTH: u32 = 6;
+        const HEIGHT: u32 = 5;
+        const STRIDE: u32 = 8;
+        let mut luma = vec![0u8; (STRIDE * HEIGHT) as usize];
+        let mut chroma = vec![0u8; (STRIDE * 3) as usize];
+        let mut info = sys::CcapVideoFrameInfo {
+            width: WIDTH,
+            height: HEIGHT,
+            pixelFormat: PixelFormat::Nv12.to_c_enum(),
+            sizeInBytes: (luma.len() + chroma.len()) as u32,
+            ..Default::default()
+        };
+        info.data[0] = luma.as_mut_ptr();
+        info.data[1] = chroma.as_mut_ptr();
+        info.stride[0] = STRIDE;
+        info.stride[1] = STRIDE;
+
+        let frame = VideoFrame::from_c_ptr_ref(std::ptr::null_mut());
+        frame.info.set(Some(info));
+        let info = frame.info().unwrap();
+
+        assert_eq!(
+            info.data_planes[0].unwrap().len(),
+            (info.strides[0] * HEIGHT) as usize
+        );
+        assert_eq!(info.data_planes[1].unwrap().len(), chroma.len());
+        assert!(info.data_planes[2].is_none());
+    }
+
+    #[test]
+    fn test_null_plane_frame_is_reported_as_empty() {
+        let info = sys::CcapVideoFrameInfo {
+            width: 4,
+            height: 4,
+            sizeInBytes: 16,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            check_frame_not_empty(&info),
+            Err(CcapError::InternalError(_))
+        ));
+    }
+
+    #[test]
+    fn test_require_plane_reports_missing_uv_plane() {
+        let y = [0u8; 16];
+        let info = VideoFrameInfo {
+            width: 4,
+            height: 4,
+            pixel_format: PixelFormat::Nv12,
+            size_in_bytes: 24,
+            timestamp: 0,
+            frame_index: 0,
+            orientation: FrameOrientation::TopToBottom,
+            data_planes: [Some(&y), None, None],
+            strides: [4, 4, 0],
+        };
+
+        let plane = info.require_plane(0).unwrap();
+        assert_eq!((plane.data.len(), plane.stride), (16, 4));
+        assert!(matches!(
+            info.require_plane(1),
+            Err(CcapError::InternalError(_))
+        ));
+        assert!(matches!(
+            info.require_plane(2),
+            Err(CcapError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_contiguous_packs_nv12_planes() {
+        // 4x2 NV12 with 6-byte strides: 2 bytes of padding per row
+        let y = [1u8, 2, 3, 4, 0, 0, 5, 6, 7, 8, 0, 0];
+        let uv = [9u8, 10, 11, 12, 0, 0];
+        let info = VideoFrameInfo {
+            width: 4,
+            height: 2,
+            pixel_format: PixelFormat::Nv12,
+            size_in_bytes: 18,
+            timestamp: 7,
+            frame_index: 3,
+            orientation: FrameOrientation::TopToBottom,
+            data_planes: [Some(&y), Some(&uv), None],
+            strides: [6, 6, 0],
+        };
+
+        let (data, packed) = info.to_contiguous().unwrap();
+        assert_eq!(data.len(), 4 * 2 + 4);
+        assert_eq!(data, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        assert_eq!(packed.strides, [4, 4, 0]);
+        assert_eq!(packed.size_in_bytes, 12);
+        assert_eq!((packed.timestamp, packed.frame_index), (7, 3));
+        assert!(packed.data_planes.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_rows_vec_strips_padding() {
+        // 2x2 RGB24 with 8-byte strides: 2 bytes of padding (0xEE) per row
+        let rgb = [
+            1u8, 2, 3, 4, 5, 6, 0xEE, 0xEE, //
+            7, 8, 9, 10, 11, 12, 0xEE, 0xEE,
+        ];
+        let info = VideoFrameInfo {
+            width: 2,
+            height: 2,
+            pixel_format: PixelFormat::Rgb24,
+            size_in_bytes: 16,
+            timestamp: 0,
+            frame_index: 0,
+            orientation: FrameOrientation::TopToBottom,
+            data_planes: [Some(&rgb), None, None],
+            strides: [8, 0, 0],
+        };
+
+        let rows = info.rows_vec(0).unwrap();
+        assert_eq!(
+            rows,
+            vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 10, 11, 12]]
+        );
+        assert!(rows.iter().all(|row| row.len() == 2 * 3));
+        assert!(matches!(
+            info.rows_vec(1),
+            Err(CcapError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_plane_ptr_matches_slices() {
+        let y = [0u8; 16];
+        let uv = [0u8; 8];
+        let info = VideoFrameInfo {
+            width: 4,
+            height: 4,
+            pixel_format: PixelFormat::Nv12,
+            size_in_bytes: 24,
+            timestamp: 0,
+            frame_index: 0,
+            orientation: FrameOrientation::TopToBottom,
+            data_planes: [Some(&y), Some(&uv), None],
+            strides: [4, 4, 0],
+        };
+
+        assert_eq!(info.plane_ptr(0), Some((y.as_ptr(), 4, 16)));
+        assert_eq!(info.plane_ptr(1), Some((uv.as_ptr(), 4, 8)));
+        assert_eq!(info.plane_ptr(2), None);
+        assert_eq!(info.plane_ptr(3), None);
+    }
+
+    #[test]
+    fn test_video_frame_debug_shows_frame_info() {
+        let mut pixels = vec![0u8; 12];
+        let info = sys::CcapVideoFrameInfo {
+            data: [
+                pixels.as_mut_ptr(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            ],
+            stride: [6, 0, 0],
+            pixelFormat: PixelFormat::Rgb24.to_c_enum(),
+            width: 2,
+            height: 2,
+            sizeInBytes: 12,
+            frameIndex: 7,
+            ..Default::default()
+        };
+        let frame = VideoFrame::from_info_for_test(info);
+        assert_eq!(
+            format!("{:?}", frame),
+            "VideoFrame { index: 7, width: 2, height: 2, pixel_format: Rgb24, data_size: 12, \
+             owns_frame: false }"
+        );
+    }
+
+    #[test]
+    fn test_flip_upright_copies_instead_of_writing_native_planes() {
+        // 2x4 NV12 stored bottom-up: Y rows 3,2,1,0 and UV rows 1,0
+        let mut y = vec![3u8, 3, 2, 2, 1, 1, 0, 0];
+        let mut uv = vec![11u8, 11, 10, 10];
+        let info = sys::CcapVideoFrameInfo {
+            data: [y.as_mut_ptr(), uv.as_mut_ptr(), std::ptr::null_mut()],
+            stride: [2, 2, 0],
+            pixelFormat: PixelFormat::Nv12.to_c_enum(),
+            width: 2,
+            height: 4,
+            sizeInBytes: 12,
+            orientation: sys::CcapFrameOrientation_CCAP_FRAME_ORIENTATION_BOTTOM_TO_TOP,
+            ..Default::default()
+        };
+
+        let mut frame = VideoFrame::from_info_for_test(info);
+        assert!(frame.flip_upright());
+        assert!(!frame.flip_upright());
+
+        let flipped = frame.info().unwrap();
+        assert_eq!(flipped.orientation, FrameOrientation::TopToBottom);
+        assert_eq!(
+            flipped.data_planes[0],
+            Some(&[0u8, 0, 1, 1, 2, 2, 3, 3][..])
+        );
+        assert_eq!(flipped.data_planes[1], Some(&[10u8, 10, 11, 11][..]));
+        assert_eq!(frame.data().unwrap(), &[0, 0, 1, 1, 2, 2, 3, 3]);
+        assert_eq!(y, vec![3, 3, 2, 2, 1, 1, 0, 0]);
+        assert_eq!(uv, vec![11, 11, 10, 10]);
+    }
+
+    #[test]
+    fn test_to_contiguous_i420_strips_stride_padding() {
+        let (width, height) = (4u32, 4u32);
+        // Y rows are 4 bytes of data followed by 4 bytes of padding (0xEE).
+        let mut y_plane = vec![0xEEu8; 8 * 4];
+        for row in 0..4 {
+            for col in 0..4 {
+                y_plane[row * 8 + col] = (row * 4 + col) as u8;
+            }
+        }
+        let u_plane = vec![100, 101, 0xEE, 0xEE, 102, 103, 0xEE, 0xEE];
+        let v_plane = vec![200, 201, 0xEE, 0xEE, 202, 203, 0xEE, 0xEE];
+
+        let frame = OwnedFrame::from_planes(
+            width,
+            height,
+            PixelFormat::I420,
+            vec![y_plane, u_plane, v_plane],
+            &[8, 4, 4],
+        )
+        .unwrap();
+
+        let packed = frame.to_contiguous_i420().unwrap();
+        assert_eq!(packed.len(), (width * height * 3 / 2) as usize);
+
+        let y_size = (width * height) as usize;
+        let expected_y: Vec<u8> = (0..16).collect();
+        assert_eq!(&packed[..y_size], &expected_y[..]);
+        assert_eq!(&packed[y_size..y_size + 4], &[100, 101, 102, 103]);
+        assert_eq!(&packed[y_size + 4..], &[200, 201, 202, 203]);
+    }
+
+    #[test]
+    fn test_to_contiguous_from_nv12() {
+        let y_plane = vec![16u8; 4 * 2];
+        let uv_plane = vec![1, 2, 3, 4];
+        let frame =
+            OwnedFrame::from_planes(4, 2, PixelFormat::Nv12, vec![y_plane, uv_plane], &[4, 4])
+                .unwrap();
+
+        let i420 = frame.to_contiguous_i420().unwrap();
+        assert_eq!(&i420[8..], &[1, 3, 2, 4]);
+
+        let nv12 = frame.to_contiguous_nv12().unwrap();
+        assert_eq!(nv12.len(), 12);
+        assert_eq!(&nv12[8..], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_to_contiguous_rejects_rgb() {
+        let frame =
+            OwnedFrame::from_planes(2, 2, PixelFormat::Rgb24, vec![vec![0u8; 12]], &[6]).unwrap();
+        assert!(matches!(
+            frame.to_contiguous_i420(),
+            Err(CcapError::NotSupported)
+        ));
+    }
+
+    #[test]
+    fn test_ascii_preview_split_frame() {
+        let (width, height) = (16u32, 8u32);
+        let mut rgb = vec![0u8; (width * height * 3) as usize];
+        for row in rgb.chunks_mut((width * 3) as usize) {
+            for value in &mut row[(width * 3 / 2) as usize..] {
+                *value = 255;
+            }
+        }
+        let frame =
+            OwnedFrame::from_planes(width, height, PixelFormat::Rgb24, vec![rgb], &[width * 3])
+                .unwrap();
+
+        let preview = frame.ascii_preview(8).unwrap();
+        let lines: Vec<&str> = preview.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            assert_eq!(line.len(), 8);
+            assert!(line[..4].chars().all(|c| c == ' '));
+            assert!(line[4..].chars().all(|c| c == '@'));
+        }
+    }
+
+    #[test]
+    fn test_thumbnail_fits_max_dim() {
+        let (width, height) = (64, 36);
+        let rgb = vec![200u8; (width * height * 3) as usize];
+        let frame =
+            OwnedFrame::from_planes(width, height, PixelFormat::Rgb24, vec![rgb], &[width * 3])
+                .unwrap();
+
+        let thumb = frame.thumbnail(16).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (16, 9));
+        assert_eq!(thumb.pixel_format(), PixelFormat::Rgba32);
+        assert!(thumb
+            .plane(0)
+            .unwrap()
+            .chunks_exact(4)
+            .all(|pixel| pixel == [200, 200, 200, 255]));
+
+        // Small frames aren't upscaled
+        let thumb = frame.thumbnail(100).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (width, height));
+        assert!(frame.thumbnail(0).is_err());
+    }
+
+    #[test]
+    fn test_crc32_matches_content() {
+        let frame = |stride: u32, data: &[u8]| {
+            OwnedFrame::from_planes(3, 1, PixelFormat::Rgb24, vec![data.to_vec()], &[stride])
+                .unwrap()
+        };
+        let check = frame(9, b"123456789");
+        // Standard CRC-32 check value
+        assert_eq!(check.crc32(), 0xCBF4_3926);
+        assert_eq!(frame(12, b"123456789pad").crc32(), check.crc32());
+        assert_ne!(frame(9, b"123456780").crc32(), check.crc32());
+    }
+
+    #[test]
+    fn test_content_eq_ignores_stride_padding() {
+        let tight = OwnedFrame::from_planes(
+            2,
+            2,
+            PixelFormat::Rgb24,
+            vec![vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]],
+            &[6],
+        )
+        .unwrap();
+        let padded = OwnedFrame::from_planes(
+            2,
+            2,
+            PixelFormat::Rgb24,
+            vec![vec![
+                1, 2, 3, 4, 5, 6, 0xEE, 0xEE, 7, 8, 9, 10, 11, 12, 0x55, 0x55,
+            ]],
+            &[8],
+        )
+        .unwrap()
+        .with_frame_index(42);
+
+        assert!(tight.content_eq(&padded));
+        assert_eq!(tight.content_hash(), padded.content_hash());
+
+        let different = OwnedFrame::from_planes(
+            2,
+            2,
+            PixelFormat::Bgr24,
+            vec![vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]],
+            &[6],
+        )
+        .unwrap();
+        assert!(!tight.content_eq(&different));
+        assert_ne!(tight.content_hash(), different.content_hash());
+    }
+
+    #[test]
+    fn test_frame_ref_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<FrameRef<'static>>();
+    }
+
+    #[test]
+    fn test_owned_frame_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<OwnedFrame>();
+
+        let frame = OwnedFrame::from_planes(2, 1, PixelFormat::Rgb24, vec![vec![7u8; 6]], &[6])
+            .unwrap()
+            .with_frame_index(3);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let consumer = std::thread::spawn(move || {
+            let frame: OwnedFrame = receiver.recv().unwrap();
+            (frame.frame_index(), frame.plane(0).unwrap().to_vec())
+        });
+        sender.send(frame).unwrap();
+
+        let (index, data) = consumer.join().unwrap();
+        assert_eq!(index, 3);
+        assert_eq!(data, vec![7u8; 6]);
+    }
+
+    #[test]
+    fn test_capability_table_lists_modes() {
+        let device = DeviceInfo {
+            name: "Mock Camera".to_string(),
+            supported_pixel_formats: vec![PixelFormat::Nv12, PixelFormat::Yuyv],
+            supported_resolutions: vec![
+                Resolution {
+                    width: 640,
+                    height: 480,
+                },
+                Resolution {
+                    width: 1920,
+                    height: 1080,
+                },
+            ],
+            name_bytes: Vec::new(),
+        };
+
+        let table = device.capability_table();
+        assert!(table.starts_with("Device: Mock Camera\n"));
+        assert!(table.contains("  1920x1080  | NV12, YUYV\n"));
+        assert!(table.contains("  640x480    | NV12, YUYV\n"));
+    }
+
+    #[test]
+    fn test_resolutions_for_format() {
+        let resolutions = vec![
+            Resolution {
+                width: 640,
+                height: 480,
+            },
+            Resolution {
+                width: 1920,
+                height: 1080,
+            },
+        ];
+        let device = DeviceInfo {
+            name: "Mock Camera".to_string(),
+            supported_pixel_formats: vec![PixelFormat::Nv12, PixelFormat::Yuyv],
+            supported_resolutions: resolutions.clone(),
+            name_bytes: Vec::new(),
+        };
+
+        assert_eq!(
+            device.resolutions_for_format(PixelFormat::Nv12),
+            resolutions
+        );
+        assert_eq!(
+            device.resolutions_for_format(PixelFormat::Yuyv),
+            resolutions
+        );
+        assert!(device.resolutions_for_format(PixelFormat::I420).is_empty());
+        assert!(device
+            .resolutions_for_format(PixelFormat::Unknown)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_best_format_for_use_cases() {
+        let device = DeviceInfo {
+            name: "Mock Camera".to_string(),
+            supported_pixel_formats: vec![
+                PixelFormat::Rgb24,
+                PixelFormat::Bgra32,
+                PixelFormat::Nv12,
+            ],
+            supported_resolutions: Vec::new(),
+            name_bytes: Vec::new(),
+        };
+
+        assert_eq!(
+            device.best_format_for(FormatUseCase::Encoding),
+            Some(PixelFormat::Nv12)
+        );
+        assert_eq!(
+            device.best_format_for(FormatUseCase::LowCpu),
+            Some(PixelFormat::Nv12)
+        );
+        assert_eq!(
+            device.best_format_for(FormatUseCase::LowLatency),
+            Some(PixelFormat::Nv12)
+        );
+        assert_eq!(
+            device.best_format_for(FormatUseCase::DirectDisplay),
+            Some(PixelFormat::Bgra32)
+        );
+
+        let unknown = DeviceInfo {
+            supported_pixel_formats: vec![PixelFormat::Unknown],
+            ..device
+        };
+        assert_eq!(unknown.best_format_for(FormatUseCase::Encoding), None);
+    }
+
+    #[test]
+    fn test_copy_from_planes_layouts() {
+        // 4x2 NV12 with 8-byte strides: 4 bytes of padding per row
+        let y_plane = [1u8, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0];
+        let uv_plane = [9u8, 10, 11, 12, 0, 0, 0, 0];
+        let planes: [&[u8]; 2] = [&y_plane, &uv_plane];
+
+        let native = OwnedFrame::copy_from_planes(
+            4,
+            2,
+            PixelFormat::Nv12,
+            &planes,
+            &[8, 8],
+            OwnedFrameLayout::Native,
+        )
+        .unwrap();
+        assert_eq!(native.strides(), [8, 8, 0]);
+        assert_eq!(native.plane(0).unwrap().len(), 16);
+        assert_eq!(native.plane(1).unwrap().len(), 8);
+
+        let packed = OwnedFrame::copy_from_planes(
+            4,
+            2,
+            PixelFormat::Nv12,
+            &planes,
+            &[8, 8],
+            OwnedFrameLayout::Packed,
+        )
+        .unwrap();
+        assert_eq!(packed.strides(), [4, 4, 0]);
+        assert_eq!(packed.plane(0).unwrap(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(packed.plane(1).unwrap(), &[9, 10, 11, 12]);
+        assert!(packed.content_eq(&native));
+    }
+
+    #[test]
+    fn test_to_rgb24_reorders_channels() {
+        let bgra = vec![1u8, 2, 3, 255, 4, 5, 6, 255, 0xEE, 0xEE];
+        let frame = OwnedFrame::from_planes(2, 1, PixelFormat::Bgra32, vec![bgra], &[10]).unwrap();
+        assert_eq!(frame.to_rgb24().unwrap(), vec![3, 2, 1, 6, 5, 4]);
+    }
+
+    #[test]
+    fn test_to_rgba8_unpadded_flips_bottom_to_top() {
+        // 3x2 RGB24 with 3 bytes of stride padding per row
+        let rgb = vec![
+            1, 1, 1, 2, 2, 2, 3, 3, 3, 0, 0, 0, //
+            4, 4, 4, 5, 5, 5, 6, 6, 6, 0, 0, 0,
+        ];
+        let frame = OwnedFrame::from_planes(3, 2, PixelFormat::Rgb24, vec![rgb], &[12])
+            .unwrap()
+            .with_orientation(FrameOrientation::BottomToTop);
+        let (rgba, width, height) = frame.to_rgba8_unpadded().unwrap();
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(rgba.len(), (width * height * 4) as usize);
+        assert_eq!(&rgba[..8], &[4, 4, 4, 255, 5, 5, 5, 255]);
+        assert_eq!(&rgba[12..16], &[1, 1, 1, 255]);
+    }
+
+    #[test]
+    fn test_to_rgb24_oriented_flips_bottom_to_top_sources() {
+        const SIZE: u32 = 16;
+        let pixel = |rgb: &[u8], x: u32, y: u32| {
+            let offset = ((y * SIZE + x) * 3) as usize;
+            [rgb[offset], rgb[offset + 1], rgb[offset + 2]]
+        };
+
+        for format in [
+            PixelFormat::Rgb24,
+            PixelFormat::Bgra32,
+            PixelFormat::Nv12,
+            PixelFormat::I420,
+            PixelFormat::Yuyv,
+        ] {
+            // The top-left checkerboard square is white in memory, the one below it black.
+            let frame = crate::TestPattern::Checkerboard
+                .frame(format, SIZE, SIZE)
+                .unwrap()
+                .with_orientation(FrameOrientation::BottomToTop);
+
+            let upright = frame
+                .to_rgb24_oriented(FrameOrientation::TopToBottom)
+                .unwrap();
+            assert!(pixel(&upright, 0, 0)[0] < 32, "{:?}", format);
+            assert!(pixel(&upright, 0, SIZE - 1)[0] > 224, "{:?}", format);
+
+            let unchanged = frame
+                .to_rgb24_oriented(FrameOrientation::BottomToTop)
+                .unwrap();
+            assert_eq!(unchanged, frame.to_rgb24().unwrap(), "{:?}", format);
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_to_rgb_image_dimensions() {
+        let frame =
+            OwnedFrame::from_planes(2, 2, PixelFormat::Rgb24, vec![vec![9u8; 12]], &[6]).unwrap();
+        let image = frame.to_rgb_image().unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(image.get_pixel(1, 1).0, [9, 9, 9]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_copy_into_image_reuses_target() {
+        let mut target = image::RgbaImage::new(1, 1);
+        for format in [PixelFormat::Nv12, PixelFormat::Bgra32] {
+            let frame = crate::TestPattern::ColorBars
+                .frame(format, 16, 8)
+                .unwrap()
+                .with_orientation(FrameOrientation::BottomToTop);
+            frame.copy_into_image(&mut target).unwrap();
+            let buffer = target.as_raw().as_ptr();
+
+            frame.copy_into_image(&mut target).unwrap();
+            assert_eq!(target.as_raw().as_ptr(), buffer, "{:?}", format);
+            assert_eq!(target.dimensions(), (16, 8));
+            assert_eq!(target.as_raw(), &frame.to_rgba8_unpadded().unwrap().0);
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_video_frame_copy_into_image_reads_native_planes() {
+        let owned = crate::TestPattern::ColorBars
+            .frame(PixelFormat::Nv12, 16, 8)
+            .unwrap()
+            .with_orientation(FrameOrientation::BottomToTop);
+        let (mut y, mut uv) = (owned.planes[0].clone(), owned.planes[1].clone());
+        let info = sys::CcapVideoFrameInfo {
+            data: [y.as_mut_ptr(), uv.as_mut_ptr(), std::ptr::null_mut()],
+            stride: [owned.strides[0], owned.strides[1], 0],
+            pixelFormat: PixelFormat::Nv12.to_c_enum(),
+            width: 16,
+            height: 8,
+            sizeInBytes: (y.len() + uv.len()) as u32,
+            orientation: sys::CcapFrameOrientation_CCAP_FRAME_ORIENTATION_BOTTOM_TO_TOP,
+            ..Default::default()
+        };
+
+        let frame = VideoFrame::from_info_for_test(info);
+        let mut target = image::RgbaImage::new(1, 1);
+        frame.copy_into_image(&mut target).unwrap();
+        assert_eq!(target.dimensions(), (16, 8));
+        assert_eq!(target.as_raw(), &owned.to_rgba8_unpadded().unwrap().0);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_to_jpeg_encodes_and_validates_quality() {
+        let frame = crate::TestPattern::ColorBars
+            .frame(PixelFormat::Nv12, 64, 32)
+            .unwrap();
+        let jpeg = frame.to_jpeg(85).unwrap();
+        assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+
+        for quality in [0, 101] {
+            assert!(matches!(
+                frame.to_jpeg(quality),
+                Err(CcapError::InvalidParameter(_))
+            ));
+        }
+    }
+}
@@ -16,22 +16,85 @@ pub mod sys {
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 }
 
+mod broadcast;
 mod convert;
 mod error;
 mod frame;
+#[cfg(feature = "mjpeg")]
+mod mjpeg;
+mod pattern;
+mod pool;
+mod prefetch;
+pub mod prelude;
 mod provider;
+mod sequence;
 mod types;
 mod utils;
 
 // Public re-exports
+pub use broadcast::{FrameBroadcast, FrameReceiver, FrameSubscriptions};
 pub use convert::Convert;
 pub use error::{CcapError, Result};
 pub use frame::*;
-pub use provider::Provider;
+#[cfg(feature = "mjpeg")]
+pub use mjpeg::serve_mjpeg;
+pub use pattern::{TestPattern, CHECKERBOARD_SQUARE, COLOR_BARS};
+pub use pool::ConvertPool;
+pub use provider::{CcapEvent, ErrorCallbackGuard, Provider, ProviderBuilder};
+pub use sequence::{GapRange, SequenceChecker, SequenceReport};
 pub use types::*;
 pub use utils::{LogLevel, Utils};
 
+/// Maximum number of devices reported by device discovery
+pub const MAX_DEVICES: usize = sys::CCAP_MAX_DEVICES as usize;
+
+/// Maximum length in bytes of a device name, including the terminating NUL
+pub const MAX_DEVICE_NAME_LENGTH: usize = sys::CCAP_MAX_DEVICE_NAME_LENGTH as usize;
+
+/// Maximum number of pixel formats listed in a [`DeviceInfo`]
+pub const MAX_PIXEL_FORMATS: usize = sys::CCAP_MAX_PIXEL_FORMATS as usize;
+
+/// Maximum number of resolutions listed in a [`DeviceInfo`]
+pub const MAX_RESOLUTIONS: usize = sys::CCAP_MAX_RESOLUTIONS as usize;
+
+/// Initialize the library: enable every color conversion backend this CPU supports.
+///
+/// Optional, idempotent and thread-safe; see [`Convert::auto_init`].
+pub fn init() -> Result<()> {
+    Convert::auto_init()
+}
+
 /// Get library version string
 pub fn version() -> Result<String> {
     Provider::version()
 }
+
+/// The camera opened when no device is selected, or `None` without any camera
+/// (see [`Provider::default_device`]).
+pub fn default_device() -> Result<Option<DeviceInfo>> {
+    Provider::default_device()
+}
+
+/// Get available camera devices from a process-wide cache (see [`Provider::cached_devices`]).
+///
+/// The cached list can be stale after hot-plug events; call [`refresh_devices`] to re-enumerate.
+pub fn cached_devices() -> Result<std::sync::Arc<Vec<DeviceInfo>>> {
+    Provider::cached_devices()
+}
+
+/// Invalidate the device cache and re-enumerate devices (see [`Provider::refresh_devices`]).
+pub fn refresh_devices() -> Result<std::sync::Arc<Vec<DeviceInfo>>> {
+    Provider::refresh_devices()
+}
+
+/// Describe the capabilities of every camera as text tables (see [`DeviceInfo::capability_table`]).
+///
+/// Useful for pasting into bug reports.
+pub fn dump_all_capabilities() -> Result<String> {
+    let devices = Provider::get_devices()?;
+    Ok(devices
+        .iter()
+        .map(DeviceInfo::capability_table)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
@@ -0,0 +1,303 @@
+//! Minimal MJPEG-over-HTTP streaming (requires the `mjpeg` feature)
+
+use crate::{
+    error::*,
+    frame::OwnedFrame,
+    provider::{validate_frame_rate, Provider},
+};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Multipart boundary separating JPEG parts in the stream
+const BOUNDARY: &str = "ccapframe";
+
+/// JPEG quality used for streamed frames
+const JPEG_QUALITY: u8 = 80;
+
+/// Longest pause between streamed frames, so tiny frame rates can't overflow a `Duration`
+const MAX_FRAME_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Most recently encoded frame, shared between the capture loop and client threads
+#[derive(Default)]
+struct LatestJpeg {
+    slot: Mutex<JpegSlot>,
+    updated: Condvar,
+}
+
+#[derive(Default)]
+struct JpegSlot {
+    sequence: u64,
+    jpeg: Option<Arc<Vec<u8>>>,
+    closed: bool,
+}
+
+impl LatestJpeg {
+    fn publish(&self, jpeg: Vec<u8>) {
+        let mut slot = self.slot.lock().unwrap_or_else(PoisonError::into_inner);
+        slot.sequence += 1;
+        slot.jpeg = Some(Arc::new(jpeg));
+        self.updated.notify_all();
+    }
+
+    /// End the stream: waiting and future [`LatestJpeg::wait_newer`] calls return `None`
+    fn close(&self) {
+        let mut slot = self.slot.lock().unwrap_or_else(PoisonError::into_inner);
+        slot.closed = true;
+        self.updated.notify_all();
+    }
+
+    /// Wait for a frame newer than `seen`, returning it with its sequence number, or `None`
+    /// once the stream is closed
+    fn wait_newer(&self, seen: u64) -> Option<(u64, Arc<Vec<u8>>)> {
+        let mut slot = self.slot.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            if slot.closed {
+                return None;
+            }
+            if let Some(jpeg) = slot.jpeg.as_ref().filter(|_| slot.sequence > seen) {
+                return Some((slot.sequence, Arc::clone(jpeg)));
+            }
+            slot = self
+                .updated
+                .wait(slot)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+/// Encode a frame as JPEG
+fn encode_jpeg(frame: &OwnedFrame) -> Result<Vec<u8>> {
+    frame.to_jpeg(JPEG_QUALITY)
+}
+
+/// Header preceding each JPEG part of the multipart stream
+fn part_header(jpeg_len: usize) -> String {
+    format!(
+        "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+        BOUNDARY, jpeg_len
+    )
+}
+
+/// Stream frames to one client until it disconnects or the stream is closed
+fn stream_to_client(request: tiny_http::Request, latest: &LatestJpeg) {
+    let mut writer = request.into_writer();
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary={}\r\n\
+         Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        BOUNDARY
+    );
+    if writer.write_all(header.as_bytes()).is_err() {
+        return;
+    }
+
+    let mut seen = 0;
+    while let Some((sequence, jpeg)) = latest.wait_newer(seen) {
+        seen = sequence;
+        let sent = writer
+            .write_all(part_header(jpeg.len()).as_bytes())
+            .and_then(|_| writer.write_all(&jpeg))
+            .and_then(|_| writer.write_all(b"\r\n"))
+            .and_then(|_| writer.flush());
+        if sent.is_err() {
+            return;
+        }
+    }
+
+    // The closing delimiter ends the multipart body, so the client stops waiting for parts.
+    let closing = format!("--{}--\r\n", BOUNDARY);
+    let _ = writer
+        .write_all(closing.as_bytes())
+        .and_then(|_| writer.flush());
+}
+
+/// Serve the camera as an MJPEG stream over HTTP.
+///
+/// Starts a small HTTP server on `addr`. Any `GET` request (e.g. `http://<addr>/` opened in a
+/// browser tab or `<img src>`) receives a `multipart/x-mixed-replace` response where each
+/// part is one JPEG-encoded frame. Frames are grabbed and encoded once at up to `fps` frames
+/// per second and shared by all connected clients.
+///
+/// Capture is started if needed. This function blocks the calling thread and only returns
+/// when the server can't be started or capturing fails. In the latter case the server is
+/// shut down first: connected clients receive the closing multipart boundary and `addr` is
+/// released.
+///
+/// # Errors
+///
+/// Returns `CcapError::InvalidParameter` for a non-positive, NaN or infinite `fps`,
+/// `CcapError::InternalError` if the server can't bind to `addr`, or the capture error that
+/// stopped streaming.
+pub fn serve_mjpeg(mut provider: Provider, addr: SocketAddr, fps: f64) -> Result<()> {
+    let interval = frame_interval(fps)?;
+
+    let server = tiny_http::Server::http(addr).map_err(|e| {
+        CcapError::InternalError(format!("failed to start HTTP server on {}: {}", addr, e))
+    })?;
+    if !provider.is_started() {
+        provider.start()?;
+    }
+
+    serve_frames(server, interval, || match provider.grab_frame(1000)? {
+        Some(frame) => encode_jpeg(&frame.to_owned_frame()?).map(Some),
+        None => Ok(None),
+    })
+}
+
+/// Stream the JPEGs produced by `next_jpeg` to every client of `server` until it fails,
+/// then shut the server down and wait for the client threads to finish
+fn serve_frames(
+    server: tiny_http::Server,
+    interval: Duration,
+    mut next_jpeg: impl FnMut() -> Result<Option<Vec<u8>>>,
+) -> Result<()> {
+    let server = Arc::new(server);
+    let latest = Arc::new(LatestJpeg::default());
+    let acceptor = {
+        let server = Arc::clone(&server);
+        let latest = Arc::clone(&latest);
+        thread::spawn(move || {
+            let mut clients = Vec::new();
+            for request in server.incoming_requests() {
+                let latest = Arc::clone(&latest);
+                clients.push(thread::spawn(move || stream_to_client(request, &latest)));
+                clients.retain(|client| !client.is_finished());
+            }
+            for client in clients {
+                let _ = client.join();
+            }
+        })
+    };
+
+    let result = loop {
+        let started = Instant::now();
+        match next_jpeg() {
+            Ok(Some(jpeg)) => latest.publish(jpeg),
+            Ok(None) => {}
+            Err(e) => break Err(e),
+        }
+        if let Some(remaining) = interval.checked_sub(started.elapsed()) {
+            thread::sleep(remaining);
+        }
+    };
+
+    latest.close();
+    server.unblock();
+    let _ = acceptor.join();
+    result
+}
+
+/// Time between frames at `fps`, capped at [`MAX_FRAME_INTERVAL`]
+fn frame_interval(fps: f64) -> Result<Duration> {
+    validate_frame_rate(fps)?;
+    Ok(Duration::from_secs_f64(
+        (1.0 / fps).min(MAX_FRAME_INTERVAL.as_secs_f64()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PixelFormat;
+
+    #[test]
+    fn test_frame_interval_validates_fps() {
+        assert_eq!(frame_interval(4.0).unwrap(), Duration::from_millis(250));
+        assert_eq!(frame_interval(1e-300).unwrap(), MAX_FRAME_INTERVAL);
+        for fps in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(
+                matches!(frame_interval(fps), Err(CcapError::InvalidParameter(_))),
+                "{}",
+                fps
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_jpeg_produces_jpeg_markers() {
+        let frame = OwnedFrame::from_planes(
+            8,
+            8,
+            PixelFormat::Rgb24,
+            vec![vec![128u8; 8 * 8 * 3]],
+            &[24],
+        )
+        .unwrap();
+        let jpeg = encode_jpeg(&frame).unwrap();
+        assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+        assert_eq!(&jpeg[jpeg.len() - 2..], &[0xFF, 0xD9]);
+    }
+
+    #[test]
+    fn test_serve_frames_shuts_down_when_capture_fails() {
+        use std::io::Read;
+        use std::net::{TcpListener, TcpStream};
+        use std::sync::mpsc;
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let (connected, streaming) = mpsc::channel();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(10)))
+                .unwrap();
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            let mut received = vec![0u8; 1];
+            stream.read_exact(&mut received).unwrap();
+            connected.send(()).unwrap();
+            // Read until the server ends the multipart body; a timeout fails the test.
+            let mut buffer = [0u8; 4096];
+            while !received.ends_with(b"--ccapframe--\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                assert!(read > 0, "connection closed before the stream ended");
+                received.extend_from_slice(&buffer[..read]);
+            }
+        });
+
+        let result = serve_frames(server, Duration::from_millis(1), || {
+            match streaming.try_recv() {
+                Ok(()) => Err(CcapError::DeviceOpenFailed),
+                Err(_) => Ok(Some(vec![0xFF, 0xD8, 0xFF, 0xD9])),
+            }
+        });
+        assert!(matches!(result, Err(CcapError::DeviceOpenFailed)));
+        client.join().unwrap();
+        // The listener is closed by tiny_http's accept thread shortly after the server drops.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while TcpListener::bind(addr).is_err() {
+            assert!(Instant::now() < deadline, "{} is still bound", addr);
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_latest_jpeg_close_wakes_waiting_clients() {
+        let latest = Arc::new(LatestJpeg::default());
+        let waiter = {
+            let latest = Arc::clone(&latest);
+            thread::spawn(move || latest.wait_newer(0))
+        };
+        latest.close();
+        assert!(waiter.join().unwrap().is_none());
+        latest.publish(vec![1]);
+        assert!(latest.wait_newer(0).is_none());
+    }
+
+    #[test]
+    fn test_latest_jpeg_returns_newer_frames() {
+        let latest = LatestJpeg::default();
+        latest.publish(vec![1]);
+        latest.publish(vec![2]);
+        let (sequence, jpeg) = latest.wait_newer(0).unwrap();
+        assert_eq!((sequence, jpeg.as_slice()), (2, &[2u8][..]));
+        assert_eq!(
+            part_header(3),
+            "--ccapframe\r\nContent-Type: image/jpeg\r\nContent-Length: 3\r\n\r\n"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Deterministic synthetic frames with known pixel values
+
+use crate::error::{CcapError, Result};
+use crate::frame::OwnedFrame;
+use crate::types::{ColorRange, PixelFormat};
+
+/// Colors of [`TestPattern::ColorBars`], left to right
+pub const COLOR_BARS: [[u8; 3]; 8] = [
+    [255, 255, 255], // white
+    [255, 255, 0],   // yellow
+    [0, 255, 255],   // cyan
+    [0, 255, 0],     // green
+    [255, 0, 255],   // magenta
+    [255, 0, 0],     // red
+    [0, 0, 255],     // blue
+    [0, 0, 0],       // black
+];
+
+/// Side length in pixels of one [`TestPattern::Checkerboard`] square
+pub const CHECKERBOARD_SQUARE: u32 = 8;
+
+/// Synthetic image content with mathematically defined pixel values
+///
+/// Used with [`TestPattern::frame`] to build frames whose content is known exactly, so a
+/// conversion or encoding pipeline can be checked without a camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern {
+    /// Eight vertical full-intensity bars in the order of [`COLOR_BARS`]. Pixel `x` belongs
+    /// to bar `x * 8 / width`.
+    ColorBars,
+    /// Horizontal gray ramp from black to white: every channel of pixel `x` is
+    /// `x * 255 / (width - 1)` (0 for a one pixel wide frame).
+    Gradient,
+    /// Black and white squares of [`CHECKERBOARD_SQUARE`] pixels, white in the top-left
+    /// corner: pixel `(x, y)` is white when `x / 8 + y / 8` is even.
+    Checkerboard,
+}
+
+impl TestPattern {
+    /// RGB color of pixel `(x, y)` in a frame `width` pixels wide
+    pub fn rgb_at(self, x: u32, y: u32, width: u32) -> [u8; 3] {
+        match self {
+            TestPattern::ColorBars => {
+                let bar = (x as u64 * 8 / width.max(1) as u64).min(7);
+                COLOR_BARS[bar as usize]
+            }
+            TestPattern::Gradient => {
+                let value = if width > 1 {
+                    (x as u64 * 255 / (width as u64 - 1)) as u8
+                } else {
+                    0
+                };
+                [value; 3]
+            }
+            TestPattern::Checkerboard => {
+                let white = (x / CHECKERBOARD_SQUARE + y / CHECKERBOARD_SQUARE) % 2 == 0;
+                [if white { 255 } else { 0 }; 3]
+            }
+        }
+    }
+
+    /// Render the pattern as a tightly-strided frame in `format`.
+    ///
+    /// RGB formats hold the values from [`TestPattern::rgb_at`] exactly, with opaque alpha.
+    /// YUV formats encode them with BT.601 coefficients, using video range for the plain
+    /// formats and full range for the `F` variants. Subsampled chroma is taken from the
+    /// top-left pixel of each chroma block.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` for a zero dimension and
+    /// `CcapError::NotSupported` for [`PixelFormat::Unknown`].
+    pub fn frame(self, format: PixelFormat, width: u32, height: u32) -> Result<OwnedFrame> {
+        if width == 0 || height == 0 {
+            return Err(CcapError::InvalidParameter(format!(
+                "pattern size must be non-zero, got {}x{}",
+                width, height
+            )));
+        }
+
+        let rgb = |x: u32, y: u32| self.rgb_at(x, y, width);
+        let range = format.color_range().unwrap_or_default();
+        let yuv = |x: u32, y: u32| rgb_to_yuv(rgb(x, y), range);
+        let (w, h) = (width as usize, height as usize);
+        let (chroma_w, chroma_h) = ((w + 1) / 2, (h + 1) / 2);
+
+        let planes = match format {
+            PixelFormat::Unknown => return Err(CcapError::NotSupported),
+            PixelFormat::Rgb24 | PixelFormat::Bgr24 | PixelFormat::Rgba32 | PixelFormat::Bgra32 => {
+                let bgr = matches!(format, PixelFormat::Bgr24 | PixelFormat::Bgra32);
+                let alpha = matches!(format, PixelFormat::Rgba32 | PixelFormat::Bgra32);
+                let mut data = Vec::with_capacity(format.buffer_size(width, height));
+                for y in 0..height {
+                    for x in 0..width {
+                        let [r, g, b] = rgb(x, y);
+                        data.extend_from_slice(&if bgr { [b, g, r] } else { [r, g, b] });
+                        if alpha {
+                            data.push(255);
+                        }
+                    }
+                }
+                vec![data]
+            }
+            PixelFormat::Nv12 | PixelFormat::Nv12F | PixelFormat::I420 | PixelFormat::I420F => {
+                let luma = plane_from_fn(w, h, |x, y| yuv(x, y)[0]);
+                let u = plane_from_fn(chroma_w, chroma_h, |x, y| yuv(x * 2, y * 2)[1]);
+                let v = plane_from_fn(chroma_w, chroma_h, |x, y| yuv(x * 2, y * 2)[2]);
+                if matches!(format, PixelFormat::I420 | PixelFormat::I420F) {
+                    vec![luma, u, v]
+                } else {
+                    let uv = u.iter().zip(&v).flat_map(|(&u, &v)| [u, v]).collect();
+                    vec![luma, uv]
+                }
+            }
+            PixelFormat::Yuyv | PixelFormat::YuyvF | PixelFormat::Uyvy | PixelFormat::UyvyF => {
+                let uyvy = matches!(format, PixelFormat::Uyvy | PixelFormat::UyvyF);
+                let mut data = Vec::with_capacity(chroma_w * 4 * h);
+                for y in 0..height {
+                    for pair in 0..chroma_w as u32 {
+                        let [y0, u, v] = yuv(pair * 2, y);
+                        let y1 = yuv((pair * 2 + 1).min(width - 1), y)[0];
+                        data.extend_from_slice(&if uyvy { [u, y0, v, y1] } else { [y0, u, y1, v] });
+                    }
+                }
+                vec![data]
+            }
+        };
+
+        let strides: Vec<u32> = (0..format.num_planes())
+            .map(|plane| format.plane_row_bytes(width, plane) as u32)
+            .collect();
+        OwnedFrame::from_planes(width, height, format, planes, &strides)
+    }
+}
+
+/// `width` x `height` plane whose byte at `(x, y)` is `f(x, y)`
+fn plane_from_fn(width: usize, height: usize, f: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+    (0..height as u32)
+        .flat_map(|y| (0..width as u32).map(move |x| (x, y)))
+        .map(|(x, y)| f(x, y))
+        .collect()
+}
+
+/// Encode an RGB color as BT.601 Y, Cb, Cr
+fn rgb_to_yuv([r, g, b]: [u8; 3], range: ColorRange) -> [u8; 3] {
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let (y, cb, cr) = match range {
+        ColorRange::Full => (
+            0.299 * r + 0.587 * g + 0.114 * b,
+            128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b,
+            128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b,
+        ),
+        ColorRange::Limited => (
+            16.0 + (65.481 * r + 128.553 * g + 24.966 * b) / 255.0,
+            128.0 + (-37.797 * r - 74.203 * g + 112.0 * b) / 255.0,
+            128.0 + (112.0 * r - 93.786 * g - 18.214 * b) / 255.0,
+        ),
+    };
+    [y, cb, cr].map(|value| value.round().clamp(0.0, 255.0) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: u32 = 64;
+    const HEIGHT: u32 = 8;
+
+    /// Center pixel of each color bar
+    fn bar_centers() -> impl Iterator<Item = (usize, [u8; 3])> {
+        (0..8).map(|bar| ((bar * 8 + 4) as usize, COLOR_BARS[bar as usize]))
+    }
+
+    #[test]
+    fn test_color_bars_are_exact_in_rgb_formats() {
+        let frame = TestPattern::ColorBars
+            .frame(PixelFormat::Bgra32, WIDTH, HEIGHT)
+            .unwrap();
+        let data = frame.plane(0).unwrap();
+        for (x, [r, g, b]) in bar_centers() {
+            let offset = (3 * WIDTH as usize + x) * 4;
+            assert_eq!(&data[offset..offset + 4], &[b, g, r, 255], "bar at x={}", x);
+        }
+    }
+
+    #[test]
+    fn test_color_bars_convert_from_yuv_to_expected_rgb() {
+        for format in [
+            PixelFormat::Nv12,
+            PixelFormat::I420,
+            PixelFormat::Yuyv,
+            PixelFormat::Nv12F,
+        ] {
+            let rgb = TestPattern::ColorBars
+                .frame(format, WIDTH, HEIGHT)
+                .unwrap()
+                .to_rgb24()
+                .unwrap();
+            for (x, expected) in bar_centers() {
+                let offset = (2 * WIDTH as usize + x) * 3;
+                // The fixed-point converters round differently from the float encoder.
+                for (actual, expected) in rgb[offset..offset + 3].iter().zip(expected) {
+                    assert!(
+                        (*actual as i32 - expected as i32).abs() <= 8,
+                        "{:?} bar at x={}: got {:?}, want {:?}",
+                        format,
+                        x,
+                        &rgb[offset..offset + 3],
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gradient_and_checkerboard_values() {
+        assert_eq!(TestPattern::Gradient.rgb_at(0, 0, 256), [0; 3]);
+        assert_eq!(TestPattern::Gradient.rgb_at(255, 0, 256), [255; 3]);
+        assert_eq!(TestPattern::Gradient.rgb_at(0, 0, 1), [0; 3]);
+        assert_eq!(TestPattern::Checkerboard.rgb_at(7, 7, 64), [255; 3]);
+        assert_eq!(TestPattern::Checkerboard.rgb_at(8, 0, 64), [0; 3]);
+        assert_eq!(TestPattern::Checkerboard.rgb_at(8, 8, 64), [255; 3]);
+
+        let frame = TestPattern::Checkerboard
+            .frame(PixelFormat::Rgb24, 16, 16)
+            .unwrap();
+        assert_eq!(&frame.plane(0).unwrap()[8 * 3..8 * 3 + 3], &[0, 0, 0]);
+        assert!(matches!(
+            TestPattern::Gradient.frame(PixelFormat::Unknown, 4, 4),
+            Err(CcapError::NotSupported)
+        ));
+    }
+}
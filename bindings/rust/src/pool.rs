@@ -0,0 +1,207 @@
+//! Worker threads converting frames off the capture thread
+
+use crate::error::{CcapError, Result};
+use crate::frame::OwnedFrame;
+use crate::types::PixelFormat;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+type Job = (OwnedFrame, PixelFormat);
+
+/// Pool of worker threads converting frames to an RGB format
+///
+/// Converting high-resolution frames in the frame callback delays the return to the native
+/// layer, which backs up the camera queue and drops frames. Instead, [`ConvertPool::submit`]
+/// the frames from the callback and collect the converted frames from another thread with
+/// [`ConvertPool::recv`]. The pool is `Sync`, so it can be shared through an `Arc`.
+///
+/// Results arrive in completion order, which with several threads may differ from the
+/// submission order; use [`OwnedFrame::frame_index`] to restore it if needed.
+///
+/// # Sizing
+///
+/// One thread keeps up as long as a single conversion takes less than a frame interval,
+/// e.g. 16 ms at 60 fps. Add threads when conversions take longer, roughly
+/// `conversion time / frame interval` rounded up. More threads than CPU cores
+/// (see [`std::thread::available_parallelism`]) gain nothing, and leaving a core to the
+/// capture thread avoids starving it. If [`ConvertPool::pending`] keeps growing, the pool
+/// can't keep up and frames should be dropped before submitting.
+pub struct ConvertPool {
+    jobs: Option<Mutex<Sender<Job>>>,
+    results: Mutex<Receiver<Result<OwnedFrame>>>,
+    pending: AtomicUsize,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for ConvertPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConvertPool")
+            .field("threads", &self.workers.len())
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl ConvertPool {
+    /// Spawn a pool with `threads` worker threads (minimum 1)
+    pub fn new(threads: usize) -> Self {
+        let (job_sender, job_receiver) = mpsc::channel::<Job>();
+        let (result_sender, result_receiver) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let jobs = Arc::clone(&job_receiver);
+                let results = result_sender.clone();
+                thread::spawn(move || loop {
+                    // Only one idle worker waits on the queue at a time; the lock is released
+                    // before converting.
+                    let job = jobs.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                    let Ok((frame, format)) = job else {
+                        return;
+                    };
+                    if results.send(frame.convert_to(format)).is_err() {
+                        return;
+                    }
+                })
+            })
+            .collect();
+
+        ConvertPool {
+            jobs: Some(Mutex::new(job_sender)),
+            results: Mutex::new(result_receiver),
+            pending: AtomicUsize::new(0),
+            workers,
+        }
+    }
+
+    /// Queue `frame` for conversion to `format` (RGB24, BGR24, RGBA32 or BGRA32).
+    ///
+    /// Returns immediately. The converted frame is top-down and tightly packed, with the
+    /// source's timestamp and frame index. A failed conversion is delivered as an `Err`
+    /// result, e.g. `CcapError::NotSupported` for another target format.
+    pub fn submit(&self, frame: OwnedFrame, format: PixelFormat) -> Result<()> {
+        let jobs = self
+            .jobs
+            .as_ref()
+            .expect("job sender is only taken on drop");
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        let sent = jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .send((frame, format));
+        if sent.is_err() {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+            return Err(CcapError::InternalError(
+                "convert pool workers have stopped".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Wait for the next converted frame. Returns `None` if nothing is pending.
+    pub fn recv(&self) -> Option<Result<OwnedFrame>> {
+        if self.pending() == 0 {
+            return None;
+        }
+        let result = self.lock_results().recv().ok()?;
+        self.pending.fetch_sub(1, Ordering::AcqRel);
+        Some(result)
+    }
+
+    /// Wait up to `timeout` for the next converted frame
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Result<OwnedFrame>> {
+        let result = match self.lock_results().recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(_) => return None,
+        };
+        self.pending.fetch_sub(1, Ordering::AcqRel);
+        Some(result)
+    }
+
+    /// Take a converted frame if one is ready, without waiting
+    pub fn try_recv(&self) -> Option<Result<OwnedFrame>> {
+        let result = match self.lock_results().try_recv() {
+            Ok(result) => result,
+            Err(_) => return None,
+        };
+        self.pending.fetch_sub(1, Ordering::AcqRel);
+        Some(result)
+    }
+
+    /// Frames submitted whose results haven't been received yet
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// Number of worker threads
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    fn lock_results(&self) -> std::sync::MutexGuard<'_, Receiver<Result<OwnedFrame>>> {
+        self.results.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for ConvertPool {
+    fn drop(&mut self) {
+        // Closing the job queue lets the workers finish the queued conversions and exit.
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_pool_converts_every_frame() {
+        const FRAMES: u64 = 12;
+
+        let pool = ConvertPool::new(3);
+        for index in 0..FRAMES {
+            let value = index as u8;
+            let frame = OwnedFrame::from_planes(
+                2,
+                1,
+                PixelFormat::Rgb24,
+                vec![vec![value, 1, 2, value, 1, 2]],
+                &[6],
+            )
+            .unwrap()
+            .with_frame_index(index);
+            pool.submit(frame, PixelFormat::Bgra32).unwrap();
+        }
+
+        let mut converted: Vec<OwnedFrame> = (0..FRAMES)
+            .map(|_| {
+                pool.recv_timeout(Duration::from_secs(5))
+                    .expect("frame not converted in time")
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(pool.pending(), 0);
+        assert!(pool.recv().is_none());
+
+        converted.sort_by_key(OwnedFrame::frame_index);
+        for (index, frame) in converted.iter().enumerate() {
+            assert_eq!(frame.frame_index(), index as u64);
+            assert_eq!(frame.pixel_format(), PixelFormat::Bgra32);
+            let value = index as u8;
+            assert_eq!(
+                frame.plane(0).unwrap(),
+                &[2, 1, value, 255, 2, 1, value, 255]
+            );
+        }
+
+        pool.submit(converted.remove(0), PixelFormat::Nv12).unwrap();
+        assert!(matches!(pool.recv(), Some(Err(CcapError::NotSupported))));
+    }
+}
@@ -0,0 +1,180 @@
+//! Background grabbing that keeps the next frame ready (see `Provider::set_prefetch`)
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Pause between background grabs that found no frame ready.
+///
+/// The worker grabs with a zero timeout: a native grab that times out reports
+/// `FrameCaptureTimeout` through the error callback, which would fire constantly for cameras
+/// slower than the timeout. Bounds the latency the worker adds to a frame arrival.
+const PREFETCH_POLL: Duration = Duration::from_millis(5);
+
+struct SlotState<T> {
+    frame: Option<T>,
+    running: bool,
+}
+
+struct Slot<T> {
+    state: Mutex<SlotState<T>>,
+    changed: Condvar,
+}
+
+impl<T> Slot<T> {
+    fn lock(&self) -> MutexGuard<'_, SlotState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Worker thread grabbing one frame ahead of the consumer.
+///
+/// The consumer holds the current frame while the worker fills the single slot with the next
+/// one, so at most two frames are alive at a time. The worker waits for the slot to be taken
+/// before grabbing again, which keeps the prefetched frame at most one frame old.
+pub(crate) struct Prefetcher<T: Send + 'static> {
+    slot: Arc<Slot<T>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> Prefetcher<T> {
+    /// Spawn the worker. `grab` must not block: it returns `None` when no frame is ready,
+    /// and the worker polls it again after [`PREFETCH_POLL`].
+    pub(crate) fn spawn<G>(mut grab: G) -> Self
+    where
+        G: FnMut() -> Option<T> + Send + 'static,
+    {
+        let slot = Arc::new(Slot {
+            state: Mutex::new(SlotState {
+                frame: None,
+                running: true,
+            }),
+            changed: Condvar::new(),
+        });
+
+        let worker = {
+            let slot = Arc::clone(&slot);
+            thread::spawn(move || loop {
+                {
+                    let mut state = slot.lock();
+                    while state.running && state.frame.is_some() {
+                        state = slot
+                            .changed
+                            .wait(state)
+                            .unwrap_or_else(PoisonError::into_inner);
+                    }
+                    if !state.running {
+                        return;
+                    }
+                }
+
+                let frame = grab();
+                let mut state = slot.lock();
+                if !state.running {
+                    return;
+                }
+                match frame {
+                    Some(frame) => {
+                        state.frame = Some(frame);
+                        slot.changed.notify_all();
+                    }
+                    // Park until the next poll; dropping the prefetcher wakes the worker.
+                    None => drop(
+                        slot.changed
+                            .wait_timeout(state, PREFETCH_POLL)
+                            .unwrap_or_else(PoisonError::into_inner),
+                    ),
+                }
+            })
+        };
+
+        Self {
+            slot,
+            worker: Some(worker),
+        }
+    }
+
+    /// Take the prefetched frame, waiting up to `timeout` for one to arrive
+    pub(crate) fn take(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.slot.lock();
+        loop {
+            if let Some(frame) = state.frame.take() {
+                self.slot.changed.notify_all();
+                return Some(frame);
+            }
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            state = self
+                .slot
+                .changed
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Drop the prefetched frame, if any
+    pub(crate) fn clear(&self) {
+        if self.slot.lock().frame.take().is_some() {
+            self.slot.changed.notify_all();
+        }
+    }
+}
+
+impl<T: Send + 'static> Drop for Prefetcher<T> {
+    fn drop(&mut self) {
+        self.slot.lock().running = false;
+        self.slot.changed.notify_all();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefetch_hides_grab_latency() {
+        const GRAB_LATENCY: Duration = Duration::from_millis(30);
+        const PROCESSING: Duration = Duration::from_millis(40);
+        const FRAMES: u32 = 5;
+
+        let mut next = 0u32;
+        let prefetcher = Prefetcher::spawn(move || {
+            thread::sleep(GRAB_LATENCY);
+            next += 1;
+            Some(next)
+        });
+
+        assert_eq!(prefetcher.take(Duration::from_secs(5)), Some(1));
+        let mut waited = Duration::ZERO;
+        for expected in 2..=FRAMES + 1 {
+            thread::sleep(PROCESSING);
+            let started = Instant::now();
+            assert_eq!(prefetcher.take(Duration::from_secs(5)), Some(expected));
+            waited += started.elapsed();
+        }
+        // Without prefetching every grab would block for the full latency.
+        assert!(waited < GRAB_LATENCY * FRAMES / 2, "waited {:?}", waited);
+    }
+
+    #[test]
+    fn test_prefetch_take_times_out_and_drop_stops_worker() {
+        let prefetcher = Prefetcher::<u32>::spawn(|| None);
+        assert_eq!(prefetcher.take(Duration::from_millis(10)), None);
+        drop(prefetcher);
+    }
+
+    #[test]
+    fn test_prefetch_polls_until_a_frame_is_ready() {
+        let mut polls = 0u32;
+        let prefetcher = Prefetcher::spawn(move || {
+            polls += 1;
+            (polls == 4).then_some(polls)
+        });
+        assert_eq!(prefetcher.take(Duration::from_secs(5)), Some(4));
+    }
+}
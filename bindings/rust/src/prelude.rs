@@ -0,0 +1,17 @@
+//! Commonly used types, for glob import
+//!
+//! ```
+//! use ccap::prelude::*;
+//! ```
+//!
+//! Everything here is also exported from the crate root.
+
+pub use crate::convert::Convert;
+pub use crate::error::{CcapError, Result};
+pub use crate::frame::{DeviceInfo, FrameRef, OwnedFrame, VideoFrame};
+pub use crate::provider::{CcapEvent, Provider, ProviderBuilder};
+pub use crate::types::{
+    CapturePreferences, CaptureSettings, ColorConversionBackend, ColorRange, DeviceSelector,
+    FrameOrientation, PixelFormat, PropertyName, Resolution,
+};
+pub use crate::utils::{LogLevel, Utils};
@@ -1,24 +1,158 @@
 //! Camera provider for synchronous camera capture operations
 
-use crate::{error::*, frame::*, sys, types::*};
-use std::ffi::{CStr, CString};
+use crate::{
+    broadcast::{FrameBroadcast, FrameSubscriptions},
+    error::*,
+    frame::*,
+    prefetch::Prefetcher,
+    sys,
+    types::*,
+    utils::{LogLevel, Utils},
+};
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString, OsStr};
 use std::ptr;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, Weak};
+use std::time::{Duration, Instant};
 
 /// A wrapper around a raw pointer that can be safely shared between threads.
 /// This is used for storing callback pointers that we know are safe to share
 /// because the callback itself is `Send + Sync`.
 struct SendSyncPtr(*mut std::ffi::c_void);
 
-// SAFETY: The pointer stored here always points to a `Box<ErrorCallbackBox>`
-// where `ErrorCallbackBox = Box<dyn Fn(i32, &str) + Send + Sync>`.
+// SAFETY: The pointer stored here always points to a `Box<ErrorCallback>`
+// where `ErrorCallback = Arc<dyn Fn(i32, &str) + Send + Sync>`.
 // The underlying callback is `Send + Sync`, so the pointer is safe to share.
 unsafe impl Send for SendSyncPtr {}
 unsafe impl Sync for SendSyncPtr {}
 
+/// Native provider handle used by the prefetch and open worker threads
+#[derive(Clone, Copy)]
+struct WorkerHandle(*mut sys::CcapProvider);
+
+// SAFETY: The native grab is internally synchronized, and the prefetch worker is joined before
+// the handle is destroyed or released by `Provider::into_raw`. An open worker has the handle
+// to itself: the provider waits for it, or switches to a new handle if it times out.
+unsafe impl Send for WorkerHandle {}
+
+impl WorkerHandle {
+    // A method call makes closures capture the whole wrapper rather than the raw pointer.
+    fn get(self) -> *mut sys::CcapProvider {
+        self.0
+    }
+}
+
+/// Native provider and frame callback left to an open that outlived `Provider::set_open_timeout`
+struct AbandonedProvider {
+    handle: *mut sys::CcapProvider,
+    callback_ptr: Option<*mut std::ffi::c_void>,
+}
+
+// SAFETY: Once abandoned, both are only touched by the detached open thread, and the callback
+// is a `FrameCallbackBox`, which is `Send + Sync`.
+unsafe impl Send for AbandonedProvider {}
+
+impl AbandonedProvider {
+    /// Free the handle and callback once the native open has returned
+    fn destroy(self) {
+        unsafe {
+            sys::ccap_provider_destroy(self.handle);
+            if let Some(callback_ptr) = self.callback_ptr {
+                let _ = Box::from_raw(callback_ptr as *mut FrameCallbackBox);
+            }
+        }
+    }
+}
+
+/// Run `work` on a helper thread and wait at most `timeout` for its result.
+///
+/// Without a timeout `work` simply runs on the calling thread. On timeout the thread is left
+/// running and hands its late result to `abandon`; native calls can't be cancelled.
+fn run_with_timeout<T: Send + 'static>(
+    timeout: Option<Duration>,
+    work: impl FnOnce() -> T + Send + 'static,
+    abandon: impl FnOnce(T) + Send + 'static,
+) -> Result<T> {
+    let Some(timeout) = timeout else {
+        return Ok(work());
+    };
+    // A rendezvous channel: once the receiver gives up, `send` hands the value back.
+    let (sender, receiver) = std::sync::mpsc::sync_channel(0);
+    std::thread::spawn(move || {
+        if let Err(std::sync::mpsc::SendError(late)) = sender.send(work()) {
+            abandon(late);
+        }
+    });
+    receiver
+        .recv_timeout(timeout)
+        .map_err(|_| CcapError::Timeout)
+}
+
+/// Boxed frame callback installed by `Provider::set_new_frame_callback`.
+///
+/// Shared with `Provider::cleanup_callback`, which must free the pointer as the same type.
+type FrameCallbackBox = Box<dyn Fn(&mut VideoFrame) -> bool + Send + Sync>;
+
+/// Native trampoline for the callbacks installed by `Provider::set_new_frame_callback`
+unsafe extern "C" fn new_frame_callback_wrapper(
+    frame: *const sys::CcapVideoFrame,
+    user_data: *mut std::ffi::c_void,
+) -> bool {
+    if user_data.is_null() || frame.is_null() {
+        return false;
+    }
+
+    // SAFETY: user_data points to a Box<FrameCallbackBox> created by set_new_frame_callback
+    let callback = &**(user_data as *const FrameCallbackBox);
+
+    // Create a temporary VideoFrame wrapper that doesn't own the frame
+    let mut video_frame = VideoFrame::from_c_ptr_ref(frame as *mut sys::CcapVideoFrame);
+    callback(&mut video_frame)
+}
+
+/// Error callback installed by `Provider::set_error_callback`.
+///
+/// Reference counted so callers can clone it out of `GLOBAL_ERROR_CALLBACK` and run it with
+/// the lock released; the callback may itself replace or clear the error callback.
+type ErrorCallback = Arc<dyn Fn(i32, &str) + Send + Sync>;
+
 // Global error callback storage - must be at module level to be shared between functions
 static GLOBAL_ERROR_CALLBACK: Mutex<Option<SendSyncPtr>> = Mutex::new(None);
 
+/// Most errors kept for [`Provider::poll_event`]; older ones are dropped first
+const ERROR_QUEUE_CAPACITY: usize = 64;
+
+/// Errors waiting for [`Provider::poll_event`], as `(code, description)`, and the number
+/// dropped because the queue was full
+#[derive(Debug, Default)]
+struct ErrorEvents {
+    queued: VecDeque<(i32, String)>,
+    dropped: u64,
+}
+
+type ErrorQueue = Mutex<ErrorEvents>;
+
+// Error queues of providers polled with `Provider::poll_event`; every error goes to all of them
+static ERROR_QUEUES: Mutex<Vec<Weak<ErrorQueue>>> = Mutex::new(Vec::new());
+
+// Process-wide device enumeration cache, populated lazily by `Provider::cached_devices`
+static DEVICE_CACHE: RwLock<Option<Arc<Vec<DeviceInfo>>>> = RwLock::new(None);
+
+/// Device name as a C string; names don't have to be valid UTF-8
+fn device_c_name(name: &[u8]) -> Result<CString> {
+    CString::new(name)
+        .map_err(|_| CcapError::InvalidParameter("device name contains null byte".to_string()))
+}
+
+/// Selector that reopens the device named by `name`, keeping bytes that aren't UTF-8
+fn name_selector(name: &[u8]) -> DeviceSelector {
+    match std::str::from_utf8(name) {
+        Ok(name) => DeviceSelector::Name(name.to_string()),
+        Err(_) => DeviceSelector::NameBytes(name.to_vec()),
+    }
+}
+
 fn optional_c_string(value: Option<&str>, parameter_name: &str) -> Result<Option<CString>> {
     value
         .map(|text| {
@@ -29,6 +163,593 @@ fn optional_c_string(value: Option<&str>, parameter_name: &str) -> Result<Option
         .transpose()
 }
 
+/// Number of recent frame arrivals used by `Provider::measured_frame_rate`
+const FRAME_RATE_WINDOW: usize = 30;
+
+/// Minimum arrivals before a measured frame rate is reported
+const FRAME_RATE_MIN_SAMPLES: usize = 5;
+
+/// Frames discarded by `Provider::thumbnail` while auto exposure settles
+const THUMBNAIL_WARMUP_FRAMES: usize = 5;
+
+/// Timeout of each grab in `Provider::thumbnail`
+const THUMBNAIL_GRAB_TIMEOUT_MS: u32 = 2000;
+
+/// Rolling window of frame arrival times and of intervals between frame timestamps, plus
+/// running counts of captured and dropped frames
+#[derive(Debug, Default)]
+struct FrameRateTracker {
+    arrivals: VecDeque<Instant>,
+    last_timestamp: Option<u64>,
+    intervals: VecDeque<Duration>,
+    captured: u64,
+    dropped: u64,
+    last_index: Option<u64>,
+}
+
+impl FrameRateTracker {
+    /// Record a frame arriving at `arrival` with the given capture timestamp in nanoseconds
+    fn record(&mut self, arrival: Instant, timestamp: u64) {
+        if self.arrivals.len() == FRAME_RATE_WINDOW {
+            self.arrivals.pop_front();
+        }
+        self.arrivals.push_back(arrival);
+
+        // A timestamp that doesn't move forward (e.g. after a backend clock reset) starts
+        // a new sequence rather than producing a bogus interval.
+        if let Some(interval) = self
+            .last_timestamp
+            .and_then(|last| timestamp.checked_sub(last))
+            .filter(|&interval| interval > 0)
+        {
+            if self.intervals.len() == FRAME_RATE_WINDOW {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(Duration::from_nanos(interval));
+        }
+        self.last_timestamp = Some(timestamp);
+    }
+
+    /// Count a frame handed out with the given frame index.
+    ///
+    /// Indices skipped since the previous frame are counted as dropped. An index that doesn't
+    /// move forward (e.g. after capture restarted) starts a new sequence.
+    fn count(&mut self, index: u64) {
+        self.captured += 1;
+        if let Some(skipped) = self
+            .last_index
+            .and_then(|last| index.checked_sub(last))
+            .filter(|&step| step > 1)
+        {
+            self.dropped += skipped - 1;
+        }
+        self.last_index = Some(index);
+    }
+
+    /// Forget the recent frames; the captured and dropped counts are kept
+    fn clear(&mut self) {
+        self.arrivals.clear();
+        self.last_timestamp = None;
+        self.intervals.clear();
+        self.last_index = None;
+    }
+
+    /// Whether the most recent frame arrived no more than `window` before `now`
+    fn delivered_within(&self, now: Instant, window: Duration) -> bool {
+        self.arrivals
+            .back()
+            .map_or(false, |&last| now.saturating_duration_since(last) <= window)
+    }
+
+    fn last_interval(&self) -> Option<Duration> {
+        self.intervals.back().copied()
+    }
+
+    fn interval_stddev(&self) -> Option<Duration> {
+        if self.intervals.len() < 2 {
+            return None;
+        }
+        let count = self.intervals.len() as f64;
+        let mean = self
+            .intervals
+            .iter()
+            .map(Duration::as_secs_f64)
+            .sum::<f64>()
+            / count;
+        let variance = self
+            .intervals
+            .iter()
+            .map(|interval| (interval.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        Some(Duration::from_secs_f64(variance.sqrt()))
+    }
+
+    fn frame_rate(&self) -> Option<f64> {
+        if self.arrivals.len() < FRAME_RATE_MIN_SAMPLES {
+            return None;
+        }
+        let (first, last) = (self.arrivals.front()?, self.arrivals.back()?);
+        let elapsed = last.duration_since(*first).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        Some((self.arrivals.len() - 1) as f64 / elapsed)
+    }
+}
+
+/// Shared state behind [`Provider::set_auto_orient`], also read by frame callbacks
+#[derive(Debug, Default)]
+struct AutoOrient {
+    enabled: AtomicBool,
+    flips: AtomicU64,
+}
+
+/// Tracks frame activity to detect capture that silently stopped delivering frames
+#[derive(Debug, Default)]
+struct StallDetector {
+    timeout: Option<Duration>,
+    last_activity: Option<Instant>,
+    reported: bool,
+    watchdog_running: bool,
+}
+
+impl StallDetector {
+    /// Note that capture started or a frame arrived
+    fn activity(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+        self.reported = false;
+    }
+
+    /// Stop watching until capture starts again
+    fn disarm(&mut self) {
+        self.last_activity = None;
+    }
+
+    /// Returns `true` once per stall, when no activity was seen for the timeout
+    fn check(&mut self, now: Instant) -> bool {
+        match (self.timeout, self.last_activity) {
+            (Some(timeout), Some(last))
+                if !self.reported && now.duration_since(last) >= timeout =>
+            {
+                self.reported = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Poll the stall detector until it is disabled or its provider is dropped
+fn run_stall_watchdog(stall: Weak<Mutex<StallDetector>>) {
+    loop {
+        let interval = {
+            let Some(stall) = stall.upgrade() else {
+                return;
+            };
+            let mut stall = stall.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(timeout) = stall.timeout else {
+                stall.watchdog_running = false;
+                return;
+            };
+            let stalled = stall.check(Instant::now());
+            // Report with the lock released: the error callback may call back into the
+            // provider, e.g. `set_stall_timeout`.
+            drop(stall);
+            if stalled {
+                let error = CcapError::Timeout;
+                report_error(
+                    sys::CcapErrorCode_CCAP_ERROR_FRAME_CAPTURE_TIMEOUT as i32,
+                    &format!("{}: no frame received for {:?}", error, timeout),
+                );
+            }
+            (timeout / 4).max(Duration::from_millis(10))
+        };
+        std::thread::sleep(interval);
+    }
+}
+
+/// Register a new error queue that receives every error reported from now on
+fn subscribe_errors() -> Arc<ErrorQueue> {
+    let queue = Arc::default();
+    ERROR_QUEUES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Arc::downgrade(&queue));
+    queue
+}
+
+/// Append an error to the queue of every provider that polls for events
+fn queue_error(code: i32, description: &str) {
+    let mut queues = ERROR_QUEUES.lock().unwrap_or_else(PoisonError::into_inner);
+    queues.retain(|queue| match queue.upgrade() {
+        Some(queue) => {
+            let mut events = queue.lock().unwrap_or_else(PoisonError::into_inner);
+            if events.queued.len() >= ERROR_QUEUE_CAPACITY {
+                events.queued.pop_front();
+                events.dropped += 1;
+            }
+            events.queued.push_back((code, description.to_owned()));
+            true
+        }
+        None => false,
+    });
+}
+
+unsafe extern "C" fn error_callback_wrapper(
+    error_code: sys::CcapErrorCode,
+    description: *const std::os::raw::c_char,
+    _user_data: *mut std::ffi::c_void,
+) {
+    if description.is_null() {
+        return;
+    }
+
+    // The native layer calls this with no lock held, so `user_data` may already have been
+    // freed by a concurrent `clear_error_callback` or guard drop. Run whatever callback is
+    // installed now, kept alive by its own reference.
+    let desc_cstr = std::ffi::CStr::from_ptr(description);
+    if let Ok(desc_str) = desc_cstr.to_str() {
+        report_error(error_code as i32, desc_str);
+    }
+}
+
+/// Replace the global error callback, returning the pointer identifying the new one
+fn install_error_callback<F>(callback: F) -> *mut std::ffi::c_void
+where
+    F: Fn(i32, &str) + Send + Sync + 'static,
+{
+    let mut guard = GLOBAL_ERROR_CALLBACK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // Clean up old callback if exists
+    if let Some(SendSyncPtr(old_ptr)) = guard.take() {
+        unsafe {
+            let _ = Box::from_raw(old_ptr as *mut ErrorCallback);
+        }
+    }
+
+    // Store new callback - box the Arc for a thin, stable pointer
+    let callback: ErrorCallback = Arc::new(callback);
+    let callback_ptr = Box::into_raw(Box::new(callback)) as *mut std::ffi::c_void;
+    unsafe {
+        sys::ccap_set_error_callback(Some(error_callback_wrapper), callback_ptr);
+    }
+    *guard = Some(SendSyncPtr(callback_ptr));
+    callback_ptr
+}
+
+/// Unregister the global error callback and free it
+fn remove_error_callback(installed: &mut Option<SendSyncPtr>) {
+    // Always clear the C-side callback even if we don't have a stored Rust callback.
+    unsafe {
+        sys::ccap_set_error_callback(None, ptr::null_mut());
+    }
+    if let Some(SendSyncPtr(old_ptr)) = installed.take() {
+        unsafe {
+            let _ = Box::from_raw(old_ptr as *mut ErrorCallback);
+        }
+    }
+}
+
+/// Unregisters the error callback set by [`Provider::set_scoped_error_callback`] when dropped
+#[must_use = "the error callback is unregistered as soon as the guard is dropped"]
+pub struct ErrorCallbackGuard {
+    callback_ptr: *mut std::ffi::c_void,
+}
+
+// SAFETY: the pointer is only compared against the installed callback, never dereferenced.
+unsafe impl Send for ErrorCallbackGuard {}
+unsafe impl Sync for ErrorCallbackGuard {}
+
+impl Drop for ErrorCallbackGuard {
+    fn drop(&mut self) {
+        let mut installed = GLOBAL_ERROR_CALLBACK
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if matches!(*installed, Some(SendSyncPtr(ptr)) if ptr == self.callback_ptr) {
+            remove_error_callback(&mut installed);
+        }
+    }
+}
+
+/// Invoke the global error callback (if any) for errors detected on the Rust side
+fn report_error(code: i32, description: &str) {
+    queue_error(code, description);
+    call_error_callback(code, description);
+}
+
+/// Invoke the global error callback, if any, without queueing the error for `poll_event`
+fn call_error_callback(code: i32, description: &str) {
+    if let Some(callback) = current_error_callback() {
+        callback(code, description);
+    }
+}
+
+/// Clone the installed error callback out of `GLOBAL_ERROR_CALLBACK`.
+///
+/// The clone keeps the callback alive after the lock is released, so it can be called
+/// without holding the lock even if it is replaced or cleared meanwhile.
+fn current_error_callback() -> Option<ErrorCallback> {
+    let guard = GLOBAL_ERROR_CALLBACK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // SAFETY: the pointer is a Box<ErrorCallback>, which isn't freed while the lock is held
+    guard.as_ref().map(|SendSyncPtr(callback_ptr)| unsafe {
+        Arc::clone(&*(*callback_ptr as *const ErrorCallback))
+    })
+}
+
+/// Yield at most `n` items from `grab`, ending early on a timeout (`Ok(None)`) or after an error
+fn take_grabbed<T>(
+    n: usize,
+    mut grab: impl FnMut() -> Result<Option<T>>,
+) -> impl Iterator<Item = Result<T>> {
+    let mut remaining = n;
+    std::iter::from_fn(move || {
+        if remaining == 0 {
+            return None;
+        }
+        remaining -= 1;
+        match grab() {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => {
+                remaining = 0;
+                None
+            }
+            Err(e) => {
+                remaining = 0;
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+/// Pass every item from `grab` to `f` until `duration` has elapsed, returning the count.
+///
+/// `grab` is given the remaining time in milliseconds as its timeout; timeouts (`Ok(None)`)
+/// are retried until the deadline, errors end the loop.
+fn grab_until<T>(
+    duration: Duration,
+    mut grab: impl FnMut(u32) -> Result<Option<T>>,
+    mut f: impl FnMut(T),
+) -> Result<u64> {
+    let deadline = Instant::now() + duration;
+    let mut count = 0;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        let timeout_ms = remaining.as_millis().min(u32::MAX as u128) as u32;
+        if timeout_ms == 0 {
+            break;
+        }
+        if let Some(item) = grab(timeout_ms)? {
+            f(item);
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Call `grab` with the time left of `timeout_ms` until it yields an item `accept` takes.
+///
+/// Rejected items are dropped and grabbed again while time is left. A `None` from `grab` ends
+/// the loop unless `retry_none` is set; each extra attempt after a `None` counts in `retries`.
+fn grab_within<T>(
+    timeout_ms: u32,
+    retry_none: bool,
+    retries: &mut u64,
+    mut grab: impl FnMut(u32) -> Option<T>,
+    accept: impl Fn(&T) -> bool,
+) -> Option<T> {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+    let mut remaining_ms = timeout_ms;
+    loop {
+        let got_none = match grab(remaining_ms) {
+            Some(item) if accept(&item) => return Some(item),
+            Some(_) => false,
+            None if !retry_none => return None,
+            None => true,
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+        if got_none {
+            *retries += 1;
+        }
+        remaining_ms = (deadline - now).as_millis() as u32;
+    }
+}
+
+/// Reject resolutions the native layer can't meaningfully apply
+fn validate_resolution(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 {
+        return Err(CcapError::InvalidParameter(format!(
+            "resolution must be non-zero, got {}x{}",
+            width, height
+        )));
+    }
+    Ok(())
+}
+
+/// Reject non-positive, NaN or infinite frame rates
+pub(crate) fn validate_frame_rate(fps: f64) -> Result<()> {
+    if !(fps > 0.0 && fps.is_finite()) {
+        return Err(CcapError::InvalidParameter(format!(
+            "frame rate must be positive and finite, got {}",
+            fps
+        )));
+    }
+    Ok(())
+}
+
+/// Sort resolutions by width then height and drop duplicates
+fn sorted_modes(resolutions: &[Resolution]) -> Vec<Resolution> {
+    let mut modes = resolutions.to_vec();
+    modes.sort_by_key(|mode| (mode.width, mode.height));
+    modes.dedup();
+    modes
+}
+
+/// Output formats the native conversion stage can produce from any YUV or RGB input.
+///
+/// It can't convert YUV to another YUV layout or RGB to YUV, so YUV outputs are only
+/// available when the camera delivers them.
+const CONVERTIBLE_OUTPUTS: [PixelFormat; 4] = [
+    PixelFormat::Rgb24,
+    PixelFormat::Bgr24,
+    PixelFormat::Rgba32,
+    PixelFormat::Bgra32,
+];
+
+/// Output formats reachable from the `native` camera formats
+fn deliverable_from_native(native: &[PixelFormat]) -> Vec<PixelFormat> {
+    let mut formats = Vec::new();
+    let known = native
+        .iter()
+        .filter(|&&format| format != PixelFormat::Unknown);
+    let converted = if native.iter().any(|&format| format != PixelFormat::Unknown) {
+        &CONVERTIBLE_OUTPUTS[..]
+    } else {
+        &[]
+    };
+    for &format in known.chain(converted) {
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    formats
+}
+
+/// Return the cached device info, running `query` to fill the cache if it is empty.
+/// Failed queries aren't cached.
+fn cached_device_info(
+    cache: &Mutex<Option<DeviceInfo>>,
+    query: impl FnOnce() -> Result<DeviceInfo>,
+) -> Result<DeviceInfo> {
+    let mut cached = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(info) = cached.as_ref() {
+        return Ok(info.clone());
+    }
+    let info = query()?;
+    *cached = Some(info.clone());
+    Ok(info)
+}
+
+/// Pick the enumerated device `selector` refers to; `None` or a negative index means the
+/// default camera, which is assumed to be the first one listed.
+#[cfg(feature = "serde")]
+fn select_enumerated_device(
+    devices: Vec<DeviceInfo>,
+    selector: Option<&DeviceSelector>,
+) -> Option<DeviceInfo> {
+    match selector {
+        Some(DeviceSelector::Name(name)) => devices.into_iter().find(|device| &device.name == name),
+        Some(DeviceSelector::NameBytes(name)) => devices
+            .into_iter()
+            .find(|device| device.name_bytes() == name.as_slice()),
+        Some(DeviceSelector::Index(index)) if *index >= 0 => {
+            devices.into_iter().nth(*index as usize)
+        }
+        _ => devices.into_iter().next(),
+    }
+}
+
+/// JSON body of [`Provider::capabilities_json`]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct CapabilitiesReport<'a> {
+    device: &'a DeviceInfo,
+    settings: Option<CaptureSettings>,
+}
+
+#[cfg(feature = "serde")]
+fn capabilities_to_json(device: &DeviceInfo, settings: Option<CaptureSettings>) -> Result<String> {
+    serde_json::to_string_pretty(&CapabilitiesReport { device, settings })
+        .map_err(|e| CcapError::InternalError(format!("failed to serialize capabilities: {}", e)))
+}
+
+/// Pick the mode for `max`: the largest mode fitting within it, else the smallest mode
+fn choose_mode(resolutions: &[Resolution], max: Option<Resolution>) -> Option<Resolution> {
+    let area = |mode: &Resolution| mode.width as u64 * mode.height as u64;
+    let fits = |mode: &&Resolution| {
+        max.map_or(true, |max| {
+            mode.width <= max.width && mode.height <= max.height
+        })
+    };
+    resolutions
+        .iter()
+        .filter(fits)
+        .max_by_key(|mode| area(mode))
+        .or_else(|| resolutions.iter().min_by_key(|mode| area(mode)))
+        .copied()
+}
+
+/// Frame rate to request so capture runs at least at `min_fps`, or `None` to keep `current`.
+///
+/// A faster current rate is kept; an unknown one is replaced by the minimum.
+fn raised_frame_rate(current: Option<f64>, min_fps: f64) -> Option<f64> {
+    match current {
+        Some(current) if current >= min_fps => None,
+        _ => Some(min_fps),
+    }
+}
+
+/// Sliding window over the most recent owned frames
+struct FrameWindow {
+    capacity: usize,
+    frames: VecDeque<OwnedFrame>,
+}
+
+impl FrameWindow {
+    fn new(capacity: usize) -> Self {
+        FrameWindow {
+            capacity,
+            frames: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Add a frame, evicting the oldest one when full, and return the window oldest-first
+    fn push(&mut self, frame: OwnedFrame) -> &[OwnedFrame] {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+        self.frames.make_contiguous()
+    }
+}
+
+/// Check that `format` can be delivered by a device producing `supported` formats.
+///
+/// An empty `supported` list means the device didn't report its formats, so nothing is
+/// rejected.
+fn check_output_format(
+    supported: &[PixelFormat],
+    format: PixelFormat,
+    allow_conversion: bool,
+) -> Result<()> {
+    let converted = CONVERTIBLE_OUTPUTS.contains(&format);
+    if supported.is_empty() || supported.contains(&format) || (allow_conversion && converted) {
+        Ok(())
+    } else {
+        Err(CcapError::NotSupported)
+    }
+}
+
+/// Something that happened on a provider, returned by [`Provider::poll_event`]
+#[derive(Debug)]
+pub enum CcapEvent {
+    /// A captured frame
+    Frame(VideoFrame),
+    /// An error reported by the library, with a native `CcapErrorCode` value
+    Error {
+        /// Native error code
+        code: i32,
+        /// Human-readable description
+        message: String,
+    },
+}
+
 /// Type alias for the global error callback
 ///
 /// # Thread Safety
@@ -60,6 +781,18 @@ pub struct Provider {
     handle: *mut sys::CcapProvider,
     is_opened: bool,
     callback_ptr: Option<*mut std::ffi::c_void>,
+    skip_empty_frames: bool,
+    grab_retry: bool,
+    grab_retries: u64,
+    open_timeout: Option<Duration>,
+    windows_backend: Option<WindowsBackend>,
+    frame_rate: Arc<Mutex<FrameRateTracker>>,
+    stall: Arc<Mutex<StallDetector>>,
+    auto_orient: Arc<AutoOrient>,
+    prefetch: Option<Prefetcher<VideoFrame>>,
+    device_info_cache: Mutex<Option<DeviceInfo>>,
+    attached_device: Option<DeviceSelector>,
+    error_events: Option<Arc<ErrorQueue>>,
 }
 
 // SAFETY: Provider is Send because:
@@ -84,12 +817,39 @@ impl Provider {
             handle,
             is_opened: false,
             callback_ptr: None,
+            skip_empty_frames: false,
+            grab_retry: false,
+            grab_retries: 0,
+            open_timeout: None,
+            windows_backend: None,
+            frame_rate: Arc::default(),
+            stall: Arc::default(),
+            auto_orient: Arc::default(),
+            prefetch: None,
+            device_info_cache: Mutex::new(None),
+            attached_device: None,
+            error_events: None,
         })
     }
 
-    /// Create a provider with a specific device index
-    pub fn with_device(device_index: i32) -> Result<Self> {
-        Self::with_device_and_extra_info(device_index, None)
+    /// Start configuring a provider with a [`ProviderBuilder`]
+    pub fn builder() -> ProviderBuilder {
+        ProviderBuilder::new()
+    }
+
+    /// Create a provider and open a device selected by index or by name
+    ///
+    /// Accepts anything convertible into a [`DeviceSelector`], so both
+    /// `Provider::with_device(0)` and `Provider::with_device(&devices[0])` work. Indices go
+    /// through [`Provider::with_device_and_extra_info`] and names through
+    /// [`Provider::with_device_name`]. The device is opened right away; use
+    /// [`Provider::attach_device`] to defer that.
+    pub fn with_device(device: impl Into<DeviceSelector>) -> Result<Self> {
+        match device.into() {
+            DeviceSelector::Index(index) => Self::with_device_and_extra_info(index, None),
+            DeviceSelector::Name(name) => Self::with_device_name(name),
+            DeviceSelector::NameBytes(name) => Self::with_device_name_bytes(&name, None),
+        }
     }
 
     /// Create a provider with a specific device index and optional extra info.
@@ -119,9 +879,50 @@ impl Provider {
             // See `include/ccap_c.h`: "Create a camera provider and open device by index".
             is_opened: true,
             callback_ptr: None,
+            skip_empty_frames: false,
+            grab_retry: false,
+            grab_retries: 0,
+            open_timeout: None,
+            windows_backend: None,
+            frame_rate: Arc::default(),
+            stall: Arc::default(),
+            auto_orient: Arc::default(),
+            prefetch: None,
+            device_info_cache: Mutex::new(None),
+            attached_device: Some(DeviceSelector::Index(device_index)),
+            error_events: None,
         })
     }
 
+    /// Create a provider bound to a device without opening it.
+    ///
+    /// Unlike [`Provider::with_device`] and [`Provider::with_device_name`], the camera isn't
+    /// acquired: [`Provider::is_opened`] stays `false` until [`Provider::open`] opens the
+    /// attached device. The device is only looked up at that point, so a missing camera is
+    /// reported by `open`. Capabilities of unopened devices are listed by
+    /// [`Provider::get_devices`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if a device name contains a null byte.
+    pub fn attach_device(device: impl Into<DeviceSelector>) -> Result<Self> {
+        let device = device.into();
+        let name = match &device {
+            DeviceSelector::Name(name) => Some(name.as_bytes()),
+            DeviceSelector::NameBytes(name) => Some(name.as_slice()),
+            DeviceSelector::Index(_) => None,
+        };
+        if let Some(name) = name {
+            device_c_name(name)?;
+        }
+
+        let mut provider = Self::new()?;
+        // The native provider is created closed; trust its state rather than assuming it.
+        provider.is_opened = unsafe { sys::ccap_provider_is_opened(provider.handle) };
+        provider.attached_device = Some(device);
+        Ok(provider)
+    }
+
     /// Create a provider with a specific device name
     pub fn with_device_name<S: AsRef<str>>(device_name: S) -> Result<Self> {
         Self::with_device_name_and_extra_info(device_name, None)
@@ -135,9 +936,29 @@ impl Provider {
         device_name: S,
         extra_info: Option<&str>,
     ) -> Result<Self> {
-        let c_name = CString::new(device_name.as_ref()).map_err(|_| {
-            CcapError::InvalidParameter("device name contains null byte".to_string())
-        })?;
+        Self::with_device_name_bytes(device_name.as_ref().as_bytes(), extra_info)
+    }
+
+    /// Create a provider with a device name that may not be valid UTF-8.
+    ///
+    /// Pass [`DeviceInfo::name_os`] to reopen a device whose name has non-UTF-8 bytes;
+    /// [`DeviceInfo::name`] replaces those bytes and no longer matches the device. Windows
+    /// names are always UTF-8, so there this behaves like [`Provider::with_device_name`].
+    pub fn with_device_name_os<S: AsRef<OsStr>>(device_name: S) -> Result<Self> {
+        #[cfg(windows)]
+        {
+            Self::with_device_name(device_name.as_ref().to_string_lossy())
+        }
+
+        #[cfg(not(windows))]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            Self::with_device_name_bytes(device_name.as_ref().as_bytes(), None)
+        }
+    }
+
+    fn with_device_name_bytes(device_name: &[u8], extra_info: Option<&str>) -> Result<Self> {
+        let c_name = device_c_name(device_name)?;
         let extra_info = optional_c_string(extra_info, "extra info")?;
 
         let handle = unsafe {
@@ -149,7 +970,9 @@ impl Provider {
             )
         };
         if handle.is_null() {
-            return Err(CcapError::InvalidDevice(device_name.as_ref().to_string()));
+            return Err(CcapError::InvalidDevice(
+                String::from_utf8_lossy(device_name).into_owned(),
+            ));
         }
 
         Ok(Provider {
@@ -158,9 +981,80 @@ impl Provider {
             // See `include/ccap_c.h`: "Create a camera provider and open specified device".
             is_opened: true,
             callback_ptr: None,
+            skip_empty_frames: false,
+            grab_retry: false,
+            grab_retries: 0,
+            open_timeout: None,
+            windows_backend: None,
+            frame_rate: Arc::default(),
+            stall: Arc::default(),
+            auto_orient: Arc::default(),
+            prefetch: None,
+            device_info_cache: Mutex::new(None),
+            attached_device: Some(name_selector(device_name)),
+            error_events: None,
+        })
+    }
+
+    /// Take ownership of a native provider handle created by the C API.
+    ///
+    /// The opened state is queried from the handle. The returned `Provider` destroys
+    /// the handle when dropped.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid pointer obtained from `ccap_provider_create*` (or
+    /// [`Provider::into_raw`]) that is not owned or destroyed by anyone else.
+    pub unsafe fn from_raw(handle: *mut sys::CcapProvider) -> Result<Self> {
+        if handle.is_null() {
+            return Err(CcapError::InvalidParameter(
+                "provider handle is null".to_string(),
+            ));
+        }
+
+        Ok(Provider {
+            handle,
+            is_opened: sys::ccap_provider_is_opened(handle),
+            callback_ptr: None,
+            skip_empty_frames: false,
+            grab_retry: false,
+            grab_retries: 0,
+            open_timeout: None,
+            windows_backend: None,
+            frame_rate: Arc::default(),
+            stall: Arc::default(),
+            auto_orient: Arc::default(),
+            prefetch: None,
+            device_info_cache: Mutex::new(None),
+            attached_device: None,
+            error_events: None,
         })
     }
 
+    /// Release the native provider handle without destroying it.
+    ///
+    /// Any new-frame callback installed from Rust is removed first, since its closure is
+    /// owned by this wrapper. The caller becomes responsible for the handle and must
+    /// eventually call `ccap_provider_destroy` on it, or hand it back with
+    /// [`Provider::from_raw`].
+    pub fn into_raw(self) -> *mut sys::CcapProvider {
+        let mut provider = std::mem::ManuallyDrop::new(self);
+        if provider.callback_ptr.is_some() {
+            // If detaching fails the closure is leaked rather than freed under the native side.
+            let _ = provider.remove_new_frame_callback();
+        }
+        // SAFETY: `provider` is never dropped, so moving the tracker out can't double-drop it.
+        drop(unsafe { ptr::read(&provider.frame_rate) });
+        drop(unsafe { ptr::read(&provider.stall) });
+        drop(unsafe { ptr::read(&provider.auto_orient) });
+        // Stops the prefetch worker before the handle is handed out.
+        drop(unsafe { ptr::read(&provider.prefetch) });
+        drop(unsafe { ptr::read(&provider.device_info_cache) });
+        drop(unsafe { ptr::read(&provider.attached_device) });
+        drop(unsafe { ptr::read(&provider.error_events) });
+        provider.handle
+    }
+
     /// Get available camera devices
     pub fn get_devices() -> Result<Vec<DeviceInfo>> {
         // Create a temporary provider to query devices
@@ -177,22 +1071,24 @@ impl Provider {
 
         let mut devices = Vec::new();
         for i in 0..device_names_list.deviceCount {
-            let name_bytes = &device_names_list.deviceNames[i];
-            let name = unsafe {
-                let cstr = CStr::from_ptr(name_bytes.as_ptr());
-                cstr.to_string_lossy().to_string()
+            let name_bytes = unsafe {
+                CStr::from_ptr(device_names_list.deviceNames[i].as_ptr())
+                    .to_bytes()
+                    .to_vec()
             };
 
-            // Try to get device info by creating provider with this device
-            if let Ok(device_provider) = Self::with_device_name(&name) {
+            // Try to get device info by creating provider with this device. The raw bytes
+            // are used so devices with non-UTF-8 names can be opened too.
+            if let Ok(device_provider) = Self::with_device_name_bytes(&name_bytes, None) {
                 if let Ok(device_info) = device_provider.get_device_info_direct() {
                     devices.push(device_info);
                 } else {
                     // Fallback: create minimal device info from just the name
                     devices.push(DeviceInfo {
-                        name,
+                        name: String::from_utf8_lossy(&name_bytes).into_owned(),
                         supported_pixel_formats: Vec::new(),
                         supported_resolutions: Vec::new(),
+                        name_bytes,
                     });
                 }
             }
@@ -201,6 +1097,58 @@ impl Provider {
         Ok(devices)
     }
 
+    /// The camera that [`Provider::open`] picks when no device is selected.
+    ///
+    /// Resolved from enumeration alone: the first device listed by [`Provider::get_devices`],
+    /// which is what the default open picks on Linux and Windows. No device is opened beyond
+    /// what `get_devices` does to read capabilities. On macOS the default open asks the system
+    /// for its default camera instead, which can differ from the first listed device (the
+    /// list puts real cameras before virtual ones). Returns `None` without any camera.
+    pub fn default_device() -> Result<Option<DeviceInfo>> {
+        Ok(Self::get_devices()?.into_iter().next())
+    }
+
+    /// Get available camera devices from a process-wide cache.
+    ///
+    /// The first call enumerates devices (same as [`Provider::get_devices`]) and caches the
+    /// result; later calls return the same `Arc` without touching the native layer. This makes
+    /// it cheap to query devices from a UI render loop.
+    ///
+    /// **Note**: the cache is never invalidated automatically, so it can be stale after a camera
+    /// is plugged in or removed. Call [`Provider::refresh_devices`] to re-enumerate.
+    pub fn cached_devices() -> Result<Arc<Vec<DeviceInfo>>> {
+        if let Some(devices) = DEVICE_CACHE
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            return Ok(Arc::clone(devices));
+        }
+
+        let mut guard = DEVICE_CACHE.write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have populated the cache while we waited for the write lock.
+        if let Some(devices) = guard.as_ref() {
+            return Ok(Arc::clone(devices));
+        }
+
+        let devices = Arc::new(Self::get_devices()?);
+        *guard = Some(Arc::clone(&devices));
+        Ok(devices)
+    }
+
+    /// Invalidate the device cache and re-enumerate devices (e.g. after a hot-plug event).
+    ///
+    /// Returns the freshly enumerated list, which subsequent [`Provider::cached_devices`] calls
+    /// will return until the next refresh.
+    pub fn refresh_devices() -> Result<Arc<Vec<DeviceInfo>>> {
+        let mut guard = DEVICE_CACHE.write().unwrap_or_else(PoisonError::into_inner);
+        *guard = None;
+
+        let devices = Arc::new(Self::get_devices()?);
+        *guard = Some(Arc::clone(&devices));
+        Ok(devices)
+    }
+
     /// Get device info directly from current provider
     fn get_device_info_direct(&self) -> Result<DeviceInfo> {
         let mut device_info = sys::CcapDeviceInfo::default();
@@ -211,43 +1159,62 @@ impl Provider {
             return Err(CcapError::DeviceOpenFailed);
         }
 
-        let name = unsafe {
-            let cstr = CStr::from_ptr(device_info.deviceName.as_ptr());
-            cstr.to_string_lossy().to_string()
-        };
+        DeviceInfo::from_c_struct(&device_info)
+    }
 
-        let mut formats = Vec::new();
-        for i in 0..device_info.pixelFormatCount {
-            if i < device_info.supportedPixelFormats.len() {
-                formats.push(PixelFormat::from(device_info.supportedPixelFormats[i]));
-            }
+    /// Open the camera device
+    ///
+    /// Opening an already opened provider is treated as a caller bug and returns
+    /// `CcapError::DeviceAlreadyOpened` (earlier versions silently returned `Ok`).
+    /// Note that [`Provider::with_device`] and [`Provider::with_device_name`] already
+    /// open the device. Use [`Provider::open_or_noop`] when idempotent behavior is wanted.
+    ///
+    /// Opens the device last selected for this provider: the one given to
+    /// [`Provider::attach_device`], to a constructor such as [`Provider::with_device`], or to
+    /// the last `open_with_index*`/`open_device*` call. Without a selection the default
+    /// camera is opened. A name that isn't valid UTF-8, such as one given to
+    /// [`Provider::with_device_name_os`], is reopened by its raw bytes.
+    pub fn open(&mut self) -> Result<()> {
+        if self.is_opened {
+            return Err(CcapError::DeviceAlreadyOpened);
         }
+        self.invalidate_device_info();
 
-        let mut resolutions = Vec::new();
-        for i in 0..device_info.resolutionCount {
-            if i < device_info.supportedResolutions.len() {
-                let res = &device_info.supportedResolutions[i];
-                resolutions.push(Resolution {
-                    width: res.width,
-                    height: res.height,
-                });
+        let extra_info = self.backend_extra_info(None);
+        let name = match self.attached_device.clone() {
+            Some(DeviceSelector::Name(name)) => Some(name.into_bytes()),
+            Some(DeviceSelector::NameBytes(name)) => Some(name),
+            Some(DeviceSelector::Index(_)) | None => None,
+        };
+        if let Some(name) = name {
+            if extra_info.is_some() {
+                return self.open_named_device(&name, extra_info, false);
             }
+            let c_name = device_c_name(&name)?;
+            let opened = self.open_in_place(move |handle| unsafe {
+                sys::ccap_provider_open(handle, c_name.as_ptr(), false)
+            })?;
+            if !opened {
+                return Err(CcapError::InvalidDevice(
+                    String::from_utf8_lossy(&name).into_owned(),
+                ));
+            }
+            self.is_opened = true;
+            return Ok(());
         }
 
-        Ok(DeviceInfo {
-            name,
-            supported_pixel_formats: formats,
-            supported_resolutions: resolutions,
-        })
-    }
+        let index = match self.attached_device {
+            Some(DeviceSelector::Index(index)) => index,
+            _ => -1,
+        };
 
-    /// Open the camera device
-    pub fn open(&mut self) -> Result<()> {
-        if self.is_opened {
-            return Ok(());
+        if let Some(extra_info) = extra_info {
+            return self.open_with_index_and_extra_info(index, Some(extra_info), false);
         }
 
-        let result = unsafe { sys::ccap_provider_open_by_index(self.handle, -1, false) };
+        let result = self.open_in_place(move |handle| unsafe {
+            sys::ccap_provider_open_by_index(handle, index, false)
+        })?;
         if !result {
             return Err(CcapError::DeviceOpenFailed);
         }
@@ -256,72 +1223,312 @@ impl Provider {
         Ok(())
     }
 
+    /// Open the camera device, doing nothing if it is already open
+    pub fn open_or_noop(&mut self) -> Result<()> {
+        if self.is_opened {
+            return Ok(());
+        }
+        self.open()
+    }
+
+    /// Bound how long [`Provider::open`] and the other `open*` methods may block.
+    ///
+    /// A wedged driver (e.g. a headset unplugged mid-open) can block a native open forever.
+    /// With a timeout set, each open runs on a helper thread and returns `CcapError::Timeout`
+    /// if it hasn't finished in time. Passing `Duration::ZERO` disables the timeout, which is
+    /// the default.
+    ///
+    /// A native open can't be aborted on any platform, so on timeout the helper thread is
+    /// detached and keeps the native provider it was opening until the open returns; it then
+    /// destroys it. If the driver never returns, it is leaked along with the thread. Either
+    /// way this provider carries on with a new, closed native provider. When the open was
+    /// reusing the existing native provider, the registered frame callback stays with the
+    /// helper thread and has to be set again; otherwise it moves to the new provider. The
+    /// device may stay busy for other opens until the stuck one completes.
+    ///
+    /// The constructors that open a device, such as [`Provider::with_device`], are not
+    /// covered; use [`Provider::attach_device`] and then `open` to bound those.
+    pub fn set_open_timeout(&mut self, timeout: Duration) {
+        self.open_timeout = (!timeout.is_zero()).then_some(timeout);
+    }
+
+    /// Select the capture backend used on Windows for the next open.
+    ///
+    /// DirectShow is the default backend. Some drivers behave better under
+    /// MediaFoundation, so this can be used to work around backend-specific bugs.
+    /// An explicit `extra_info` passed to the `*_with_extra_info` methods takes
+    /// precedence over this setting.
+    ///
+    /// Returns `CcapError::DeviceAlreadyOpened` if the device is already open, and
+    /// `CcapError::NotSupported` on platforms other than Windows.
+    pub fn set_windows_backend(&mut self, backend: WindowsBackend) -> Result<()> {
+        if !cfg!(windows) {
+            return Err(CcapError::NotSupported);
+        }
+        if self.is_opened {
+            return Err(CcapError::DeviceAlreadyOpened);
+        }
+        self.windows_backend = Some(backend);
+        Ok(())
+    }
+
+    /// Resolve the backend hint passed to the C API when opening a device
+    fn backend_extra_info<'a>(&self, extra_info: Option<&'a str>) -> Option<&'a str> {
+        extra_info.or_else(|| {
+            self.windows_backend
+                .filter(|_| cfg!(windows))
+                .map(WindowsBackend::as_extra_info)
+        })
+    }
+
     /// Open device with optional device name and auto start
     pub fn open_device(&mut self, device_name: Option<&str>, auto_start: bool) -> Result<()> {
         self.open_device_with_extra_info(device_name, None, auto_start)
     }
 
+    /// Switch the existing native provider to another device without recreating it.
+    ///
+    /// Capture is stopped and the current device closed, but the handle and the registered
+    /// frame callback are kept. Returns `false` if there is no handle to reuse or `open`
+    /// failed, in which case the caller falls back to destroying and recreating the handle.
+    fn reopen_in_place(
+        &mut self,
+        open: impl FnOnce(*mut sys::CcapProvider) -> bool + Send + 'static,
+    ) -> Result<bool> {
+        if self.handle.is_null() {
+            return Ok(false);
+        }
+        let _ = self.stop_capture();
+        unsafe { sys::ccap_provider_close(self.handle) };
+        self.is_opened = false;
+        self.is_opened = self.open_in_place(open)?;
+        Ok(self.is_opened)
+    }
+
+    /// Run a native open on the current handle, bounded by [`Provider::set_open_timeout`].
+    ///
+    /// On timeout the handle and the frame callback are left to the open thread, which
+    /// destroys them if the open ever returns, and this provider continues with a new,
+    /// closed native provider without a callback.
+    fn open_in_place(
+        &mut self,
+        open: impl FnOnce(*mut sys::CcapProvider) -> bool + Send + 'static,
+    ) -> Result<bool> {
+        if self.open_timeout.is_none() {
+            return Ok(open(self.handle));
+        }
+        // The prefetch worker grabs from the handle the open thread may keep.
+        self.prefetch = None;
+        let handle = WorkerHandle(self.handle);
+        let abandoned = AbandonedProvider {
+            handle: self.handle,
+            callback_ptr: self.callback_ptr,
+        };
+        let result = run_with_timeout(
+            self.open_timeout,
+            move || open(handle.get()),
+            move |_| abandoned.destroy(),
+        );
+        if result.is_err() {
+            self.callback_ptr = None;
+            self.install_closed_handle();
+        }
+        result
+    }
+
+    /// Carry on with a new, closed native provider after the previous one was given up.
+    ///
+    /// A frame callback still owned by this provider is registered on the new handle.
+    fn install_closed_handle(&mut self) {
+        self.handle = unsafe { sys::ccap_provider_create() };
+        self.is_opened = false;
+        self.register_callback_on_handle();
+    }
+
+    /// Register the boxed frame callback, if any, on the current handle
+    fn register_callback_on_handle(&mut self) {
+        let Some(callback_ptr) = self.callback_ptr else {
+            return;
+        };
+        if self.handle.is_null() {
+            return;
+        }
+        let registered = unsafe {
+            sys::ccap_provider_set_new_frame_callback(
+                self.handle,
+                Some(new_frame_callback_wrapper),
+                callback_ptr,
+            )
+        };
+        if !registered {
+            self.cleanup_callback();
+        }
+    }
+
+    /// Create a native provider that opens a device, bounded by [`Provider::set_open_timeout`]
+    ///
+    /// A handle created after the timeout is destroyed by the open thread.
+    fn create_opened_handle(
+        &self,
+        create: impl FnOnce() -> *mut sys::CcapProvider + Send + 'static,
+    ) -> Result<*mut sys::CcapProvider> {
+        run_with_timeout(
+            self.open_timeout,
+            move || WorkerHandle(create()),
+            |late| {
+                if !late.get().is_null() {
+                    AbandonedProvider {
+                        handle: late.get(),
+                        callback_ptr: None,
+                    }
+                    .destroy();
+                }
+            },
+        )
+        .map(WorkerHandle::get)
+    }
+
+    /// Replace the native provider with one created (and opened) by `create`.
+    ///
+    /// Capture on the old provider is stopped before it is destroyed. The registered frame
+    /// callback is moved over to the new provider, so it keeps receiving frames. Returns
+    /// `false` if `create` returned no provider, and `CcapError::Timeout` if it didn't return
+    /// within the open timeout; either way this provider continues with a new, closed native
+    /// provider that has the callback registered.
+    fn recreate_handle(
+        &mut self,
+        create: impl FnOnce() -> *mut sys::CcapProvider + Send + 'static,
+    ) -> Result<bool> {
+        if !self.handle.is_null() {
+            let _ = self.stop_capture();
+            unsafe {
+                // Detach the callback so the box outlives the old provider.
+                sys::ccap_provider_set_new_frame_callback(self.handle, None, ptr::null_mut());
+                sys::ccap_provider_destroy(self.handle);
+            }
+            self.handle = ptr::null_mut();
+            self.is_opened = false;
+        }
+
+        let created = match self.create_opened_handle(create) {
+            Ok(handle) if !handle.is_null() => handle,
+            Ok(_) => {
+                self.install_closed_handle();
+                return Ok(false);
+            }
+            Err(e) => {
+                self.install_closed_handle();
+                return Err(e);
+            }
+        };
+        self.handle = created;
+        // ccap C API contract: the create_with_* functions open the device.
+        self.is_opened = true;
+        self.register_callback_on_handle();
+        Ok(true)
+    }
+
     /// Open a device with optional device name, optional extra info, and optional auto start.
     ///
     /// On Windows, `extra_info` can be used to force backend selection with values like
     /// `"auto"`, `"msmf"`, `"dshow"`, or `"backend=<value>"`.
+    ///
+    /// Switching to a named device reuses the native provider. Only when a backend hint is
+    /// given, or reopening fails, is the provider recreated. The frame callback stays
+    /// registered either way.
     pub fn open_device_with_extra_info(
         &mut self,
         device_name: Option<&str>,
         extra_info: Option<&str>,
         auto_start: bool,
     ) -> Result<()> {
+        let extra_info = self.backend_extra_info(extra_info);
+        self.invalidate_device_info();
         if let Some(name) = device_name {
-            let c_name = CString::new(name).map_err(|_| {
-                CcapError::InvalidParameter("device name contains null byte".to_string())
-            })?;
-            let extra_info = optional_c_string(extra_info, "extra info")?;
-
-            // Recreate provider with specific device
-            if !self.handle.is_null() {
-                // If the previous provider was running, stop it and detach callbacks
-                // before destroying the underlying handle.
-                let _ = self.stop_capture();
-                let _ = self.remove_new_frame_callback();
-                self.cleanup_callback();
-                unsafe {
-                    sys::ccap_provider_destroy(self.handle);
-                }
-                self.handle = ptr::null_mut();
-                self.is_opened = false;
-            } else {
-                self.cleanup_callback();
-            }
-
-            self.handle = unsafe {
-                sys::ccap_provider_create_with_device(
-                    c_name.as_ptr(),
-                    extra_info
-                        .as_ref()
-                        .map_or(ptr::null(), |value| value.as_ptr()),
-                )
-            };
-            if self.handle.is_null() {
-                return Err(CcapError::InvalidDevice(name.to_string()));
-            }
-            self.is_opened = true;
-            if !auto_start {
-                self.stop_capture()?;
-            }
+            return self.open_named_device(name.as_bytes(), extra_info, auto_start);
         } else if extra_info.is_some() {
             return self.open_with_index_and_extra_info(-1, extra_info, auto_start);
-        } else {
-            self.open()?;
         }
+        self.open_or_noop()?;
         if auto_start {
             self.start_capture()?;
         }
         Ok(())
     }
 
-    /// Get device info for the current provider
+    /// Open the device named by `name`, which may not be valid UTF-8, and remember it for
+    /// [`Provider::open`]
+    fn open_named_device(
+        &mut self,
+        name: &[u8],
+        extra_info: Option<&str>,
+        auto_start: bool,
+    ) -> Result<()> {
+        let c_name = device_c_name(name)?;
+        let extra_info = optional_c_string(extra_info, "extra info")?;
+        self.attached_device = Some(name_selector(name));
+
+        // The prefetch worker grabs from the current handle; restart it on the new one.
+        let prefetching = self.prefetch.take().is_some();
+
+        // A backend hint can only be applied by creating a new provider.
+        let reopen_name = c_name.clone();
+        if extra_info.is_none()
+            && self.reopen_in_place(move |handle| unsafe {
+                sys::ccap_provider_open(handle, reopen_name.as_ptr(), false)
+            })?
+        {
+            self.set_prefetch(prefetching);
+            if auto_start {
+                self.start_capture()?;
+            }
+            return Ok(());
+        }
+
+        // Recreate provider with specific device
+        let created = self.recreate_handle(move || unsafe {
+            sys::ccap_provider_create_with_device(
+                c_name.as_ptr(),
+                extra_info
+                    .as_ref()
+                    .map_or(ptr::null(), |value| value.as_ptr()),
+            )
+        })?;
+        if !created {
+            return Err(CcapError::InvalidDevice(
+                String::from_utf8_lossy(name).into_owned(),
+            ));
+        }
+        self.set_prefetch(prefetching);
+        if !auto_start {
+            self.stop_capture()?;
+        }
+        if auto_start {
+            self.start_capture()?;
+        }
+        Ok(())
+    }
+
+    /// Get device info for the current provider.
+    ///
+    /// The first successful query is cached until another device is opened, since the
+    /// capabilities of an open device don't change. Use [`Provider::refresh_device_info`]
+    /// to force a new query.
     pub fn device_info(&self) -> Result<DeviceInfo> {
-        self.get_device_info_direct()
+        cached_device_info(&self.device_info_cache, || self.get_device_info_direct())
+    }
+
+    /// Discard the cached device info and query the native layer again
+    pub fn refresh_device_info(&self) -> Result<DeviceInfo> {
+        self.invalidate_device_info();
+        self.device_info()
+    }
+
+    fn invalidate_device_info(&self) {
+        *self
+            .device_info_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
     }
 
     /// Check if capture is started
@@ -345,6 +1552,10 @@ impl Provider {
     }
 
     /// Set camera property
+    ///
+    /// The value is passed to the native layer unchecked. Prefer the typed setters such as
+    /// [`Provider::set_resolution`] and [`Provider::set_frame_rate`], which reject zero,
+    /// negative and non-finite values with `CcapError::InvalidParameter`.
     pub fn set_property(&mut self, property: PropertyName, value: f64) -> Result<()> {
         let property_id: sys::CcapPropertyName = property.into();
         let success = unsafe { sys::ccap_provider_set_property(self.handle, property_id, value) };
@@ -367,8 +1578,50 @@ impl Provider {
         Ok(value)
     }
 
+    /// Check whether the device reports a value for `property`.
+    ///
+    /// The native layer returns NaN for properties it doesn't handle, so this does a
+    /// tentative read and treats NaN as unsupported. UIs can use it to only show the
+    /// controls a camera actually has. Note that [`PropertyName::FrameOrientation`] is
+    /// write-only in the native layer and therefore reports `false`.
+    pub fn supports_property(&self, property: PropertyName) -> bool {
+        self.get_property(property)
+            .map_or(false, |value| !value.is_nan())
+    }
+
+    /// Capture the current value of every supported property.
+    ///
+    /// Properties for which [`Provider::supports_property`] is `false` are left out.
+    pub fn properties_snapshot(&self) -> Result<PropertiesSnapshot> {
+        let mut values = Vec::with_capacity(PropertyName::ALL.len());
+        for property in PropertyName::ALL {
+            let value = self.get_property(property)?;
+            if !value.is_nan() {
+                values.push((property, value));
+            }
+        }
+        Ok(PropertiesSnapshot { values })
+    }
+
+    /// Restore property values saved by [`Provider::properties_snapshot`].
+    ///
+    /// Properties the device doesn't support are skipped, so a snapshot taken on one
+    /// camera can be applied to another. Stops at the first property that fails to apply.
+    pub fn apply_properties_snapshot(&mut self, snapshot: &PropertiesSnapshot) -> Result<()> {
+        for &(property, value) in &snapshot.values {
+            if self.supports_property(property) {
+                self.set_property(property, value)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Set camera resolution
+    ///
+    /// Returns `CcapError::InvalidParameter` if either dimension is zero.
     pub fn set_resolution(&mut self, width: u32, height: u32) -> Result<()> {
+        validate_resolution(width, height)?;
+
         // Avoid leaving the device in a partially-updated state if only one property update
         // succeeds (e.g. width succeeds but height fails).
         let (old_w, old_h) = self.resolution()?;
@@ -384,28 +1637,550 @@ impl Provider {
         Ok(())
     }
 
+    /// Capture modes advertised by the device, sorted by width then height without duplicates
+    ///
+    /// The indices of this list are the ones accepted by [`Provider::set_mode`].
+    pub fn modes(&self) -> Result<Vec<Resolution>> {
+        Ok(sorted_modes(&self.device_info()?.supported_resolutions))
+    }
+
+    /// Resolutions valid for `format`, e.g. to refresh a resolution list when the user
+    /// picks another format. Empty if the device doesn't deliver `format` natively.
+    ///
+    /// See [`DeviceInfo::resolutions_for_format`].
+    pub fn resolutions_for_format(&self, format: PixelFormat) -> Result<Vec<Resolution>> {
+        Ok(self.device_info()?.resolutions_for_format(format))
+    }
+
+    /// Output pixel formats [`Provider::set_pixel_format`] can deliver on this device.
+    ///
+    /// The device's native formats come first, followed by the RGB formats the library
+    /// converts to (e.g. RGBA32 from a camera that only delivers NV12). YUV outputs are
+    /// limited to what the camera delivers natively.
+    pub fn deliverable_formats(&self) -> Result<Vec<PixelFormat>> {
+        Ok(deliverable_from_native(
+            &self.device_info()?.supported_pixel_formats,
+        ))
+    }
+
+    /// Select a capture mode by its index in [`Provider::modes`].
+    ///
+    /// Unlike [`Provider::set_resolution`], this only ever requests a resolution the device
+    /// advertises, so there's no silent fallback to a nearby size.
+    ///
+    /// Returns `CcapError::InvalidParameter` if `index` is out of range.
+    pub fn set_mode(&mut self, index: usize) -> Result<()> {
+        let modes = self.modes()?;
+        let mode = modes.get(index).ok_or_else(|| {
+            CcapError::InvalidParameter(format!(
+                "mode index {} out of range ({} modes available)",
+                index,
+                modes.len()
+            ))
+        })?;
+        self.set_resolution(mode.width, mode.height)
+    }
+
+    /// Index in [`Provider::modes`] of the current resolution, or `None` if it isn't listed
+    pub fn current_mode_index(&self) -> Result<Option<usize>> {
+        let (width, height) = self.resolution()?;
+        Ok(self
+            .modes()?
+            .iter()
+            .position(|mode| mode.width == width && mode.height == height))
+    }
+
     /// Set camera frame rate
+    ///
+    /// Returns `CcapError::InvalidParameter` if `fps` is zero, negative, NaN or infinite.
     pub fn set_frame_rate(&mut self, fps: f64) -> Result<()> {
+        validate_frame_rate(fps)?;
         self.set_property(PropertyName::FrameRate, fps)
     }
 
     /// Set pixel format
+    ///
+    /// When the device is open, the format is checked against the device's supported
+    /// formats first; RGB-family outputs (RGB24/BGR24/RGBA32/BGRA32) are also accepted since
+    /// the library converts to them internally. Unsupported formats are rejected with
+    /// `CcapError::NotSupported` instead of being silently substituted at capture time; the
+    /// rejected format is named in a `CCAP_ERROR_UNSUPPORTED_PIXEL_FORMAT` report through the
+    /// error callback and [`Provider::poll_event`]. Use
+    /// [`Provider::set_pixel_format_with_conversion`] to only accept native formats.
     pub fn set_pixel_format(&mut self, format: PixelFormat) -> Result<()> {
+        self.set_pixel_format_with_conversion(format, true)
+    }
+
+    /// Set the output pixel format, choosing whether internally converted formats are allowed.
+    ///
+    /// With `allow_conversion == false` only formats the device produces natively are accepted.
+    /// The check is skipped while the device isn't open or doesn't report its formats, as
+    /// they aren't known then.
+    pub fn set_pixel_format_with_conversion(
+        &mut self,
+        format: PixelFormat,
+        allow_conversion: bool,
+    ) -> Result<()> {
+        let supported = if self.is_opened {
+            self.device_info().map(|info| info.supported_pixel_formats)
+        } else {
+            Ok(Vec::new())
+        };
+        if let Ok(supported) = supported {
+            if let Err(e) = check_output_format(&supported, format, allow_conversion) {
+                report_error(
+                    sys::CcapErrorCode_CCAP_ERROR_UNSUPPORTED_PIXEL_FORMAT as i32,
+                    &format!("Pixel format not supported by device: {:?}", format),
+                );
+                return Err(e);
+            }
+        }
         self.set_property(PropertyName::PixelFormatOutput, format.to_c_enum() as f64)
     }
 
+    /// Skip frames that carry no pixel data in [`Provider::grab_frame`].
+    ///
+    /// Some drivers occasionally deliver a frame with zero bytes or a null data plane.
+    /// When enabled, `grab_frame` drops such frames and keeps grabbing until a frame with
+    /// data arrives or the timeout elapses. Disabled by default.
+    pub fn set_skip_empty_frames(&mut self, skip: bool) {
+        self.skip_empty_frames = skip;
+    }
+
+    /// Keep waiting in [`Provider::grab_frame`] when the native grab returns no frame early.
+    ///
+    /// Some backends transiently report no frame between valid ones well before the timeout
+    /// has elapsed, which `grab_frame` would otherwise pass on as `Ok(None)`. When enabled,
+    /// `grab_frame` grabs again with the remaining time until a frame arrives or the full
+    /// timeout elapses; the extra attempts are counted by [`Provider::grab_retries`].
+    /// Disabled by default.
+    pub fn set_grab_retry(&mut self, enabled: bool) {
+        self.grab_retry = enabled;
+    }
+
+    /// Number of extra native grabs made by [`Provider::set_grab_retry`]
+    pub fn grab_retries(&self) -> u64 {
+        self.grab_retries
+    }
+
+    /// Flip bottom-to-top frames upright before handing them out.
+    ///
+    /// Some backends (notably BGR24 on Windows) deliver frames stored bottom-up. When
+    /// enabled, frames from [`Provider::grab_frame`] and the new-frame callback whose
+    /// orientation is [`FrameOrientation::BottomToTop`] are copied upright and then report
+    /// [`FrameOrientation::TopToBottom`]. The native buffers are never modified, since the
+    /// library may share them. Disabled by default. The number of flipped frames is reported
+    /// by [`Provider::stats`].
+    pub fn set_auto_orient(&mut self, enabled: bool) {
+        self.auto_orient.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the provider's capture counters
+    pub fn stats(&self) -> CaptureStats {
+        let frame_rate = self.lock_frame_rate();
+        CaptureStats {
+            frames_captured: frame_rate.captured,
+            frames_dropped: frame_rate.dropped,
+            auto_orient_flips: self.auto_orient.flips.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Grab the next frame on a background thread while the current one is processed.
+    ///
+    /// When enabled, a worker thread grabs each frame as soon as the previous one has been
+    /// handed out, and [`Provider::grab_frame`] returns it immediately if it is already there.
+    /// In a loop whose processing takes longer than the wait for a frame, this hides the grab
+    /// latency behind the processing time.
+    ///
+    /// The trade-off: the returned frame may have been waiting for up to one processing
+    /// iteration, so it can be one frame older than a direct grab would give, and one extra
+    /// native frame buffer is held while the caller keeps the current frame. Disabled by
+    /// default; disabling drops any prefetched frame.
+    pub fn set_prefetch(&mut self, enabled: bool) {
+        if !enabled {
+            self.prefetch = None;
+            return;
+        }
+        if self.prefetch.is_some() {
+            return;
+        }
+
+        let handle = WorkerHandle(self.handle);
+        self.prefetch = Some(Prefetcher::spawn(move || {
+            // A zero timeout never waits, so neither a slow camera nor stopped capture makes
+            // the native grab report errors through the error callback.
+            let frame = unsafe { sys::ccap_provider_grab(handle.get(), 0) };
+            (!frame.is_null()).then(|| VideoFrame::from_c_ptr(frame))
+        }));
+    }
+
+    /// Whether [`Provider::set_prefetch`] is enabled
+    pub fn is_prefetching(&self) -> bool {
+        self.prefetch.is_some()
+    }
+
+    /// Next frame from the prefetch worker, or straight from the native provider
+    fn next_native_frame(&self, timeout_ms: u32) -> Option<VideoFrame> {
+        match &self.prefetch {
+            Some(prefetch) => prefetch.take(Duration::from_millis(timeout_ms as u64)),
+            None => {
+                let frame = unsafe { sys::ccap_provider_grab(self.handle, timeout_ms) };
+                (!frame.is_null()).then(|| VideoFrame::from_c_ptr(frame))
+            }
+        }
+    }
+
     /// Grab a single frame with timeout
+    ///
+    /// Returns `Ok(None)` if no frame arrived within `timeout_ms`. With
+    /// [`Provider::set_prefetch`] enabled the frame is taken from the background worker.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::DeviceNotOpened` if no device is open, and
+    /// `CcapError::CaptureNotStarted` if the device is open but capture hasn't been started
+    /// with [`Provider::start_capture`].
     pub fn grab_frame(&mut self, timeout_ms: u32) -> Result<Option<VideoFrame>> {
         if !self.is_opened {
             return Err(CcapError::DeviceNotOpened);
         }
+        if !self.is_started() {
+            return Err(CcapError::CaptureNotStarted);
+        }
+
+        Ok(self.grab_from(timeout_ms, Self::next_native_frame))
+    }
+
+    /// Body of [`Provider::grab_frame`] once the provider is known to be capturing, with the
+    /// native frames coming from `next`
+    fn grab_from(
+        &mut self,
+        timeout_ms: u32,
+        next: impl Fn(&Self, u32) -> Option<VideoFrame>,
+    ) -> Option<VideoFrame> {
+        let this = &*self;
+        let skip_empty_frames = this.skip_empty_frames;
+        let mut retries = 0;
+        let frame = grab_within(
+            timeout_ms,
+            this.grab_retry,
+            &mut retries,
+            |remaining_ms| next(this, remaining_ms),
+            |frame| !skip_empty_frames || !frame.is_empty(),
+        );
+        self.grab_retries += retries;
+
+        let mut frame = frame?;
+        self.record_frame_arrival(&frame);
+        self.lock_frame_rate().count(frame.index());
+        if self.auto_orient.enabled.load(Ordering::Relaxed) && frame.flip_upright() {
+            self.auto_orient.flips.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(frame)
+    }
+
+    /// Return the next pending event without blocking, for immediate-mode UI loops.
+    ///
+    /// Queued errors come first, oldest first; once none are left, a frame is grabbed with a
+    /// zero timeout if capture is running. `None` means nothing happened since the last call,
+    /// so the intended use is draining it once per UI frame:
+    ///
+    /// ```ignore
+    /// while let Some(event) = provider.poll_event() {
+    ///     match event {
+    ///         CcapEvent::Frame(frame) => upload_texture(&frame),
+    ///         CcapEvent::Error { code, message } => show_error(code, &message),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Errors are process-global like [`Provider::set_error_callback`], so every polling
+    /// provider sees every error, starting with the first `poll_event` call. Native errors
+    /// are only delivered while a global error callback is installed; errors detected by the
+    /// bindings themselves (e.g. [`Provider::set_stall_timeout`]) always are. A failed grab
+    /// is reported as a `CCAP_ERROR_FRAME_CAPTURE_FAILED` error event.
+    ///
+    /// At most 64 errors are kept between calls; when more arrive the oldest are discarded
+    /// and counted by [`Provider::dropped_error_events`].
+    pub fn poll_event(&mut self) -> Option<CcapEvent> {
+        let queued = self
+            .error_events
+            .get_or_insert_with(subscribe_errors)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .queued
+            .pop_front();
+        if let Some((code, message)) = queued {
+            return Some(CcapEvent::Error { code, message });
+        }
+        if !self.is_opened || !self.is_started() {
+            return None;
+        }
+        match self.grab_frame(0) {
+            Ok(frame) => frame.map(CcapEvent::Frame),
+            Err(e) => Some(CcapEvent::Error {
+                code: sys::CcapErrorCode_CCAP_ERROR_FRAME_CAPTURE_FAILED as i32,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    /// Number of errors discarded from this provider's [`Provider::poll_event`] queue
+    /// because it was full
+    pub fn dropped_error_events(&self) -> u64 {
+        self.error_events.as_ref().map_or(0, |queue| {
+            queue.lock().unwrap_or_else(PoisonError::into_inner).dropped
+        })
+    }
+
+    /// Grab a frame and report how long the grab blocked.
+    ///
+    /// The duration is the wall-clock time spent waiting inside [`Provider::grab_frame`],
+    /// which separates a slow camera from slow processing in a capture loop. With
+    /// [`Provider::set_prefetch`] enabled it is the wait for the prefetched frame.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Provider::grab_frame`].
+    pub fn grab_frame_timed(&mut self, timeout_ms: u32) -> Result<Option<(VideoFrame, Duration)>> {
+        let started = Instant::now();
+        let frame = self.grab_frame(timeout_ms)?;
+        Ok(frame.map(|frame| (frame, started.elapsed())))
+    }
+
+    /// Grab a frame that borrows the provider until it is dropped.
+    ///
+    /// The returned [`FrameRef`] is valid only until the next grab: the borrow checker
+    /// rejects any further call on the provider while it is alive, which makes it suitable
+    /// for tight loops that process each frame in place and never keep it around.
+    /// Use [`Provider::grab_frame`] when frames need to outlive the loop iteration.
+    pub fn grab_frame_borrowed(&mut self, timeout_ms: u32) -> Result<Option<FrameRef<'_>>> {
+        Ok(self.grab_frame(timeout_ms)?.map(FrameRef::new))
+    }
+
+    /// Grab the next frame, pass it to `f` and release it, returning what `f` returned.
+    ///
+    /// The pull-style counterpart of [`Provider::set_new_frame_callback`]: the frame is
+    /// only borrowed for the duration of the call and is handed back to the frame pool as
+    /// soon as `f` returns, so it can't be kept by accident. Returns `Ok(None)` on timeout.
+    ///
+    /// ```compile_fail
+    /// # fn check(mut provider: ccap::Provider) {
+    /// // The frame can't outlive the closure
+    /// let frame = provider.with_next_frame(1000, |frame| frame);
+    /// # }
+    /// ```
+    pub fn with_next_frame<R>(
+        &mut self,
+        timeout_ms: u32,
+        f: impl FnOnce(&FrameRef<'_>) -> R,
+    ) -> Result<Option<R>> {
+        Ok(self.grab_frame_borrowed(timeout_ms)?.map(|frame| f(&frame)))
+    }
+
+    /// Grab frames for `duration`, passing each one to `f`, and return how many arrived.
+    ///
+    /// Grab timeouts don't end the capture early; the loop keeps waiting until the deadline,
+    /// so a camera that stalls briefly just yields fewer frames. Capture must already be
+    /// started.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`Provider::grab_frame`], e.g.
+    /// `CcapError::CaptureNotStarted`.
+    pub fn capture_for(
+        &mut self,
+        duration: Duration,
+        mut f: impl FnMut(&FrameRef<'_>),
+    ) -> Result<u64> {
+        grab_until(
+            duration,
+            |timeout_ms| self.grab_frame(timeout_ms),
+            |frame| f(&FrameRef::new(frame)),
+        )
+    }
+
+    /// Lazily grab at most `n` frames, waiting up to `timeout_ms` for each.
+    ///
+    /// Frames are grabbed one at a time as the iterator is advanced, so only the frame
+    /// currently held by the caller is kept in memory. The iterator ends after `n` frames,
+    /// on the first timeout, or right after yielding an error.
+    pub fn grab_n(
+        &mut self,
+        n: usize,
+        timeout_ms: u32,
+    ) -> impl Iterator<Item = Result<VideoFrame>> + '_ {
+        take_grabbed(n, move || self.grab_frame(timeout_ms))
+    }
+
+    /// Open the device if needed, configure it from `prefs` and start capturing.
+    ///
+    /// Picks the mode from the resolutions the device advertises (see
+    /// [`CapturePreferences::max_resolution`]), applies the requested pixel format, raises the
+    /// frame rate if it is below [`CapturePreferences::min_frame_rate`], then starts capture and
+    /// returns the settings the camera actually negotiated, which may differ from the request
+    /// (e.g. a lower frame rate than asked for). The device only applies new settings when
+    /// capture starts, so capture that is already running is stopped and restarted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` for a non-positive or non-finite
+    /// `min_frame_rate`, or the error from opening, configuring or starting the device.
+    pub fn open_and_configure(&mut self, prefs: CapturePreferences) -> Result<CaptureSettings> {
+        if let Some(fps) = prefs.min_frame_rate {
+            validate_frame_rate(fps)?;
+        }
+        self.open_or_noop()?;
+        if self.is_started() {
+            self.stop_capture()?;
+        }
+
+        let info = self.device_info()?;
+        if let Some(mode) = choose_mode(&info.supported_resolutions, prefs.max_resolution) {
+            self.set_resolution(mode.width, mode.height)?;
+        }
+        if let Some(format) = prefs.pixel_format {
+            self.set_pixel_format(format)?;
+        }
+        if let Some(min_fps) = prefs.min_frame_rate {
+            if let Some(fps) = raised_frame_rate(self.frame_rate().ok(), min_fps) {
+                self.set_frame_rate(fps)?;
+            }
+        }
+        self.start_capture()?;
+        self.capture_settings()
+    }
+
+    /// Grab a small RGBA32 preview of what the camera sees, e.g. for a device picker.
+    ///
+    /// Opens the device and starts capture if needed, discards a few frames while auto
+    /// exposure settles, then grabs one frame and scales it with [`OwnedFrame::thumbnail`]
+    /// so its longest side is at most `max_dim`. Capture is stopped and the device closed
+    /// again afterwards if this call opened or started them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if `max_dim` is zero, `CcapError::Timeout` if
+    /// the camera delivers no frame, or the error from opening or starting the device.
+    pub fn thumbnail(&mut self, max_dim: u32) -> Result<OwnedFrame> {
+        if max_dim == 0 {
+            return Err(CcapError::InvalidParameter(
+                "thumbnail size must be non-zero".to_string(),
+            ));
+        }
+        let was_opened = self.is_opened;
+        if !was_opened {
+            self.open()?;
+        }
+        let was_started = self.is_started();
+        let result = if was_started {
+            self.grab_thumbnail(max_dim)
+        } else {
+            self.start_capture()
+                .and_then(|()| self.grab_thumbnail(max_dim))
+        };
+
+        if !was_started {
+            let _ = self.stop_capture();
+        }
+        if !was_opened {
+            unsafe { sys::ccap_provider_close(self.handle) };
+            self.is_opened = false;
+        }
+        result
+    }
 
-        let frame = unsafe { sys::ccap_provider_grab(self.handle, timeout_ms) };
-        if frame.is_null() {
-            return Ok(None);
+    fn grab_thumbnail(&mut self, max_dim: u32) -> Result<OwnedFrame> {
+        for _ in 0..THUMBNAIL_WARMUP_FRAMES {
+            if self.grab_frame(THUMBNAIL_GRAB_TIMEOUT_MS)?.is_none() {
+                break;
+            }
         }
+        let frame = self
+            .grab_frame(THUMBNAIL_GRAB_TIMEOUT_MS)?
+            .ok_or(CcapError::Timeout)?;
+        frame.to_owned_frame()?.thumbnail(max_dim)
+    }
+
+    /// Apply a resolution, output pixel format and frame rate in one call.
+    ///
+    /// Zero dimensions and non-positive frame rates are rejected with
+    /// `CcapError::InvalidParameter` before anything is applied. Read back
+    /// [`Provider::capture_settings`] for what the camera actually negotiated.
+    pub fn apply_capture_settings(&mut self, settings: &CaptureSettings) -> Result<()> {
+        validate_resolution(settings.resolution.width, settings.resolution.height)?;
+        validate_frame_rate(settings.frame_rate)?;
+        self.set_resolution(settings.resolution.width, settings.resolution.height)?;
+        self.set_pixel_format(settings.pixel_format)?;
+        self.set_frame_rate(settings.frame_rate)
+    }
 
-        Ok(Some(VideoFrame::from_c_ptr(frame)))
+    /// Resolution, output pixel format and frame rate currently in effect
+    pub fn capture_settings(&self) -> Result<CaptureSettings> {
+        let (width, height) = self.resolution()?;
+        Ok(CaptureSettings {
+            resolution: Resolution { width, height },
+            pixel_format: self.pixel_format()?,
+            frame_rate: self.frame_rate()?,
+        })
+    }
+
+    /// Apply the parts of `settings` this device supports, skipping the rest.
+    ///
+    /// Unlike [`Provider::apply_capture_settings`], a resolution the device doesn't advertise
+    /// or a pixel format or frame rate it rejects doesn't fail the call: the setting is left
+    /// unchanged and reported in the returned list. This lets one camera's
+    /// [`Provider::capture_settings`] be replayed on a different model.
+    ///
+    /// Zero dimensions and non-positive frame rates are still rejected with
+    /// `CcapError::InvalidParameter` before anything is applied.
+    pub fn apply_supported_capture_settings(
+        &mut self,
+        settings: &CaptureSettings,
+    ) -> Result<Vec<SkippedSetting>> {
+        let Resolution { width, height } = settings.resolution;
+        validate_resolution(width, height)?;
+        validate_frame_rate(settings.frame_rate)?;
+
+        let mut skipped = Vec::new();
+        let advertised = if self.is_opened {
+            self.device_info()
+                .map(|info| info.supported_resolutions)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let resolution_listed = advertised.is_empty() || advertised.contains(&settings.resolution);
+        if !resolution_listed || self.set_resolution(width, height).is_err() {
+            skipped.push(SkippedSetting::Resolution(settings.resolution));
+        }
+        if self.set_pixel_format(settings.pixel_format).is_err() {
+            skipped.push(SkippedSetting::PixelFormat(settings.pixel_format));
+        }
+        if self.set_frame_rate(settings.frame_rate).is_err() {
+            skipped.push(SkippedSetting::FrameRate(settings.frame_rate));
+        }
+        Ok(skipped)
+    }
+
+    /// Configure every provider in `providers` like `settings`, e.g. to match a multi-camera
+    /// rig to one camera's [`Provider::capture_settings`].
+    ///
+    /// Each provider goes through [`Provider::apply_supported_capture_settings`]; the returned
+    /// list holds the settings each provider skipped, in the order of `providers`.
+    ///
+    /// Returns `CcapError::InvalidParameter` for invalid settings, before any provider is
+    /// touched.
+    pub fn apply_capture_settings_to_all(
+        providers: &mut [Provider],
+        settings: &CaptureSettings,
+    ) -> Result<Vec<Vec<SkippedSetting>>> {
+        validate_resolution(settings.resolution.width, settings.resolution.height)?;
+        validate_frame_rate(settings.frame_rate)?;
+        providers
+            .iter_mut()
+            .map(|provider| provider.apply_supported_capture_settings(settings))
+            .collect()
     }
 
     /// Start continuous capture
@@ -419,12 +2194,210 @@ impl Provider {
             return Err(CcapError::CaptureStartFailed);
         }
 
+        self.lock_frame_rate().clear();
+        self.lock_stall().activity(Instant::now());
         Ok(())
     }
 
+    /// Report capture that stops delivering frames without an error.
+    ///
+    /// When capture is started and no frame arrives through [`Provider::grab_frame`] or the
+    /// new-frame callback within `timeout`, a background watchdog reports
+    /// `CCAP_ERROR_FRAME_CAPTURE_TIMEOUT` through the global error callback (see
+    /// [`Provider::set_error_callback`]). Each stall is reported once; the next frame
+    /// re-arms the watchdog. Passing `Duration::ZERO` disables stall detection.
+    pub fn set_stall_timeout(&mut self, timeout: Duration) {
+        let mut stall = self.lock_stall();
+        if timeout.is_zero() {
+            stall.timeout = None;
+            return;
+        }
+        stall.timeout = Some(timeout);
+        if !stall.watchdog_running {
+            stall.watchdog_running = true;
+            let weak = Arc::downgrade(&self.stall);
+            std::thread::spawn(move || run_stall_watchdog(weak));
+        }
+    }
+
+    fn lock_stall(&self) -> std::sync::MutexGuard<'_, StallDetector> {
+        self.stall.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Frame rate actually delivered by the camera, in frames per second.
+    ///
+    /// Unlike [`Provider::frame_rate`], which reports the negotiated value, this is measured
+    /// from the arrival times of the last 30 frames delivered through
+    /// [`Provider::grab_frame`] or the new-frame callback. Returns `None` until at least
+    /// 5 frames have arrived since capture was started.
+    pub fn measured_frame_rate(&self) -> Option<f64> {
+        self.lock_frame_rate().frame_rate()
+    }
+
+    /// Whether capture is started and a frame arrived within the last `window`.
+    ///
+    /// A camera can be started yet deliver nothing, e.g. without camera permission, behind
+    /// a privacy shutter or with a hung driver, so this is a more reliable health signal
+    /// than [`Provider::is_started`]. It tracks the same frames as
+    /// [`Provider::measured_frame_rate`], i.e. those delivered through
+    /// [`Provider::grab_frame`] or the new-frame callback since capture was started.
+    pub fn is_delivering(&self, window: Duration) -> bool {
+        self.is_started()
+            && self
+                .lock_frame_rate()
+                .delivered_within(Instant::now(), window)
+    }
+
+    /// Interval between the timestamps of the two most recent frames.
+    ///
+    /// Tracks the same frames as [`Provider::measured_frame_rate`], using their capture
+    /// timestamps rather than arrival times, so delays in the consumer don't show up.
+    /// Returns `None` until two frames have arrived since capture was started.
+    pub fn last_frame_interval(&self) -> Option<Duration> {
+        self.lock_frame_rate().last_interval()
+    }
+
+    /// Jitter estimate: standard deviation of the last 30 frame intervals.
+    ///
+    /// Close to zero for a camera delivering at a steady rate. Returns `None` until three
+    /// frames have arrived since capture was started.
+    pub fn frame_interval_stddev(&self) -> Option<Duration> {
+        self.lock_frame_rate().interval_stddev()
+    }
+
+    /// Human-readable diagnostic report, meant to be pasted into bug reports.
+    ///
+    /// Covers the library version, device, negotiated settings, backends, available CPU
+    /// features, the measured frame rate and the counters from [`Provider::stats`]. Fields
+    /// that can't be queried are reported as unavailable instead of failing the whole report.
+    /// Only this provider's own state is queried; other cameras aren't enumerated or opened.
+    pub fn describe(&self) -> String {
+        use crate::convert::Convert;
+        use std::fmt::Write;
+
+        fn or_unavailable<T: std::fmt::Display>(value: Result<T>) -> String {
+            value.map_or_else(|e| format!("unavailable ({})", e), |v| v.to_string())
+        }
+
+        let mut report = String::new();
+        let _ = writeln!(report, "ccap version: {}", or_unavailable(Self::version()));
+
+        let device = self.device_info().map(|info| info.name);
+        let index = match self.attached_device {
+            Some(DeviceSelector::Index(index)) if index >= 0 => Some(index),
+            _ => None,
+        };
+        let _ = writeln!(
+            report,
+            "device: {}{}",
+            or_unavailable(device),
+            index.map_or_else(String::new, |index| format!(" (index {})", index))
+        );
+        let _ = writeln!(
+            report,
+            "opened: {}, started: {}",
+            self.is_opened(),
+            self.is_started()
+        );
+        let _ = writeln!(
+            report,
+            "resolution: {}",
+            or_unavailable(
+                self.resolution()
+                    .map(|(width, height)| format!("{}x{}", width, height))
+            )
+        );
+        let _ = writeln!(
+            report,
+            "frame rate: {} (measured: {})",
+            or_unavailable(self.frame_rate()),
+            self.measured_frame_rate()
+                .map_or_else(|| "n/a".to_string(), |fps| format!("{:.2}", fps))
+        );
+        let stats = self.stats();
+        let _ = writeln!(
+            report,
+            "frames: {} captured, {} dropped, {} flipped upright",
+            stats.frames_captured, stats.frames_dropped, stats.auto_orient_flips
+        );
+        let internal = self
+            .get_property(PropertyName::PixelFormatInternal)
+            .map(|value| PixelFormat::from_c_enum(value as u32 as sys::CcapPixelFormat).as_str());
+        let _ = writeln!(
+            report,
+            "pixel format: output {}, internal {}",
+            or_unavailable(self.pixel_format().map(PixelFormat::as_str)),
+            or_unavailable(internal)
+        );
+        let orientation = match self.get_property(PropertyName::FrameOrientation) {
+            Ok(value) if value.is_nan() => Err(CcapError::NotSupported),
+            value => value.map(|value| {
+                format!(
+                    "{:?}",
+                    FrameOrientation::from(value as u32 as sys::CcapFrameOrientation)
+                )
+            }),
+        };
+        let _ = writeln!(report, "orientation: {}", or_unavailable(orientation));
+        let _ = writeln!(
+            report,
+            "windows backend: {}",
+            self.windows_backend
+                .map_or("default", WindowsBackend::as_extra_info)
+        );
+        let _ = writeln!(
+            report,
+            "convert backend: {:?} (AVX2: {}, NEON: {}, Accelerate: {})",
+            Convert::backend(),
+            Convert::has_avx2(),
+            Convert::has_neon(),
+            Convert::has_apple_accelerate()
+        );
+        report
+    }
+
+    /// Device capabilities and negotiated settings as pretty-printed JSON.
+    ///
+    /// The object has a `device` field holding the full [`DeviceInfo`] and a `settings`
+    /// field holding [`Provider::capture_settings`], which is `null` until the device is
+    /// opened. Before opening, the device info comes from enumeration, using the device
+    /// given to [`Provider::attach_device`] or the default camera.
+    #[cfg(feature = "serde")]
+    pub fn capabilities_json(&self) -> Result<String> {
+        let device = match self.device_info() {
+            Ok(info) => info,
+            Err(e) if self.is_opened => return Err(e),
+            Err(_) => select_enumerated_device(Self::get_devices()?, self.attached_device.as_ref())
+                .ok_or(CcapError::NoDeviceFound)?,
+        };
+        let settings = if self.is_opened {
+            Some(self.capture_settings()?)
+        } else {
+            None
+        };
+        capabilities_to_json(&device, settings)
+    }
+
+    fn lock_frame_rate(&self) -> std::sync::MutexGuard<'_, FrameRateTracker> {
+        self.frame_rate
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record_frame_arrival(&self, frame: &VideoFrame) {
+        let now = Instant::now();
+        let timestamp = frame.info().map(|info| info.timestamp).unwrap_or(0);
+        self.lock_frame_rate().record(now, timestamp);
+        self.lock_stall().activity(now);
+    }
+
     /// Stop continuous capture
     pub fn stop_capture(&mut self) -> Result<()> {
         unsafe { sys::ccap_provider_stop(self.handle) };
+        self.lock_stall().disarm();
+        if let Some(prefetch) = &self.prefetch {
+            prefetch.clear();
+        }
         Ok(())
     }
 
@@ -466,17 +2439,37 @@ impl Provider {
         Ok(PixelFormat::from_c_enum(format_val as sys::CcapPixelFormat))
     }
 
+    /// Color range of the YUV data delivered by the camera.
+    ///
+    /// Inferred from the internal (camera-side) pixel format: the `F` variants are full range.
+    /// Returns `None` when the camera delivers RGB or the format isn't known.
+    pub fn detected_color_range(&self) -> Result<Option<ColorRange>> {
+        let format_val = self.get_property(PropertyName::PixelFormatInternal)? as u32;
+        Ok(PixelFormat::from_c_enum(format_val as sys::CcapPixelFormat).color_range())
+    }
+
     /// Get current frame rate (convenience getter)
     pub fn frame_rate(&self) -> Result<f64> {
         self.get_property(PropertyName::FrameRate)
     }
 
+    /// Set the native log level while working with this provider
+    ///
+    /// Convenience alias for [`Utils::set_log_level`]. The native layer keeps a single
+    /// **process-global** log level, not one per provider: the new level applies to every
+    /// `Provider` instance and stays in effect until changed again, so restore the previous
+    /// level (see [`Utils::log_level`]) once you are done debugging this provider.
+    pub fn set_log_level(&self, level: LogLevel) {
+        Utils::set_log_level(level);
+    }
+
     /// Set error callback for camera errors
     ///
     /// # Memory Safety
     ///
     /// This is a **global** callback that persists until replaced or cleared.
     /// Calling this function multiple times will properly clean up the previous callback.
+    /// Use [`Provider::set_scoped_error_callback`] to have it removed automatically.
     ///
     /// **Important**: this callback is process-global (shared by all `Provider` instances).
     /// The last one set wins.
@@ -495,49 +2488,31 @@ impl Provider {
     /// ```
     pub fn set_error_callback<F>(callback: F)
     where
-        F: Fn(i32, &str) + Send + Sync + 'static,
-    {
-        use std::os::raw::c_char;
-
-        type ErrorCallbackBox = Box<dyn Fn(i32, &str) + Send + Sync>;
-
-        unsafe extern "C" fn error_callback_wrapper(
-            error_code: sys::CcapErrorCode,
-            description: *const c_char,
-            user_data: *mut std::ffi::c_void,
-        ) {
-            if user_data.is_null() || description.is_null() {
-                return;
-            }
-
-            // SAFETY: user_data points to Box<ErrorCallbackBox> created below
-            let callback = &**(user_data as *const ErrorCallbackBox);
-            let desc_cstr = std::ffi::CStr::from_ptr(description);
-            if let Ok(desc_str) = desc_cstr.to_str() {
-                callback(error_code as i32, desc_str);
-            }
-        }
-
-        // Clean up old callback if exists (use module-level GLOBAL_ERROR_CALLBACK)
-        if let Ok(mut guard) = GLOBAL_ERROR_CALLBACK.lock() {
-            if let Some(SendSyncPtr(old_ptr)) = guard.take() {
-                unsafe {
-                    let _ = Box::from_raw(old_ptr as *mut ErrorCallbackBox);
-                }
-            }
-
-            // Store new callback - double box for stable pointer
-            let callback_box: ErrorCallbackBox = Box::new(callback);
-            let callback_ptr = Box::into_raw(Box::new(callback_box));
-
-            unsafe {
-                sys::ccap_set_error_callback(
-                    Some(error_callback_wrapper),
-                    callback_ptr as *mut std::ffi::c_void,
-                );
-            }
+        F: Fn(i32, &str) + Send + Sync + 'static,
+    {
+        install_error_callback(callback);
+    }
 
-            *guard = Some(SendSyncPtr(callback_ptr as *mut std::ffi::c_void));
+    /// Set the global error callback until the returned guard is dropped.
+    ///
+    /// Works like [`Provider::set_error_callback`], but dropping the [`ErrorCallbackGuard`]
+    /// unregisters the callback and frees it, e.g. when the component that owns the
+    /// callback shuts down. If another callback was set in the meantime, that one is left
+    /// in place.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let _guard = Provider::set_scoped_error_callback(|code, desc| {
+    ///     eprintln!("Camera error {}: {}", code, desc);
+    /// });
+    /// ```
+    pub fn set_scoped_error_callback<F>(callback: F) -> ErrorCallbackGuard
+    where
+        F: Fn(i32, &str) + Send + Sync + 'static,
+    {
+        ErrorCallbackGuard {
+            callback_ptr: install_error_callback(callback),
         }
     }
 
@@ -555,19 +2530,9 @@ impl Provider {
     ///
     /// This removes the error callback and frees associated memory.
     pub fn clear_error_callback() {
-        type ErrorCallbackBox = Box<dyn Fn(i32, &str) + Send + Sync>;
-
         // Use module-level GLOBAL_ERROR_CALLBACK (same as set_error_callback)
         if let Ok(mut guard) = GLOBAL_ERROR_CALLBACK.lock() {
-            // Always clear the C-side callback even if we don't have a stored Rust callback.
-            unsafe {
-                sys::ccap_set_error_callback(None, ptr::null_mut());
-            }
-            if let Some(SendSyncPtr(old_ptr)) = guard.take() {
-                unsafe {
-                    let _ = Box::from_raw(old_ptr as *mut ErrorCallbackBox);
-                }
-            }
+            remove_error_callback(&mut guard);
         }
     }
 
@@ -587,49 +2552,52 @@ impl Provider {
     ///
     /// On Windows, `extra_info` can be used to force backend selection with values like
     /// `"auto"`, `"msmf"`, `"dshow"`, or `"backend=<value>"`.
+    ///
+    /// Switching devices reuses the native provider. Only when a backend hint is given, or
+    /// reopening fails, is the provider recreated. The frame callback stays registered
+    /// either way.
     pub fn open_with_index_and_extra_info(
         &mut self,
         device_index: i32,
         extra_info: Option<&str>,
         auto_start: bool,
     ) -> Result<()> {
-        let extra_info = optional_c_string(extra_info, "extra info")?;
-
-        // If the previous provider was running, stop it and detach callbacks
-        // before destroying the underlying handle.
-        if !self.handle.is_null() {
-            let _ = self.stop_capture();
-            let _ = self.remove_new_frame_callback();
-            self.cleanup_callback();
-            unsafe {
-                sys::ccap_provider_destroy(self.handle);
+        let extra_info = optional_c_string(self.backend_extra_info(extra_info), "extra info")?;
+        self.invalidate_device_info();
+        self.attached_device = Some(DeviceSelector::Index(device_index));
+
+        // The prefetch worker grabs from the current handle; restart it on the new one.
+        let prefetching = self.prefetch.take().is_some();
+
+        // A backend hint can only be applied by creating a new provider.
+        if extra_info.is_none()
+            && self.reopen_in_place(move |handle| unsafe {
+                sys::ccap_provider_open_by_index(handle, device_index, false)
+            })?
+        {
+            self.set_prefetch(prefetching);
+            if auto_start {
+                self.start_capture()?;
             }
-            self.handle = ptr::null_mut();
-            self.is_opened = false;
-        } else {
-            // Clean up any stale callback allocation even if handle is null.
-            self.cleanup_callback();
+            return Ok(());
         }
 
         // Create a new provider with the specified device index
-        self.handle = unsafe {
+        let created = self.recreate_handle(move || unsafe {
             sys::ccap_provider_create_with_index(
                 device_index,
                 extra_info
                     .as_ref()
                     .map_or(ptr::null(), |value| value.as_ptr()),
             )
-        };
-
-        if self.handle.is_null() {
+        })?;
+        if !created {
             return Err(CcapError::InvalidDevice(format!(
                 "device index {}",
                 device_index
             )));
         }
-
-        // ccap C API contract: create_with_index opens the device.
-        self.is_opened = true;
+        self.set_prefetch(prefetching);
         if !auto_start {
             self.stop_capture()?;
         }
@@ -639,6 +2607,39 @@ impl Provider {
         Ok(())
     }
 
+    /// Wrap a frame callback with the bookkeeping every delivered frame goes through:
+    /// arrival times for `measured_frame_rate`, stall activity and auto-orient.
+    fn instrument_frame_callback<F>(&self, callback: F) -> FrameCallbackBox
+    where
+        F: Fn(&VideoFrame) -> bool + Send + Sync + 'static,
+    {
+        let frame_rate = Arc::clone(&self.frame_rate);
+        let stall = Arc::clone(&self.stall);
+        let auto_orient = Arc::clone(&self.auto_orient);
+        Box::new(move |frame: &mut VideoFrame| {
+            let now = Instant::now();
+            stall
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .activity(now);
+
+            // The flip goes into a copy owned by this wrapper. A frame the callback doesn't
+            // consume goes on to the grab queue untouched and is recorded when grabbed.
+            let flipped = auto_orient.enabled.load(Ordering::Relaxed) && frame.flip_upright();
+            let consumed = callback(frame);
+            if consumed {
+                let timestamp = frame.info().map(|info| info.timestamp).unwrap_or(0);
+                let mut frame_rate = frame_rate.lock().unwrap_or_else(PoisonError::into_inner);
+                frame_rate.record(now, timestamp);
+                frame_rate.count(frame.index());
+                if flipped {
+                    auto_orient.flips.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            consumed
+        })
+    }
+
     /// Set a callback for new frame notifications
     ///
     /// The callback receives a reference to the captured frame and returns `true`
@@ -663,31 +2664,12 @@ impl Provider {
     {
         use std::os::raw::c_void;
 
-        // Type alias for the boxed callback to ensure consistency
-        type CallbackBox = Box<dyn Fn(&VideoFrame) -> bool + Send + Sync>;
-
         // Clean up old callback if exists
         self.cleanup_callback();
 
-        unsafe extern "C" fn new_frame_callback_wrapper(
-            frame: *const sys::CcapVideoFrame,
-            user_data: *mut c_void,
-        ) -> bool {
-            if user_data.is_null() || frame.is_null() {
-                return false;
-            }
-
-            // SAFETY: user_data points to a Box<CallbackBox> that we created below
-            let callback = &**(user_data as *const CallbackBox);
-
-            // Create a temporary VideoFrame wrapper that doesn't own the frame
-            let video_frame = VideoFrame::from_c_ptr_ref(frame as *mut sys::CcapVideoFrame);
-            callback(&video_frame)
-        }
-
         // Box the callback as a trait object, then box again to get a thin pointer
         // This ensures we can safely convert to/from *mut c_void
-        let callback_box: CallbackBox = Box::new(callback);
+        let callback_box = self.instrument_frame_callback(callback);
         let callback_ptr = Box::into_raw(Box::new(callback_box));
 
         let success = unsafe {
@@ -712,6 +2694,73 @@ impl Provider {
         }
     }
 
+    /// Set a callback that receives each new frame as an [`OwnedFrame`].
+    ///
+    /// Every frame is copied before `callback` runs, so the handler can keep it, e.g. push
+    /// it into a queue or channel or hand it to another thread. This costs one copy per
+    /// frame; use [`Provider::set_new_frame_callback`] for zero-copy access. Replaces any
+    /// existing frame callback. Frames that can't be copied (e.g. empty frames) are skipped.
+    pub fn set_owned_frame_callback<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: Fn(OwnedFrame) + Send + Sync + 'static,
+    {
+        self.set_new_frame_callback(move |frame| {
+            if let Ok(owned) = frame.to_owned_frame() {
+                callback(owned);
+            }
+            true
+        })
+    }
+
+    /// Set a callback that sees the `k` most recent frames each time a new frame arrives.
+    ///
+    /// The callback receives owned copies ordered oldest to newest; the window grows until
+    /// it holds `k` frames and then slides by one per frame. This replaces any existing
+    /// frame callback. Frames that can't be copied (e.g. empty frames) are skipped.
+    ///
+    /// Returns `CcapError::InvalidParameter` if `k` is zero.
+    pub fn set_windowed_frame_callback<F>(&mut self, k: usize, callback: F) -> Result<()>
+    where
+        F: Fn(&[OwnedFrame]) + Send + Sync + 'static,
+    {
+        if k == 0 {
+            return Err(CcapError::InvalidParameter(
+                "frame window size must be greater than 0".to_string(),
+            ));
+        }
+
+        let window = Mutex::new(FrameWindow::new(k));
+        self.set_new_frame_callback(move |frame| {
+            if let Ok(owned) = frame.to_owned_frame() {
+                let mut window = window.lock().unwrap_or_else(PoisonError::into_inner);
+                callback(window.push(owned));
+            }
+            true
+        })
+    }
+
+    /// Broadcast every captured frame to any number of subscribers.
+    ///
+    /// Installs a single frame callback (replacing any existing one) that copies each frame
+    /// into an [`OwnedFrame`] and sends it to all receivers created with
+    /// [`FrameSubscriptions::subscribe`]. Each receiver buffers up to `capacity` frames; a
+    /// slow receiver misses the oldest frames instead of blocking capture, and the number it
+    /// missed is reported by [`FrameReceiver::lagged`](crate::FrameReceiver::lagged).
+    ///
+    /// The callback holds the only sender. Receivers see the end of the stream once the
+    /// callback is removed or replaced, or the provider is dropped.
+    pub fn broadcast(&mut self, capacity: usize) -> Result<FrameSubscriptions> {
+        let sender = FrameBroadcast::new(capacity);
+        let subscriptions = sender.subscriptions();
+        self.set_new_frame_callback(move |frame| {
+            if let Ok(owned) = frame.to_owned_frame() {
+                sender.send(owned);
+            }
+            true
+        })?;
+        Ok(subscriptions)
+    }
+
     /// Remove frame callback
     pub fn remove_new_frame_callback(&mut self) -> Result<()> {
         let success = unsafe {
@@ -728,14 +2777,11 @@ impl Provider {
 
     /// Clean up callback pointer
     fn cleanup_callback(&mut self) {
-        // Type alias must match what we used in set_new_frame_callback
-        type CallbackBox = Box<dyn Fn(&VideoFrame) -> bool + Send + Sync>;
-
         if let Some(callback_ptr) = self.callback_ptr.take() {
             unsafe {
                 // SAFETY: callback_ptr was created with Box::into_raw(Box::new(callback_box))
-                // where callback_box is a CallbackBox
-                let _ = Box::from_raw(callback_ptr as *mut CallbackBox);
+                // where callback_box is a FrameCallbackBox
+                let _ = Box::from_raw(callback_ptr as *mut FrameCallbackBox);
             }
         }
     }
@@ -745,6 +2791,8 @@ impl Drop for Provider {
     fn drop(&mut self) {
         // Clean up callback first
         self.cleanup_callback();
+        // The prefetch worker uses the handle, so it must stop before the handle is destroyed.
+        self.prefetch = None;
 
         if !self.handle.is_null() {
             unsafe {
@@ -754,3 +2802,1017 @@ impl Drop for Provider {
         }
     }
 }
+
+/// Fluent configuration for a [`Provider`], ending in [`ProviderBuilder::open`].
+///
+/// ```no_run
+/// use ccap::{PixelFormat, ProviderBuilder};
+///
+/// let mut provider = ProviderBuilder::new()
+///     .device_index(0)
+///     .resolution(1920, 1080)
+///     .pixel_format(PixelFormat::Bgra32)
+///     .frame_rate(60.0)
+///     .open()?;
+/// provider.start()?;
+/// # Ok::<(), ccap::CcapError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProviderBuilder {
+    device: Option<DeviceSelector>,
+    resolution: Option<Resolution>,
+    pixel_format: Option<PixelFormat>,
+    frame_rate: Option<f64>,
+    open_timeout: Option<Duration>,
+}
+
+impl ProviderBuilder {
+    /// Builder for the default camera with the device's default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the device at `index` in the device list; `-1` selects the default camera
+    pub fn device_index(mut self, index: i32) -> Self {
+        self.device = Some(DeviceSelector::Index(index));
+        self
+    }
+
+    /// Open the device with this name, as reported by device enumeration
+    pub fn device_name(mut self, name: impl Into<String>) -> Self {
+        self.device = Some(DeviceSelector::Name(name.into()));
+        self
+    }
+
+    /// Requested capture resolution
+    pub fn resolution(mut self, width: u32, height: u32) -> Self {
+        self.resolution = Some(Resolution { width, height });
+        self
+    }
+
+    /// Requested frame rate
+    pub fn frame_rate(mut self, fps: f64) -> Self {
+        self.frame_rate = Some(fps);
+        self
+    }
+
+    /// Requested output pixel format
+    pub fn pixel_format(mut self, format: PixelFormat) -> Self {
+        self.pixel_format = Some(format);
+        self
+    }
+
+    /// Bound the device open, see [`Provider::set_open_timeout`]
+    pub fn open_timeout(mut self, timeout: Duration) -> Self {
+        self.open_timeout = Some(timeout);
+        self
+    }
+
+    /// Apply the configuration and open the device. Capture isn't started.
+    ///
+    /// All values are validated before the device is touched. The pixel format, resolution
+    /// and frame rate are then applied in that order, before opening, so the camera is
+    /// opened straight into the requested mode.
+    ///
+    /// # Errors
+    ///
+    /// A rejected value is reported as `CcapError::InvalidParameter` with a message naming
+    /// the setting, e.g. `"frame rate: ..."`. Other errors, such as a failed open, are
+    /// returned unchanged.
+    pub fn open(self) -> Result<Provider> {
+        if let Some(Resolution { width, height }) = self.resolution {
+            validate_resolution(width, height).map_err(|e| setting_error("resolution", e))?;
+        }
+        if let Some(fps) = self.frame_rate {
+            validate_frame_rate(fps).map_err(|e| setting_error("frame rate", e))?;
+        }
+
+        let mut provider = match self.device {
+            Some(device) => Provider::attach_device(device)?,
+            None => Provider::new()?,
+        };
+        if let Some(timeout) = self.open_timeout {
+            provider.set_open_timeout(timeout);
+        }
+        if let Some(format) = self.pixel_format {
+            provider
+                .set_pixel_format(format)
+                .map_err(|e| setting_error("pixel format", e))?;
+        }
+        if let Some(Resolution { width, height }) = self.resolution {
+            provider
+                .set_resolution(width, height)
+                .map_err(|e| setting_error("resolution", e))?;
+        }
+        if let Some(fps) = self.frame_rate {
+            provider
+                .set_frame_rate(fps)
+                .map_err(|e| setting_error("frame rate", e))?;
+        }
+        provider.open()?;
+        Ok(provider)
+    }
+}
+
+/// Prefix an `InvalidParameter` message with the builder setting that was rejected
+fn setting_error(setting: &str, error: CcapError) -> CcapError {
+    match error {
+        CcapError::InvalidParameter(message) => {
+            CcapError::InvalidParameter(format!("{}: {}", setting, message))
+        }
+        error => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Serializes tests that install the process-global error callback or report errors
+    static GLOBAL_ERROR_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock_global_errors() -> std::sync::MutexGuard<'static, ()> {
+        GLOBAL_ERROR_TEST_LOCK
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[test]
+    fn test_frame_rate_tracker_known_interval() {
+        let mut tracker = FrameRateTracker::default();
+        let start = Instant::now();
+        for i in 0..FRAME_RATE_MIN_SAMPLES - 1 {
+            tracker.record(start + Duration::from_millis(40 * i as u64), 0);
+        }
+        assert_eq!(tracker.frame_rate(), None);
+
+        for i in FRAME_RATE_MIN_SAMPLES - 1..FRAME_RATE_WINDOW * 2 {
+            tracker.record(start + Duration::from_millis(40 * i as u64), 0);
+        }
+        let fps = tracker.frame_rate().unwrap();
+        assert!((fps - 25.0).abs() < 0.01, "measured {} fps", fps);
+
+        tracker.clear();
+        assert_eq!(tracker.frame_rate(), None);
+    }
+
+    #[test]
+    fn test_frame_rate_tracker_delivered_within() {
+        let window = Duration::from_millis(500);
+        let mut tracker = FrameRateTracker::default();
+        let start = Instant::now();
+        assert!(!tracker.delivered_within(start, window));
+
+        tracker.record(start, 0);
+        assert!(tracker.delivered_within(start + window / 2, window));
+        assert!(!tracker.delivered_within(start + window * 2, window));
+
+        tracker.clear();
+        assert!(!tracker.delivered_within(start, window));
+    }
+
+    #[test]
+    fn test_frame_rate_tracker_counts_dropped_frames() {
+        let mut tracker = FrameRateTracker::default();
+        for index in [1, 2, 5, 6, 10] {
+            tracker.count(index);
+        }
+        assert_eq!((tracker.captured, tracker.dropped), (5, 5));
+
+        // A restarted sequence isn't a gap, and clearing keeps the totals.
+        tracker.count(0);
+        tracker.clear();
+        tracker.count(7);
+        assert_eq!((tracker.captured, tracker.dropped), (7, 5));
+    }
+
+    #[test]
+    fn test_frame_rate_tracker_intervals() {
+        let mut tracker = FrameRateTracker::default();
+        let start = Instant::now();
+        tracker.record(start, 1_000_000_000);
+        assert_eq!(tracker.last_interval(), None);
+
+        for i in 1..FRAME_RATE_WINDOW as u64 * 2 {
+            tracker.record(start, 1_000_000_000 + i * 33_333_333);
+        }
+        assert_eq!(
+            tracker.last_interval(),
+            Some(Duration::from_nanos(33_333_333))
+        );
+        assert_eq!(tracker.interval_stddev(), Some(Duration::ZERO));
+
+        // Alternating 30 ms / 50 ms intervals deviate 10 ms from their mean.
+        let mut timestamp = 0;
+        tracker.clear();
+        for i in 0..=FRAME_RATE_WINDOW as u64 {
+            timestamp += if i % 2 == 0 { 30_000_000 } else { 50_000_000 };
+            tracker.record(start, timestamp);
+        }
+        let jitter = tracker.interval_stddev().unwrap().as_secs_f64();
+        assert!((jitter - 0.010).abs() < 1e-6, "jitter {} s", jitter);
+
+        // A clock going backwards restarts the sequence instead of producing an interval.
+        tracker.record(start, 0);
+        assert_eq!(tracker.intervals.len(), FRAME_RATE_WINDOW);
+        assert_eq!(tracker.last_timestamp, Some(0));
+    }
+
+    #[test]
+    fn test_deliverable_formats_add_rgb_conversions() {
+        let formats = deliverable_from_native(&[PixelFormat::Nv12]);
+        assert_eq!(formats[0], PixelFormat::Nv12);
+        assert!(formats.contains(&PixelFormat::Rgba32));
+        assert!(!formats.contains(&PixelFormat::I420));
+
+        let formats = deliverable_from_native(&[PixelFormat::Bgr24, PixelFormat::Yuyv]);
+        assert_eq!(
+            formats,
+            [
+                PixelFormat::Bgr24,
+                PixelFormat::Yuyv,
+                PixelFormat::Rgb24,
+                PixelFormat::Rgba32,
+                PixelFormat::Bgra32,
+            ]
+        );
+        assert!(deliverable_from_native(&[PixelFormat::Unknown]).is_empty());
+    }
+
+    #[test]
+    fn test_device_info_is_queried_once_until_invalidated() {
+        let cache = Mutex::new(None);
+        let queries = std::cell::Cell::new(0);
+        let query = || {
+            queries.set(queries.get() + 1);
+            Ok(DeviceInfo {
+                name: "Mock Camera".to_string(),
+                supported_pixel_formats: vec![PixelFormat::Nv12],
+                supported_resolutions: Vec::new(),
+                name_bytes: Vec::new(),
+            })
+        };
+
+        assert!(cached_device_info(&cache, || Err(CcapError::DeviceOpenFailed)).is_err());
+        assert_eq!(
+            cached_device_info(&cache, query).unwrap().name,
+            "Mock Camera"
+        );
+        assert_eq!(
+            cached_device_info(&cache, query).unwrap().name,
+            "Mock Camera"
+        );
+        assert_eq!(queries.get(), 1);
+
+        *cache.lock().unwrap() = None;
+        cached_device_info(&cache, query).unwrap();
+        assert_eq!(queries.get(), 2);
+    }
+
+    #[test]
+    fn test_frame_callback_sees_bottom_to_top_frame_upright() {
+        // 2x2 RGB24 stored bottom-up: the bottom row (2) comes first
+        let mut pixels = vec![2u8, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1];
+        let mut info = sys::CcapVideoFrameInfo {
+            width: 2,
+            height: 2,
+            pixelFormat: PixelFormat::Rgb24.to_c_enum(),
+            sizeInBytes: 12,
+            orientation: sys::CcapFrameOrientation_CCAP_FRAME_ORIENTATION_BOTTOM_TO_TOP,
+            ..Default::default()
+        };
+        info.data[0] = pixels.as_mut_ptr();
+        info.stride[0] = 6;
+
+        let mut provider = Provider::new().unwrap();
+        provider.set_auto_orient(true);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let callback = provider.instrument_frame_callback(move |frame| {
+            let info = frame.info().unwrap();
+            assert_eq!(info.orientation, FrameOrientation::TopToBottom);
+            sink.lock()
+                .unwrap()
+                .push(info.data_planes[0].unwrap().to_vec());
+            true
+        });
+
+        assert!(callback(&mut VideoFrame::from_info_for_test(info)));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![vec![1u8, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2]]
+        );
+        assert_eq!(provider.stats().auto_orient_flips, 1);
+        // The native buffer is left as the library delivered it
+        assert_eq!(pixels, vec![2u8, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_open_remembers_selected_device() {
+        // A constructor either records the selection or reports the device it couldn't create.
+        match Provider::with_device(2) {
+            Ok(provider) => assert_eq!(provider.attached_device, Some(DeviceSelector::Index(2))),
+            Err(e) => assert!(
+                matches!(&e, CcapError::InvalidDevice(device) if device == "device index 2"),
+                "{:?}",
+                e
+            ),
+        }
+        // Without a camera the open fails, but the selection is still recorded for `open`.
+        let mut provider = Provider::new().unwrap();
+        let _ = provider.open_with_index(3, false);
+        assert_eq!(provider.attached_device, Some(DeviceSelector::Index(3)));
+    }
+
+    #[test]
+    fn test_broadcast_ends_when_callback_is_removed() {
+        let mut provider = Provider::new().unwrap();
+        let subscriptions = provider.broadcast(4).unwrap();
+        let receiver = subscriptions.subscribe();
+        provider.remove_new_frame_callback().unwrap();
+        assert!(receiver.recv().is_none());
+    }
+
+    #[test]
+    fn test_describe_reports_selected_index() {
+        let mut provider = Provider::new().unwrap();
+        assert!(!provider.describe().contains("(index"));
+        provider.attached_device = Some(DeviceSelector::Index(2));
+        assert!(provider.describe().contains(" (index 2)\n"));
+    }
+
+    #[test]
+    fn test_recreated_provider_keeps_frame_callback() {
+        let mut provider = Provider::new().unwrap();
+        provider.set_new_frame_callback(|_| true).unwrap();
+        let callback_ptr = provider.callback_ptr;
+
+        // A backend hint forces the native provider to be recreated. Whether or not the open
+        // succeeds, the provider ends up with a handle that has the callback registered.
+        let _ = provider.open_with_index_and_extra_info(0, Some("auto"), false);
+        assert!(!provider.handle.is_null());
+        assert_eq!(provider.callback_ptr, callback_ptr);
+
+        // A create that returns no provider keeps the callback for the replacement handle
+        assert!(!provider.recreate_handle(ptr::null_mut).unwrap());
+        assert!(!provider.handle.is_null());
+        assert!(!provider.is_opened());
+        assert_eq!(provider.callback_ptr, callback_ptr);
+
+        assert!(provider
+            .recreate_handle(|| unsafe { sys::ccap_provider_create() })
+            .unwrap());
+        assert_eq!(provider.callback_ptr, callback_ptr);
+    }
+
+    #[test]
+    fn test_with_device_dispatches_on_selector() {
+        // Without the device the error names what was looked up, which tells the paths apart.
+        let name = String::from("ccap-test-missing-device");
+        match Provider::with_device(&name) {
+            Ok(provider) => assert_eq!(
+                provider.attached_device,
+                Some(DeviceSelector::Name(name.clone()))
+            ),
+            Err(e) => assert!(
+                matches!(&e, CcapError::InvalidDevice(device) if device == &name),
+                "{:?}",
+                e
+            ),
+        }
+        match Provider::with_device(1) {
+            Ok(provider) => assert_eq!(provider.attached_device, Some(DeviceSelector::Index(1))),
+            Err(e) => assert!(
+                matches!(&e, CcapError::InvalidDevice(device) if device == "device index 1"),
+                "{:?}",
+                e
+            ),
+        }
+    }
+
+    #[test]
+    fn test_open_reopens_non_utf8_device_name() {
+        let raw = b"ccap-test-\xffcamera".to_vec();
+        let lossy = String::from_utf8_lossy(&raw).into_owned();
+
+        // The bytes are kept for `open`; the lossy name would no longer match the device.
+        let mut provider = Provider::new().unwrap();
+        let _ = provider.open_named_device(&raw, None, false);
+        let selector = Some(DeviceSelector::NameBytes(raw.clone()));
+        assert_eq!(provider.attached_device, selector);
+        if !provider.is_opened() {
+            match provider.open() {
+                Err(CcapError::InvalidDevice(device)) => assert_eq!(device, lossy),
+                other => panic!("Expected InvalidDevice, got {:?}", other),
+            }
+            assert_eq!(provider.attached_device, selector);
+        }
+
+        // UTF-8 names still use the readable selector
+        assert_eq!(
+            name_selector(b"Mock Camera"),
+            DeviceSelector::Name("Mock Camera".to_string())
+        );
+
+        // Two devices whose names only differ in invalid bytes are told apart
+        #[cfg(feature = "serde")]
+        {
+            let device = |name_bytes: &[u8]| DeviceInfo {
+                name: String::from_utf8_lossy(name_bytes).into_owned(),
+                supported_pixel_formats: Vec::new(),
+                supported_resolutions: Vec::new(),
+                name_bytes: name_bytes.to_vec(),
+            };
+            let devices = vec![device(b"ccap-test-\xfecamera"), device(&raw)];
+            let found = select_enumerated_device(devices, selector.as_ref()).unwrap();
+            assert_eq!(found.name_bytes(), raw.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_with_device_name_os_rejects_null_byte() {
+        match Provider::with_device_name_os("bad\0name") {
+            Err(CcapError::InvalidParameter(msg)) => {
+                assert_eq!(msg, "device name contains null byte")
+            }
+            other => panic!("Expected InvalidParameter, got {:?}", other.err()),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_capabilities_json_lists_device_and_modes() {
+        let devices = vec![
+            DeviceInfo {
+                name: "Mock Camera".to_string(),
+                supported_pixel_formats: vec![PixelFormat::Nv12, PixelFormat::Rgb24],
+                supported_resolutions: vec![Resolution {
+                    width: 1280,
+                    height: 720,
+                }],
+                name_bytes: Vec::new(),
+            },
+            DeviceInfo {
+                name: "Other Camera".to_string(),
+                supported_pixel_formats: Vec::new(),
+                supported_resolutions: Vec::new(),
+                name_bytes: Vec::new(),
+            },
+        ];
+        let selector = DeviceSelector::Name("Mock Camera".to_string());
+        let device = select_enumerated_device(devices, Some(&selector)).unwrap();
+
+        let json = capabilities_to_json(&device, None).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["device"]["name"], "Mock Camera");
+        assert_eq!(
+            value["device"]["supported_resolutions"][0],
+            serde_json::json!({ "width": 1280, "height": 720 })
+        );
+        assert!(value["settings"].is_null());
+    }
+
+    #[test]
+    fn test_choose_mode_honors_max_resolution() {
+        let mode = |width, height| Resolution { width, height };
+        let modes = [
+            mode(640, 480),
+            mode(1920, 1080),
+            mode(1280, 720),
+            mode(320, 240),
+        ];
+
+        assert_eq!(choose_mode(&modes, None), Some(mode(1920, 1080)));
+        assert_eq!(
+            choose_mode(&modes, Some(mode(1280, 1024))),
+            Some(mode(1280, 720))
+        );
+        assert_eq!(
+            choose_mode(&modes, Some(mode(1280, 720))),
+            Some(mode(1280, 720))
+        );
+        // Nothing fits: fall back to the smallest mode rather than failing
+        assert_eq!(
+            choose_mode(&modes, Some(mode(160, 120))),
+            Some(mode(320, 240))
+        );
+        assert_eq!(choose_mode(&[], Some(mode(640, 480))), None);
+    }
+
+    #[test]
+    fn test_frame_window_grows_then_slides() {
+        let frame = |index| {
+            OwnedFrame::from_planes(1, 1, PixelFormat::Rgb24, vec![vec![0u8; 3]], &[3])
+                .unwrap()
+                .with_frame_index(index)
+        };
+        let indices = |frames: &[OwnedFrame]| -> Vec<u64> {
+            frames.iter().map(OwnedFrame::frame_index).collect()
+        };
+
+        let mut window = FrameWindow::new(3);
+        assert_eq!(indices(window.push(frame(0))), vec![0]);
+        assert_eq!(indices(window.push(frame(1))), vec![0, 1]);
+        assert_eq!(indices(window.push(frame(2))), vec![0, 1, 2]);
+        assert_eq!(indices(window.push(frame(3))), vec![1, 2, 3]);
+        assert_eq!(indices(window.push(frame(4))), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_raised_frame_rate_only_raises() {
+        assert_eq!(raised_frame_rate(Some(30.0), 15.0), None);
+        assert_eq!(raised_frame_rate(Some(30.0), 30.0), None);
+        assert_eq!(raised_frame_rate(Some(10.0), 15.0), Some(15.0));
+        assert_eq!(raised_frame_rate(None, 15.0), Some(15.0));
+    }
+
+    #[test]
+    fn test_check_output_format() {
+        let supported = [PixelFormat::Yuyv, PixelFormat::Nv12];
+
+        assert!(check_output_format(&supported, PixelFormat::Nv12, false).is_ok());
+        assert!(check_output_format(&supported, PixelFormat::Bgr24, true).is_ok());
+        assert!(matches!(
+            check_output_format(&supported, PixelFormat::Bgr24, false),
+            Err(CcapError::NotSupported)
+        ));
+        assert!(matches!(
+            check_output_format(&supported, PixelFormat::I420, true),
+            Err(CcapError::NotSupported)
+        ));
+        // A device that lists no formats isn't second-guessed
+        assert!(check_output_format(&[], PixelFormat::I420, false).is_ok());
+    }
+
+    #[test]
+    fn test_stall_detector_reports_once_per_stall() {
+        let timeout = Duration::from_millis(100);
+        let start = Instant::now();
+        let mut stall = StallDetector {
+            timeout: Some(timeout),
+            ..StallDetector::default()
+        };
+
+        // Not capturing yet: nothing to report
+        assert!(!stall.check(start + timeout * 10));
+
+        stall.activity(start);
+        assert!(!stall.check(start + timeout / 2));
+        // Frames stop arriving
+        assert!(stall.check(start + timeout));
+        assert!(!stall.check(start + timeout * 3));
+
+        // A new frame re-arms detection
+        stall.activity(start + timeout * 4);
+        assert!(stall.check(start + timeout * 5));
+
+        stall.disarm();
+        assert!(!stall.check(start + timeout * 20));
+    }
+
+    #[test]
+    fn test_stall_report_lets_error_callback_reenter() {
+        let _lock = lock_global_errors();
+        let mut provider = Provider::new().expect("failed to create provider");
+        let stall = Arc::clone(&provider.stall);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let sender = Mutex::new(sender);
+        Provider::set_error_callback(move |_, description| {
+            if description.contains("no frame received") {
+                // What `set_stall_timeout` and `clear_error_callback` lock
+                stall.lock().unwrap().timeout = None;
+                Provider::clear_error_callback();
+                let _ = sender.lock().unwrap().send(());
+            }
+        });
+
+        provider.lock_stall().activity(Instant::now());
+        provider.set_stall_timeout(Duration::from_millis(10));
+        let reentered = receiver.recv_timeout(Duration::from_secs(5));
+        Provider::clear_error_callback();
+        assert!(reentered.is_ok(), "error callback deadlocked");
+    }
+
+    #[test]
+    fn test_error_callback_can_replace_itself() {
+        const MESSAGE: &str = "reentrant error callback test";
+        let _lock = lock_global_errors();
+        let calls = Arc::new(AtomicU64::new(0));
+        let counted = Arc::clone(&calls);
+        Provider::set_error_callback(move |_, description| {
+            if description == MESSAGE {
+                counted.fetch_add(1, Ordering::SeqCst);
+                // Replacing the running callback frees it only after this call returns
+                Provider::set_error_callback(|_, _| {});
+            }
+        });
+
+        call_error_callback(0, MESSAGE);
+        call_error_callback(0, MESSAGE);
+        Provider::clear_error_callback();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_sorted_modes_dedups() {
+        let resolution = |width, height| Resolution { width, height };
+        let modes = sorted_modes(&[
+            resolution(1920, 1080),
+            resolution(640, 480),
+            resolution(1920, 1080),
+            resolution(640, 360),
+        ]);
+        assert_eq!(
+            modes,
+            vec![
+                resolution(640, 360),
+                resolution(640, 480),
+                resolution(1920, 1080)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_take_grabbed_stops_at_n_or_timeout() {
+        let mut next = 0;
+        let frames: Vec<u32> = take_grabbed(3, || {
+            next += 1;
+            Ok(Some(next))
+        })
+        .map(Result::unwrap)
+        .collect();
+        assert_eq!(frames, vec![1, 2, 3]);
+
+        let mut queued = vec![Ok(None), Ok(Some(2)), Ok(Some(1))];
+        let frames: Vec<u32> = take_grabbed(5, || queued.pop().unwrap())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(frames, vec![1, 2]);
+
+        let mut grabs = 0;
+        let results: Vec<Result<u32>> = take_grabbed(5, || {
+            grabs += 1;
+            Err(CcapError::FrameGrabFailed)
+        })
+        .collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(grabs, 1);
+    }
+
+    #[test]
+    fn test_grab_until_retries_timeouts_until_deadline() {
+        let mut grabs = 0;
+        let mut seen = Vec::new();
+        let count = grab_until(
+            Duration::from_millis(50),
+            |timeout_ms| {
+                assert!(timeout_ms > 0 && timeout_ms <= 50);
+                grabs += 1;
+                std::thread::sleep(Duration::from_millis(5));
+                // Every other grab times out
+                Ok(if grabs % 2 == 0 { Some(grabs) } else { None })
+            },
+            |frame| seen.push(frame),
+        )
+        .unwrap();
+        assert!(count >= 2, "count {}", count);
+        assert_eq!(count, seen.len() as u64);
+        assert!(seen.iter().all(|frame| frame % 2 == 0));
+
+        let result = grab_until(
+            Duration::from_secs(5),
+            |_| Err::<Option<u32>, _>(CcapError::CaptureNotStarted),
+            |_| unreachable!(),
+        );
+        assert!(matches!(result, Err(CcapError::CaptureNotStarted)));
+    }
+
+    #[test]
+    fn test_grab_surfaces_or_skips_zero_byte_frames() {
+        let mut pixels = vec![0u8; 12];
+        let data = pixels.as_mut_ptr();
+        let native_frame = |index: u64, size: u32| {
+            VideoFrame::from_info_for_test(sys::CcapVideoFrameInfo {
+                data: [data, ptr::null_mut(), ptr::null_mut()],
+                stride: [6, 0, 0],
+                pixelFormat: PixelFormat::Rgb24.to_c_enum(),
+                width: 2,
+                height: 2,
+                sizeInBytes: size,
+                frameIndex: index,
+                ..Default::default()
+            })
+        };
+        // A zero-byte frame followed by a valid one, as a flaky driver would deliver them
+        let queue = std::cell::RefCell::new(VecDeque::new());
+        let next = |_: &Provider, _: u32| queue.borrow_mut().pop_front();
+        let mut provider = Provider::new().unwrap();
+
+        queue
+            .borrow_mut()
+            .extend([native_frame(1, 0), native_frame(2, 12)]);
+        let empty = provider.grab_from(1000, next).unwrap();
+        assert_eq!(queue.borrow().len(), 1);
+        assert!(empty.is_empty());
+        assert!(matches!(
+            empty.data(),
+            Err(CcapError::InternalError(msg)) if msg == "empty frame"
+        ));
+
+        queue.borrow_mut().clear();
+        queue
+            .borrow_mut()
+            .extend([native_frame(3, 0), native_frame(4, 12)]);
+        provider.set_skip_empty_frames(true);
+        let frame = provider.grab_from(1000, next).unwrap();
+        assert_eq!(frame.index(), 4);
+        assert_eq!(frame.data().unwrap().len(), 12);
+        assert!(queue.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_provider_log_level_toggle_around_capture() {
+        /// Restores the log level in effect when it was created, even if the test panics
+        struct RestoreLogLevel(Option<LogLevel>);
+
+        impl Drop for RestoreLogLevel {
+            fn drop(&mut self) {
+                // Without a level set through the crate, the release default of ccap is assumed.
+                Utils::set_log_level(self.0.unwrap_or(LogLevel::Error));
+            }
+        }
+
+        // No other test in this binary changes the process-wide level.
+        let _restore = RestoreLogLevel(Utils::log_level());
+        let mut provider = Provider::new().unwrap();
+        provider.set_log_level(LogLevel::Verbose);
+        assert_eq!(Utils::log_level(), Some(LogLevel::Verbose));
+
+        let mut pixels = vec![0u8; 12];
+        let frame = VideoFrame::from_info_for_test(sys::CcapVideoFrameInfo {
+            data: [pixels.as_mut_ptr(), ptr::null_mut(), ptr::null_mut()],
+            stride: [6, 0, 0],
+            pixelFormat: PixelFormat::Rgb24.to_c_enum(),
+            width: 2,
+            height: 2,
+            sizeInBytes: 12,
+            ..Default::default()
+        });
+        let queue = std::cell::RefCell::new(VecDeque::from([frame]));
+        let next = |_: &Provider, _: u32| queue.borrow_mut().pop_front();
+        let grabbed = provider.grab_from(0, next).unwrap();
+        assert_eq!(grabbed.data().unwrap().len(), 12);
+
+        provider.set_log_level(LogLevel::Warning);
+        assert_eq!(Utils::log_level(), Some(LogLevel::Warning));
+    }
+
+    #[test]
+    fn test_frames_declined_by_callback_are_recorded_once() {
+        let mut pixels = vec![0u8; 12];
+        let data = pixels.as_mut_ptr();
+        let native_frame = |index: u64| {
+            VideoFrame::from_info_for_test(sys::CcapVideoFrameInfo {
+                data: [data, ptr::null_mut(), ptr::null_mut()],
+                stride: [6, 0, 0],
+                pixelFormat: PixelFormat::Rgb24.to_c_enum(),
+                width: 2,
+                height: 2,
+                sizeInBytes: 12,
+                frameIndex: index,
+                timestamp: index * 1_000_000,
+                ..Default::default()
+            })
+        };
+        let mut provider = Provider::new().unwrap();
+        // Even frames are consumed; odd ones go on to the grab queue.
+        let callback = provider.instrument_frame_callback(|frame| frame.index() % 2 == 0);
+        let queue = std::cell::RefCell::new(VecDeque::new());
+        let next = |_: &Provider, _: u32| queue.borrow_mut().pop_front();
+
+        for index in 1..=6 {
+            let mut frame = native_frame(index);
+            if !callback(&mut frame) {
+                queue.borrow_mut().push_back(frame);
+                assert_eq!(provider.grab_from(0, next).unwrap().index(), index);
+            }
+        }
+
+        let stats = provider.stats();
+        assert_eq!(stats.frames_captured, 6);
+        assert_eq!(stats.frames_dropped, 0);
+        let frame_rate = provider.lock_frame_rate();
+        assert_eq!(frame_rate.arrivals.len(), 6);
+        assert_eq!(frame_rate.intervals.len(), 5);
+    }
+
+    #[test]
+    fn test_grab_within_retries_none_when_enabled() {
+        let flaky = || {
+            let mut results = [None, Some(7)].into_iter();
+            move |_| results.next().flatten()
+        };
+
+        let mut retries = 0;
+        assert_eq!(
+            grab_within(1000, false, &mut retries, flaky(), |_| true),
+            None
+        );
+        assert_eq!(retries, 0);
+
+        assert_eq!(
+            grab_within(1000, true, &mut retries, flaky(), |_| true),
+            Some(7)
+        );
+        assert_eq!(retries, 1);
+
+        // Items `accept` rejects are skipped without counting as retries.
+        let mut retries = 0;
+        let mut items = [0, 0, 3].into_iter();
+        let grabbed = grab_within(1000, false, &mut retries, |_| items.next(), |&n| n != 0);
+        assert_eq!((grabbed, retries), (Some(3), 0));
+    }
+
+    #[test]
+    fn test_run_with_timeout_abandons_hung_work() {
+        assert_eq!(run_with_timeout(None, || 1, |_| unreachable!()).unwrap(), 1);
+        let quick = run_with_timeout(Some(Duration::from_secs(5)), || 2, |_| unreachable!());
+        assert_eq!(quick.unwrap(), 2);
+
+        // Simulates a driver that blocks in open well past the timeout.
+        let (sender, late_results) = std::sync::mpsc::channel();
+        let sender = Mutex::new(sender);
+        let started = Instant::now();
+        let hung = run_with_timeout(
+            Some(Duration::from_millis(20)),
+            || {
+                std::thread::sleep(Duration::from_millis(200));
+                3
+            },
+            move |late| {
+                let _ = sender.lock().unwrap().send(late);
+            },
+        );
+        assert!(matches!(hung, Err(CcapError::Timeout)));
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert_eq!(late_results.recv_timeout(Duration::from_secs(5)), Ok(3));
+    }
+
+    #[test]
+    fn test_hung_open_leaves_a_closed_handle() {
+        let hang = Duration::from_millis(200);
+        let mut provider = Provider::new().unwrap();
+        provider.set_open_timeout(Duration::from_millis(20));
+
+        // Reusing the handle: it is left to the open thread together with the callback.
+        provider.set_new_frame_callback(|_| true).unwrap();
+        let old_handle = provider.handle;
+        let hung = provider.reopen_in_place(move |_| {
+            std::thread::sleep(hang);
+            false
+        });
+        assert!(matches!(hung, Err(CcapError::Timeout)));
+        assert!(!provider.handle.is_null());
+        assert_ne!(provider.handle, old_handle);
+        assert!(!provider.is_opened());
+        assert_eq!(provider.callback_ptr, None);
+
+        // Recreating: the late handle is dropped and the callback moves to the new one.
+        provider.set_new_frame_callback(|_| true).unwrap();
+        let callback_ptr = provider.callback_ptr;
+        let hung = provider.recreate_handle(move || {
+            std::thread::sleep(hang);
+            unsafe { sys::ccap_provider_create() }
+        });
+        assert!(matches!(hung, Err(CcapError::Timeout)));
+        assert!(!provider.handle.is_null());
+        assert!(!provider.is_opened());
+        assert_eq!(provider.callback_ptr, callback_ptr);
+    }
+
+    #[test]
+    fn test_validate_typed_property_values() {
+        assert!(validate_resolution(640, 480).is_ok());
+        assert!(validate_frame_rate(29.97).is_ok());
+        for fps in [0.0, -30.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(
+                validate_frame_rate(fps),
+                Err(CcapError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_scoped_error_callback_unregisters_on_drop() {
+        let _lock = lock_global_errors();
+        const MESSAGE: &str = "scoped error callback test";
+        let counter = |count: &Arc<AtomicU64>| {
+            let count = Arc::clone(count);
+            move |_: i32, description: &str| {
+                if description == MESSAGE {
+                    count.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        };
+        let (first, second) = (Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0)));
+
+        let guard = Provider::set_scoped_error_callback(counter(&first));
+        call_error_callback(0, MESSAGE);
+        drop(guard);
+        call_error_callback(0, MESSAGE);
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+
+        // A guard whose callback was replaced leaves the newer one alone
+        let stale = Provider::set_scoped_error_callback(counter(&first));
+        let current = Provider::set_scoped_error_callback(counter(&second));
+        drop(stale);
+        call_error_callback(0, MESSAGE);
+        assert_eq!(second.load(Ordering::SeqCst), 1);
+        drop(current);
+        call_error_callback(0, MESSAGE);
+        assert_eq!(second.load(Ordering::SeqCst), 1);
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_native_error_after_guard_drop_skips_freed_callback() {
+        let _lock = lock_global_errors();
+        let description = CString::new("native error after guard drop").unwrap();
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = |calls: &Arc<AtomicU64>| {
+            let calls = Arc::clone(calls);
+            move |_: i32, _: &str| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        // A native report racing the guard drop still carries the freed pointer
+        let guard = Provider::set_scoped_error_callback(counter(&calls));
+        let stale = guard.callback_ptr;
+        drop(guard);
+        let code = sys::CcapErrorCode_CCAP_ERROR_FRAME_CAPTURE_TIMEOUT;
+        unsafe { error_callback_wrapper(code, description.as_ptr(), stale) };
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // It reaches whichever callback is installed by then
+        let guard = Provider::set_scoped_error_callback(counter(&calls));
+        unsafe { error_callback_wrapper(code, description.as_ptr(), stale) };
+        drop(guard);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_poll_event_surfaces_queued_error() {
+        let _lock = lock_global_errors();
+        let mut provider = Provider::new().expect("failed to create provider");
+        // Not started: nothing to grab and no error queued yet.
+        assert!(provider.poll_event().is_none());
+
+        let code = sys::CcapErrorCode_CCAP_ERROR_FRAME_CAPTURE_TIMEOUT as i32;
+        report_error(code, "no frame received");
+        let Some(CcapEvent::Error {
+            code: event_code,
+            message,
+        }) = provider.poll_event()
+        else {
+            panic!("expected the queued error");
+        };
+        assert_eq!(event_code, code);
+        assert_eq!(message, "no frame received");
+        assert!(provider.poll_event().is_none());
+
+        report_error(code, "still no frame");
+        assert_eq!(
+            format!("{:?}", provider.poll_event()),
+            format!(
+                "Some(Error {{ code: {}, message: \"still no frame\" }})",
+                code
+            )
+        );
+    }
+
+    #[test]
+    fn test_poll_event_queue_drops_oldest_errors() {
+        let _lock = lock_global_errors();
+        let mut provider = Provider::new().expect("failed to create provider");
+        assert!(provider.poll_event().is_none());
+        assert_eq!(provider.dropped_error_events(), 0);
+
+        let code = sys::CcapErrorCode_CCAP_ERROR_FRAME_CAPTURE_TIMEOUT as i32;
+        for n in 0..ERROR_QUEUE_CAPACITY + 3 {
+            report_error(code, &format!("error {}", n));
+        }
+        assert_eq!(provider.dropped_error_events(), 3);
+
+        let mut messages = Vec::new();
+        while let Some(CcapEvent::Error { message, .. }) = provider.poll_event() {
+            messages.push(message);
+        }
+        assert_eq!(messages.len(), ERROR_QUEUE_CAPACITY);
+        assert_eq!(messages[0], "error 3");
+        assert_eq!(
+            messages.last().unwrap(),
+            &format!("error {}", ERROR_QUEUE_CAPACITY + 2)
+        );
+    }
+}
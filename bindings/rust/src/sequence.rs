@@ -0,0 +1,103 @@
+//! Detection of dropped frames from their frame indices
+
+/// A run of consecutive frame indices that never arrived (inclusive on both ends)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GapRange {
+    /// First missing frame index
+    pub first: u64,
+    /// Last missing frame index
+    pub last: u64,
+}
+
+impl GapRange {
+    /// Number of frames missing in this gap
+    pub fn missing(&self) -> u64 {
+        self.last - self.first + 1
+    }
+}
+
+/// Summary produced by [`SequenceChecker::report`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SequenceReport {
+    /// Gaps in the order they were detected
+    pub gaps: Vec<GapRange>,
+    /// Total number of missing frames across all gaps
+    pub dropped: u64,
+    /// Number of frame indices fed to the checker
+    pub received: u64,
+}
+
+/// Tracks `frame_index` values and reports frames that were skipped.
+///
+/// Feed every frame's index in arrival order with [`SequenceChecker::feed`]. An index lower
+/// than or equal to the previous one (e.g. after the capture was restarted) resynchronizes
+/// the checker without being counted as a gap.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceChecker {
+    expected: Option<u64>,
+    report: SequenceReport,
+}
+
+impl SequenceChecker {
+    /// Create a checker that accepts any index as the first one
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the next frame index, returning the gap it revealed, if any
+    pub fn feed(&mut self, frame_index: u64) -> Option<GapRange> {
+        self.report.received += 1;
+        let expected = self.expected.replace(frame_index.saturating_add(1));
+        match expected {
+            Some(expected) if frame_index > expected => {
+                let gap = GapRange {
+                    first: expected,
+                    last: frame_index - 1,
+                };
+                self.report.gaps.push(gap);
+                self.report.dropped += gap.missing();
+                Some(gap)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gaps and counters observed so far
+    pub fn report(&self) -> &SequenceReport {
+        &self.report
+    }
+
+    /// Forget all history, e.g. when starting a new recording
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_checker_reports_gap() {
+        let mut checker = SequenceChecker::new();
+        for index in [10, 11, 12] {
+            assert_eq!(checker.feed(index), None);
+        }
+        assert_eq!(
+            checker.feed(16),
+            Some(GapRange {
+                first: 13,
+                last: 15
+            })
+        );
+        assert_eq!(checker.feed(17), None);
+        // A restart resyncs instead of reporting a gap
+        assert_eq!(checker.feed(0), None);
+        assert_eq!(checker.feed(2), Some(GapRange { first: 1, last: 1 }));
+
+        let report = checker.report();
+        assert_eq!(report.gaps.len(), 2);
+        assert_eq!(report.dropped, 4);
+        assert_eq!(report.received, 7);
+    }
+}
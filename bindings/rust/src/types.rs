@@ -1,25 +1,27 @@
+use crate::error::{CcapError, Result};
 use crate::sys;
 
 /// Pixel format enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PixelFormat {
     /// Unknown pixel format
     Unknown,
     /// NV12 pixel format
     Nv12,
-    /// NV12F pixel format
+    /// NV12 pixel format, full range
     Nv12F,
     /// I420 pixel format
     I420,
-    /// I420F pixel format
+    /// I420 pixel format, full range
     I420F,
     /// YUYV pixel format
     Yuyv,
-    /// YUYV flipped pixel format
+    /// YUYV pixel format, full range
     YuyvF,
     /// UYVY pixel format
     Uyvy,
-    /// UYVY flipped pixel format
+    /// UYVY pixel format, full range
     UyvyF,
     /// RGB24 pixel format
     Rgb24,
@@ -81,6 +83,154 @@ impl PixelFormat {
             PixelFormat::Bgra32 => "BGRA32",
         }
     }
+
+    /// Color range of a YUV format, or `None` for RGB formats and `Unknown`.
+    ///
+    /// The `F` variants ([`PixelFormat::Nv12F`], [`PixelFormat::I420F`], ...) are full range.
+    pub fn color_range(self) -> Option<ColorRange> {
+        match self {
+            PixelFormat::Nv12F | PixelFormat::I420F | PixelFormat::YuyvF | PixelFormat::UyvyF => {
+                Some(ColorRange::Full)
+            }
+            PixelFormat::Nv12 | PixelFormat::I420 | PixelFormat::Yuyv | PixelFormat::Uyvy => {
+                Some(ColorRange::Limited)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the number of memory planes used by this pixel format
+    ///
+    /// NV12 uses two planes (Y + interleaved UV), I420 uses three (Y, U, V),
+    /// packed formats use one. `Unknown` has no known layout and returns 0.
+    pub fn num_planes(self) -> usize {
+        match self {
+            PixelFormat::Unknown => 0,
+            PixelFormat::Nv12 | PixelFormat::Nv12F => 2,
+            PixelFormat::I420 | PixelFormat::I420F => 3,
+            _ => 1,
+        }
+    }
+
+    /// Byte size of each plane for a tightly-packed frame of the given size.
+    ///
+    /// Chroma planes of NV12/I420 are subsampled 2x2 (rounded up for odd dimensions).
+    /// Returns an empty vector for [`PixelFormat::Unknown`]. A size that doesn't fit in `usize`
+    /// saturates to `usize::MAX`; see [`PixelFormat::checked_buffer_size`].
+    pub fn plane_sizes(self, width: u32, height: u32) -> Vec<usize> {
+        (0..self.num_planes())
+            .map(|plane| {
+                self.plane_row_bytes(width, plane)
+                    .saturating_mul(self.plane_rows(height, plane))
+            })
+            .collect()
+    }
+
+    /// Total byte size of a tightly-packed frame of the given size (sum of [`plane_sizes`]).
+    ///
+    /// Saturates to `usize::MAX` if the size doesn't fit; use
+    /// [`PixelFormat::checked_buffer_size`] to get an error for dimensions that come from
+    /// untrusted input.
+    ///
+    /// [`plane_sizes`]: PixelFormat::plane_sizes
+    pub fn buffer_size(self, width: u32, height: u32) -> usize {
+        self.plane_sizes(width, height)
+            .into_iter()
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Total byte size of a tightly-packed frame of the given size, without overflowing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CcapError::InvalidParameter` if a row, a plane or the whole frame does not
+    /// fit in `usize`, e.g. for corrupt dimensions on a 32-bit target.
+    pub fn checked_buffer_size(self, width: u32, height: u32) -> Result<usize> {
+        self.checked_plane_sizes(width, height)
+            .and_then(|sizes| sizes.into_iter().try_fold(0usize, usize::checked_add))
+            .ok_or_else(|| {
+                CcapError::InvalidParameter(format!(
+                    "{:?} frame of {}x{} is too large to address",
+                    self, width, height
+                ))
+            })
+    }
+
+    /// [`PixelFormat::plane_sizes`], or `None` if a row or plane size overflows `usize`
+    fn checked_plane_sizes(self, width: u32, height: u32) -> Option<Vec<usize>> {
+        (0..self.num_planes())
+            .map(|plane| {
+                self.checked_plane_row_bytes(width, plane)?
+                    .checked_mul(self.plane_rows(height, plane))
+            })
+            .collect()
+    }
+
+    /// Number of interleaved samples per pixel: 3 for RGB24/BGR24, 4 for RGBA32/BGRA32.
+    ///
+    /// Returns `None` for YUV formats, whose chroma is subsampled and so has no per-pixel
+    /// channel count, and for [`PixelFormat::Unknown`].
+    pub fn channels(self) -> Option<u8> {
+        match self {
+            PixelFormat::Rgb24 | PixelFormat::Bgr24 => Some(3),
+            PixelFormat::Rgba32 | PixelFormat::Bgra32 => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Byte order of the samples of one pixel, e.g. `[B, G, R, A]` for BGRA32.
+    ///
+    /// Empty for the formats [`PixelFormat::channels`] returns `None` for.
+    pub fn channel_order(self) -> &'static [Channel] {
+        match self {
+            PixelFormat::Rgb24 => &[Channel::R, Channel::G, Channel::B],
+            PixelFormat::Bgr24 => &[Channel::B, Channel::G, Channel::R],
+            PixelFormat::Rgba32 => &[Channel::R, Channel::G, Channel::B, Channel::A],
+            PixelFormat::Bgra32 => &[Channel::B, Channel::G, Channel::R, Channel::A],
+            _ => &[],
+        }
+    }
+
+    /// Bytes in one row of `plane` without any stride padding
+    ///
+    /// Cannot overflow on 64-bit targets; saturates to `usize::MAX` where it would on narrower
+    /// ones.
+    pub(crate) fn plane_row_bytes(self, width: u32, plane: usize) -> usize {
+        self.checked_plane_row_bytes(width, plane)
+            .unwrap_or(usize::MAX)
+    }
+
+    /// [`PixelFormat::plane_row_bytes`], or `None` if it overflows `usize`
+    pub(crate) fn checked_plane_row_bytes(self, width: u32, plane: usize) -> Option<usize> {
+        let width = width as usize;
+        let chroma_width = width / 2 + width % 2;
+        match (self, plane) {
+            (PixelFormat::Nv12 | PixelFormat::Nv12F, 0) => Some(width),
+            (PixelFormat::Nv12 | PixelFormat::Nv12F, 1) => chroma_width.checked_mul(2),
+            (PixelFormat::I420 | PixelFormat::I420F, 0) => Some(width),
+            (PixelFormat::I420 | PixelFormat::I420F, 1 | 2) => Some(chroma_width),
+            (
+                PixelFormat::Yuyv | PixelFormat::YuyvF | PixelFormat::Uyvy | PixelFormat::UyvyF,
+                0,
+            ) => chroma_width.checked_mul(4),
+            (PixelFormat::Rgb24 | PixelFormat::Bgr24, 0) => width.checked_mul(3),
+            (PixelFormat::Rgba32 | PixelFormat::Bgra32, 0) => width.checked_mul(4),
+            _ => Some(0),
+        }
+    }
+
+    /// Number of rows in `plane` (chroma planes of 4:2:0 formats have half the rows)
+    pub(crate) fn plane_rows(self, height: u32, plane: usize) -> usize {
+        if plane >= self.num_planes() {
+            return 0;
+        }
+        if plane == 0 {
+            height as usize
+        } else {
+            let height = height as usize;
+            height / 2 + height % 2
+        }
+    }
 }
 
 impl From<PixelFormat> for sys::CcapPixelFormat {
@@ -103,6 +253,19 @@ impl From<PixelFormat> for sys::CcapPixelFormat {
     }
 }
 
+/// A color channel of an interleaved pixel (see [`PixelFormat::channel_order`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Red
+    R,
+    /// Green
+    G,
+    /// Blue
+    B,
+    /// Alpha
+    A,
+}
+
 /// Frame orientation enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FrameOrientation {
@@ -128,6 +291,7 @@ impl From<sys::CcapFrameOrientation> for FrameOrientation {
 
 /// Camera property enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyName {
     /// Width property
     Width,
@@ -144,6 +308,16 @@ pub enum PropertyName {
 }
 
 impl PropertyName {
+    /// Every property, in the order they should be applied when restoring settings
+    pub const ALL: [PropertyName; 6] = [
+        PropertyName::Width,
+        PropertyName::Height,
+        PropertyName::FrameRate,
+        PropertyName::PixelFormatInternal,
+        PropertyName::PixelFormatOutput,
+        PropertyName::FrameOrientation,
+    ];
+
     /// Convert property name to C enum
     pub fn to_c_enum(self) -> sys::CcapPropertyName {
         self.into()
@@ -167,6 +341,27 @@ impl From<PropertyName> for sys::CcapPropertyName {
     }
 }
 
+/// Saved property values, created by
+/// [`Provider::properties_snapshot`](crate::Provider::properties_snapshot)
+///
+/// Serializable with the `serde` feature, e.g. to store camera presets.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PropertiesSnapshot {
+    /// Property values in the order they are applied on restore
+    pub values: Vec<(PropertyName, f64)>,
+}
+
+impl PropertiesSnapshot {
+    /// Saved value of `property`, if it was captured
+    pub fn get(&self, property: PropertyName) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|(name, _)| *name == property)
+            .map(|&(_, value)| value)
+    }
+}
+
 /// Color conversion backend enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColorConversionBackend {
@@ -207,8 +402,177 @@ impl ColorConversionBackend {
     }
 }
 
+/// Quantization range of YUV samples
+///
+/// Limited (TV) range puts black at Y=16 and white at Y=235, full range uses 0-255.
+/// Decoding with the wrong range crushes blacks and clips whites, or makes the
+/// image look washed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorRange {
+    /// Full range (0-255)
+    Full,
+    /// Limited/TV range (16-235 luma, 16-240 chroma)
+    #[default]
+    Limited,
+}
+
+impl ColorRange {
+    /// Conversion flag passed to the C converters (BT.601 matrix with this range)
+    pub fn to_convert_flag(self) -> sys::CcapConvertFlag {
+        let range = match self {
+            ColorRange::Full => sys::CcapConvertFlag_CCAP_CONVERT_FLAG_FULL_RANGE,
+            ColorRange::Limited => sys::CcapConvertFlag_CCAP_CONVERT_FLAG_VIDEO_RANGE,
+        };
+        sys::CcapConvertFlag_CCAP_CONVERT_FLAG_BT601 | range
+    }
+}
+
+/// How color channels relate to alpha in RGBA output
+///
+/// Image files (PNG, egui's `ColorImage::from_rgba_unmultiplied`, `image::RgbaImage`) expect
+/// straight alpha. GPU blending with `ONE, ONE_MINUS_SRC_ALPHA` and most compositors
+/// (e.g. `tiny-skia`, Core Graphics, Direct2D) expect premultiplied alpha.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlphaMode {
+    /// Color channels are independent of alpha
+    #[default]
+    Straight,
+    /// Color channels are scaled by alpha
+    Premultiplied,
+}
+
+/// What captured frames will be used for (see [`crate::DeviceInfo::best_format_for`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatUseCase {
+    /// Move as few bytes per frame as possible
+    LowLatency,
+    /// Avoid color conversion: prefer the YUV formats cameras produce natively
+    LowCpu,
+    /// Upload straight to a display surface (BGRA on Windows and macOS, RGBA elsewhere)
+    DirectDisplay,
+    /// Feed a video encoder, which takes planar 4:2:0 YUV
+    Encoding,
+}
+
+impl FormatUseCase {
+    /// Pixel formats suited to this use case, best first
+    pub fn preferred_formats(self) -> &'static [PixelFormat] {
+        use PixelFormat::*;
+
+        match self {
+            FormatUseCase::LowLatency => &[
+                Nv12, Nv12F, I420, I420F, Yuyv, YuyvF, Uyvy, UyvyF, Rgb24, Bgr24, Bgra32, Rgba32,
+            ],
+            FormatUseCase::LowCpu => &[
+                Nv12, Nv12F, Yuyv, YuyvF, Uyvy, UyvyF, I420, I420F, Bgra32, Rgba32, Bgr24, Rgb24,
+            ],
+            #[cfg(any(target_os = "windows", target_os = "macos"))]
+            FormatUseCase::DirectDisplay => &[
+                Bgra32, Rgba32, Bgr24, Rgb24, Nv12, Nv12F, Yuyv, YuyvF, Uyvy, UyvyF, I420, I420F,
+            ],
+            #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+            FormatUseCase::DirectDisplay => &[
+                Rgba32, Bgra32, Rgb24, Bgr24, Nv12, Nv12F, Yuyv, YuyvF, Uyvy, UyvyF, I420, I420F,
+            ],
+            FormatUseCase::Encoding => &[
+                Nv12, I420, Nv12F, I420F, Yuyv, Uyvy, YuyvF, UyvyF, Bgra32, Rgba32, Rgb24, Bgr24,
+            ],
+        }
+    }
+}
+
+/// Clockwise rotation applied to a frame, e.g. for portrait-mounted cameras
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    /// No rotation
+    #[default]
+    None,
+    /// 90 degrees clockwise
+    Cw90,
+    /// 180 degrees
+    Rot180,
+    /// 270 degrees clockwise (90 degrees counter-clockwise)
+    Cw270,
+}
+
+impl Rotation {
+    /// Dimensions of a `width` x `height` image after this rotation
+    pub fn rotated_size(self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Rotation::None | Rotation::Rot180 => (width, height),
+            Rotation::Cw90 | Rotation::Cw270 => (height, width),
+        }
+    }
+}
+
+/// Camera capture backend on Windows
+///
+/// Both backends are always compiled into the Windows build. When no backend is
+/// chosen, the library uses DirectShow unless the `CCAP_WINDOWS_BACKEND`
+/// environment variable says otherwise. If MediaFoundation is requested but not
+/// available at runtime, the library falls back to DirectShow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowsBackend {
+    /// Let the library decide (DirectShow by default)
+    Auto,
+    /// DirectShow backend
+    DirectShow,
+    /// MediaFoundation backend
+    MediaFoundation,
+}
+
+impl WindowsBackend {
+    /// Backend hint string understood by the C API `extraInfo` parameter
+    pub fn as_extra_info(self) -> &'static str {
+        match self {
+            WindowsBackend::Auto => "auto",
+            WindowsBackend::DirectShow => "dshow",
+            WindowsBackend::MediaFoundation => "msmf",
+        }
+    }
+}
+
+/// A camera identified by index or by name
+///
+/// Index `-1` selects the default camera. Integers and strings convert into a selector, so
+/// `Provider::attach_device(0)` and `Provider::attach_device("FaceTime HD Camera")` both work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceSelector {
+    /// Position in the device list, or `-1` for the default camera
+    Index(i32),
+    /// Device name as reported by device enumeration
+    Name(String),
+    /// Raw device name, for names that aren't valid UTF-8 (see [`crate::DeviceInfo::name_bytes`])
+    NameBytes(Vec<u8>),
+}
+
+impl From<i32> for DeviceSelector {
+    fn from(index: i32) -> Self {
+        DeviceSelector::Index(index)
+    }
+}
+
+impl From<&str> for DeviceSelector {
+    fn from(name: &str) -> Self {
+        DeviceSelector::Name(name.to_string())
+    }
+}
+
+impl From<String> for DeviceSelector {
+    fn from(name: String) -> Self {
+        DeviceSelector::Name(name)
+    }
+}
+
+impl From<&String> for DeviceSelector {
+    fn from(name: &String) -> Self {
+        DeviceSelector::Name(name.clone())
+    }
+}
+
 /// Resolution structure
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resolution {
     /// Width in pixels
     pub width: u32,
@@ -224,3 +588,212 @@ impl From<sys::CcapResolution> for Resolution {
         }
     }
 }
+
+/// Desired capture configuration for
+/// [`Provider::open_and_configure`](crate::Provider::open_and_configure)
+///
+/// Every field is optional; unset fields keep the device defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CapturePreferences {
+    /// Largest acceptable resolution. The largest supported mode that fits is chosen, or the
+    /// smallest mode if none fits. `None` picks the largest mode the device offers.
+    pub max_resolution: Option<Resolution>,
+    /// Output pixel format, converted by the library if the camera doesn't deliver it
+    pub pixel_format: Option<PixelFormat>,
+    /// Lowest acceptable frame rate. The camera's rate is raised to it when lower and kept
+    /// otherwise
+    pub min_frame_rate: Option<f64>,
+}
+
+/// Capture counters reported by [`Provider::stats`](crate::Provider::stats)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CaptureStats {
+    /// Frames handed out by [`Provider::grab_frame`](crate::Provider::grab_frame) or
+    /// consumed by the new-frame callback
+    pub frames_captured: u64,
+    /// Frames the capture pipeline skipped, detected as gaps in the frame indices (e.g.
+    /// frames overwritten because they weren't grabbed fast enough)
+    pub frames_dropped: u64,
+    /// Frames flipped upright by
+    /// [`Provider::set_auto_orient`](crate::Provider::set_auto_orient)
+    pub auto_orient_flips: u64,
+}
+
+/// Capture settings: resolution, output pixel format and frame rate
+///
+/// Returned by [`Provider::capture_settings`](crate::Provider::capture_settings) and applied
+/// with [`Provider::apply_capture_settings`](crate::Provider::apply_capture_settings). The
+/// default is 1280x720 RGB24 at 30 fps. Use [`CaptureSettings::merge`] to layer a
+/// [`CaptureSettingsPatch`] (e.g. user preferences) over a base configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaptureSettings {
+    /// Frame size
+    pub resolution: Resolution,
+    /// Output pixel format
+    pub pixel_format: PixelFormat,
+    /// Frame rate in frames per second
+    pub frame_rate: f64,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        CaptureSettings {
+            resolution: Resolution {
+                width: 1280,
+                height: 720,
+            },
+            pixel_format: PixelFormat::Rgb24,
+            frame_rate: 30.0,
+        }
+    }
+}
+
+impl CaptureSettings {
+    /// Apply the fields set in `patch` on top of these settings
+    pub fn merge(self, patch: &CaptureSettingsPatch) -> CaptureSettings {
+        CaptureSettings {
+            resolution: patch.resolution.unwrap_or(self.resolution),
+            pixel_format: patch.pixel_format.unwrap_or(self.pixel_format),
+            frame_rate: patch.frame_rate.unwrap_or(self.frame_rate),
+        }
+    }
+}
+
+/// Partial [`CaptureSettings`]: only the fields that are `Some` override the base
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CaptureSettingsPatch {
+    /// Frame size override
+    pub resolution: Option<Resolution>,
+    /// Output pixel format override
+    pub pixel_format: Option<PixelFormat>,
+    /// Frame rate override
+    pub frame_rate: Option<f64>,
+}
+
+/// A [`CaptureSettings`] field that a device could not take
+///
+/// Reported by [`Provider::apply_supported_capture_settings`](crate::Provider::apply_supported_capture_settings),
+/// which leaves such settings unchanged instead of failing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SkippedSetting {
+    /// The device doesn't advertise this resolution, or rejected it
+    Resolution(Resolution),
+    /// The device can't deliver this output pixel format
+    PixelFormat(PixelFormat),
+    /// The device rejected this frame rate
+    FrameRate(f64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channels_match_channel_order_and_layout() {
+        use Channel::*;
+
+        let packed: [(PixelFormat, &[Channel]); 4] = [
+            (PixelFormat::Rgb24, &[R, G, B]),
+            (PixelFormat::Bgr24, &[B, G, R]),
+            (PixelFormat::Rgba32, &[R, G, B, A]),
+            (PixelFormat::Bgra32, &[B, G, R, A]),
+        ];
+        for (format, order) in packed {
+            assert_eq!(format.channel_order(), order, "{:?}", format);
+            assert_eq!(format.channels(), Some(order.len() as u8), "{:?}", format);
+            assert_eq!(format.plane_row_bytes(1, 0), order.len(), "{:?}", format);
+            assert_eq!(format.num_planes(), 1, "{:?}", format);
+        }
+
+        for format in [
+            PixelFormat::Unknown,
+            PixelFormat::Nv12,
+            PixelFormat::Nv12F,
+            PixelFormat::I420,
+            PixelFormat::I420F,
+            PixelFormat::Yuyv,
+            PixelFormat::YuyvF,
+            PixelFormat::Uyvy,
+            PixelFormat::UyvyF,
+        ] {
+            assert_eq!(format.channels(), None, "{:?}", format);
+            assert!(format.channel_order().is_empty(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_capture_settings_merge_only_changes_patched_fields() {
+        let base = CaptureSettings::default();
+        let merged = base.merge(&CaptureSettingsPatch {
+            frame_rate: Some(60.0),
+            ..Default::default()
+        });
+        assert_eq!(merged.frame_rate, 60.0);
+        assert_eq!(merged.resolution, base.resolution);
+        assert_eq!(merged.pixel_format, base.pixel_format);
+        assert_eq!(base.merge(&CaptureSettingsPatch::default()), base);
+    }
+
+    #[test]
+    fn test_buffer_size_1080p() {
+        let (width, height) = (1920, 1080);
+        assert_eq!(
+            PixelFormat::Rgb24.buffer_size(width, height),
+            1920 * 1080 * 3
+        );
+        assert_eq!(
+            PixelFormat::Rgba32.buffer_size(width, height),
+            1920 * 1080 * 4
+        );
+        assert_eq!(
+            PixelFormat::Nv12.plane_sizes(width, height),
+            vec![1920 * 1080, 1920 * 540]
+        );
+        assert_eq!(
+            PixelFormat::I420.plane_sizes(width, height),
+            vec![1920 * 1080, 960 * 540, 960 * 540]
+        );
+        assert_eq!(
+            PixelFormat::I420.buffer_size(width, height),
+            1920 * 1080 * 3 / 2
+        );
+    }
+
+    #[test]
+    fn test_buffer_size_odd_dimensions() {
+        assert_eq!(PixelFormat::I420.plane_sizes(3, 3), vec![9, 4, 4]);
+        assert_eq!(PixelFormat::Nv12.plane_sizes(3, 3), vec![9, 8]);
+        assert!(PixelFormat::Unknown.plane_sizes(3, 3).is_empty());
+    }
+
+    #[test]
+    fn test_checked_buffer_size_rejects_overflow() {
+        assert_eq!(PixelFormat::I420.checked_buffer_size(3, 3).unwrap(), 17);
+        assert_eq!(PixelFormat::Unknown.checked_buffer_size(3, 3).unwrap(), 0);
+        for format in [PixelFormat::Rgb24, PixelFormat::Rgba32, PixelFormat::Yuyv] {
+            assert!(matches!(
+                format.checked_buffer_size(u32::MAX, u32::MAX),
+                Err(CcapError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_buffer_size_saturates_instead_of_panicking() {
+        // u32::MAX * u32::MAX * 4 bytes doesn't fit in a 64-bit usize
+        assert_eq!(
+            PixelFormat::Rgba32.buffer_size(u32::MAX, u32::MAX),
+            usize::MAX
+        );
+        assert_eq!(
+            PixelFormat::I420.plane_sizes(u32::MAX, u32::MAX)[0],
+            (u32::MAX as usize) * (u32::MAX as usize)
+        );
+        assert_eq!(
+            PixelFormat::I420.buffer_size(u32::MAX, u32::MAX),
+            usize::MAX
+        );
+    }
+}
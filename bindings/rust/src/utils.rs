@@ -3,7 +3,13 @@ use crate::frame::VideoFrame;
 use crate::sys;
 use crate::types::PixelFormat;
 use std::ffi::CString;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// Level last passed to the native layer by [`Utils::set_log_level`]
+static LOG_LEVEL: Mutex<Option<LogLevel>> = Mutex::new(None);
 
 /// Utility functions
 pub struct Utils;
@@ -70,22 +76,70 @@ impl Utils {
 
         #[cfg(not(windows))]
         {
-            // On Unix-like systems, standard conversion should work
-            let path_str = path
-                .as_ref()
-                .to_str()
-                .ok_or_else(|| CcapError::StringConversionError("Invalid file path".to_string()))?;
-            CString::new(path_str)
+            // Unix paths are arbitrary bytes and need not be valid UTF-8
+            use std::os::unix::ffi::OsStrExt;
+            CString::new(path.as_ref().as_os_str().as_bytes())
                 .map_err(|_| CcapError::StringConversionError("Invalid file path".to_string()))
         }
     }
 
+    /// Convert a path returned by the C library into a `PathBuf` without assuming UTF-8
+    fn bytes_to_path(bytes: Vec<u8>) -> PathBuf {
+        #[cfg(windows)]
+        {
+            // The C library reports Windows paths as UTF-8
+            PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+        }
+
+        #[cfg(not(windows))]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            PathBuf::from(std::ffi::OsString::from_vec(bytes))
+        }
+    }
+
+    /// Convert a path returned by the C library into a `String`
+    fn bytes_to_string(bytes: Vec<u8>) -> Result<String> {
+        String::from_utf8(bytes)
+            .map_err(|_| CcapError::StringConversionError("Invalid output path string".to_string()))
+    }
+
     /// Save a video frame to a file with automatic format detection
+    ///
+    /// Returns the path of the written file. Fails with `CcapError::StringConversionError` if
+    /// that path isn't valid UTF-8; use [`Utils::dump_frame_to_file_os`] to accept any path.
+    ///
+    /// Failures return `CcapError::FileOperationFailed`. When a file can't be created in the
+    /// target directory (e.g. it is missing or read-only), the OS error is attached as its
+    /// source.
     pub fn dump_frame_to_file<P: AsRef<Path>>(
         frame: &VideoFrame,
         filename_no_suffix: P,
     ) -> Result<String> {
-        let c_path = Self::path_to_cstring(filename_no_suffix)?;
+        Self::dump_frame_to_file_bytes(frame, filename_no_suffix).and_then(Self::bytes_to_string)
+    }
+
+    /// Save a video frame to a file with automatic format detection, returning its path.
+    ///
+    /// Unlike [`Utils::dump_frame_to_file`] this doesn't require the path to be valid UTF-8,
+    /// which filesystem paths on Linux aren't guaranteed to be.
+    pub fn dump_frame_to_file_os<P: AsRef<Path>>(
+        frame: &VideoFrame,
+        filename_no_suffix: P,
+    ) -> Result<PathBuf> {
+        Self::dump_frame_to_file_bytes(frame, filename_no_suffix).map(Self::bytes_to_path)
+    }
+
+    fn dump_frame_to_file_bytes<P: AsRef<Path>>(
+        frame: &VideoFrame,
+        filename_no_suffix: P,
+    ) -> Result<Vec<u8>> {
+        let path = filename_no_suffix.as_ref();
+        let c_path = Self::path_to_cstring(path)?;
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
 
         // First call to get required buffer size
         let buffer_size = unsafe {
@@ -93,9 +147,7 @@ impl Utils {
         };
 
         if buffer_size <= 0 {
-            return Err(CcapError::FileOperationFailed(
-                "Failed to dump frame to file".to_string(),
-            ));
+            return Err(file_error(directory, "Failed to dump frame to file"));
         }
 
         // Second call to get actual result
@@ -110,22 +162,45 @@ impl Utils {
         };
 
         if result_len <= 0 {
-            return Err(CcapError::FileOperationFailed(
-                "Failed to dump frame to file".to_string(),
-            ));
+            return Err(file_error(directory, "Failed to dump frame to file"));
         }
 
-        // Convert to string
         buffer.truncate(result_len as usize);
-        String::from_utf8(buffer)
-            .map_err(|_| CcapError::StringConversionError("Invalid output path string".to_string()))
+        Ok(buffer)
     }
 
     /// Save a video frame to directory with auto-generated filename
+    ///
+    /// Returns the path of the written file. Fails with `CcapError::StringConversionError` if
+    /// that path isn't valid UTF-8; use [`Utils::dump_frame_to_directory_os`] to accept any
+    /// path.
+    ///
+    /// Failures return `CcapError::FileOperationFailed`. When a file can't be created in the
+    /// target directory (e.g. it is missing or read-only), the OS error is attached as its
+    /// source.
     pub fn dump_frame_to_directory<P: AsRef<Path>>(
         frame: &VideoFrame,
         directory: P,
     ) -> Result<String> {
+        Self::dump_frame_to_directory_bytes(frame, directory).and_then(Self::bytes_to_string)
+    }
+
+    /// Save a video frame to directory with auto-generated filename, returning its path.
+    ///
+    /// Unlike [`Utils::dump_frame_to_directory`] this doesn't require the path to be valid
+    /// UTF-8.
+    pub fn dump_frame_to_directory_os<P: AsRef<Path>>(
+        frame: &VideoFrame,
+        directory: P,
+    ) -> Result<PathBuf> {
+        Self::dump_frame_to_directory_bytes(frame, directory).map(Self::bytes_to_path)
+    }
+
+    fn dump_frame_to_directory_bytes<P: AsRef<Path>>(
+        frame: &VideoFrame,
+        directory: P,
+    ) -> Result<Vec<u8>> {
+        let directory = directory.as_ref();
         let c_dir = Self::path_to_cstring(directory)?;
 
         // First call to get required buffer size
@@ -139,9 +214,7 @@ impl Utils {
         };
 
         if buffer_size <= 0 {
-            return Err(CcapError::FileOperationFailed(
-                "Failed to dump frame to directory".to_string(),
-            ));
+            return Err(file_error(directory, "Failed to dump frame to directory"));
         }
 
         // Second call to get actual result
@@ -156,15 +229,11 @@ impl Utils {
         };
 
         if result_len <= 0 {
-            return Err(CcapError::FileOperationFailed(
-                "Failed to dump frame to directory".to_string(),
-            ));
+            return Err(file_error(directory, "Failed to dump frame to directory"));
         }
 
-        // Convert to string
         buffer.truncate(result_len as usize);
-        String::from_utf8(buffer)
-            .map_err(|_| CcapError::StringConversionError("Invalid output path string".to_string()))
+        Ok(buffer)
     }
 
     /// Save RGB data as BMP file (generic version)
@@ -199,6 +268,7 @@ impl Utils {
         } else {
             Err(CcapError::FileOperationFailed(
                 "Failed to save RGB data as BMP".to_string(),
+                None,
             ))
         }
     }
@@ -240,10 +310,23 @@ impl Utils {
     }
 
     /// Set log level
+    ///
+    /// The level is process-global and shared by every `Provider`.
     pub fn set_log_level(level: LogLevel) {
+        let mut current = LOG_LEVEL.lock().unwrap_or_else(PoisonError::into_inner);
         unsafe {
             sys::ccap_set_log_level(level.to_c_enum());
         }
+        *current = Some(level);
+    }
+
+    /// Log level last set with [`Utils::set_log_level`], for restoring it later.
+    ///
+    /// The C API can't report the native level, so this is `None` until the level is set
+    /// through this crate; the library's built-in default (errors only in release builds of
+    /// ccap) applies until then.
+    pub fn log_level() -> Option<LogLevel> {
+        *LOG_LEVEL.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
@@ -274,3 +357,95 @@ impl LogLevel {
         }
     }
 }
+
+/// Error for a native write into `directory` that failed.
+///
+/// The native layer only reports success or failure. To recover the OS error behind it, a
+/// probe file is created in `directory` and removed right away; the error from that
+/// `File::create` becomes the source. If the probe can be written, the frame itself is
+/// assumed to be the problem and no source is attached.
+fn file_error(directory: &Path, message: &str) -> CcapError {
+    static PROBE_COUNT: AtomicU64 = AtomicU64::new(0);
+    let probe = directory.join(format!(
+        ".ccap-write-probe-{}-{}",
+        std::process::id(),
+        PROBE_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let source = match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            None
+        }
+        Err(e) => Some(e),
+    };
+    match source {
+        // The OS error is only reported through `source()`, so error chains print it once.
+        Some(source) => CcapError::FileOperationFailed(
+            format!("{} in {}", message, directory.display()),
+            Some(source),
+        ),
+        None => CcapError::FileOperationFailed(message.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_paths_round_trip() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = b"/tmp/ccap-\xff\xfe-frame".to_vec();
+        assert!(String::from_utf8(bytes.clone()).is_err());
+
+        let path = Utils::bytes_to_path(bytes.clone());
+        assert_eq!(path.as_os_str().as_bytes(), &bytes[..]);
+        assert_eq!(
+            Utils::path_to_cstring(&path).unwrap().as_bytes(),
+            &bytes[..]
+        );
+        assert!(matches!(
+            Utils::bytes_to_string(bytes),
+            Err(CcapError::StringConversionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_error_keeps_os_error_for_missing_directory() {
+        use std::error::Error;
+
+        let missing = std::env::temp_dir().join("ccap-test-missing-dir/nested");
+        let err = file_error(&missing, "Failed to dump frame to directory");
+        let CcapError::FileOperationFailed(_, Some(source)) = &err else {
+            panic!("expected an I/O error source, got {:?}", err);
+        };
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+        assert!(err.source().is_some());
+        // The OS error is left to `source()` rather than repeated in the message.
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "File operation failed: Failed to dump frame to directory in {}",
+                missing.display()
+            )
+        );
+
+        // A writable directory points at the frame rather than the filesystem, and the
+        // probe doesn't leave anything behind.
+        let dir = std::env::temp_dir().join(format!("ccap-test-probe-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert!(matches!(
+            file_error(&dir, "Failed to dump frame to file"),
+            CcapError::FileOperationFailed(_, None)
+        ));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -0,0 +1,37 @@
+//! Checks that conflicting Cargo features fail the build with an actionable message
+
+use std::process::Command;
+
+#[test]
+fn test_build_source_with_static_link_fails_to_build() {
+    // `build-source` is a default feature, so adding `static-link` enables both.
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args([
+            "check",
+            "--lib",
+            "--features",
+            "static-link",
+            "--manifest-path",
+        ])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .env(
+            "CARGO_TARGET_DIR",
+            concat!(env!("CARGO_TARGET_TMPDIR"), "/feature-conflict"),
+        )
+        .output()
+        .expect("failed to run cargo");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "build succeeded:\n{}", stderr);
+    assert!(
+        stderr.contains("the `build-source` and `static-link` features are mutually exclusive"),
+        "unexpected error:\n{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("cargo build --no-default-features --features static-link"),
+        "unexpected error:\n{}",
+        stderr
+    );
+}
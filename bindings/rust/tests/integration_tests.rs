@@ -2,12 +2,43 @@
 //!
 //! Tests the main API functionality
 
-use ccap::{CcapError, PixelFormat, Provider, Result};
+use ccap::{
+    CapturePreferences, CaptureSettings, CcapError, PixelFormat, Provider, ProviderBuilder,
+    Resolution, Result, SkippedSetting,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 fn skip_camera_tests() -> bool {
     std::env::var("CCAP_SKIP_CAMERA_TESTS").is_ok()
 }
 
+/// Device 0, opened, or `None` when camera tests are skipped or the device can't be opened
+fn opened_camera() -> Option<Provider> {
+    if skip_camera_tests() {
+        eprintln!("Skipping due to CCAP_SKIP_CAMERA_TESTS");
+        return None;
+    }
+    match Provider::with_device(0) {
+        Ok(provider) => Some(provider),
+        Err(e) => {
+            eprintln!("No device 0, skipping: {}", e);
+            None
+        }
+    }
+}
+
+/// Device 0, capturing, or `None` when it isn't available or can't start capturing
+fn started_camera() -> Option<Provider> {
+    let mut provider = opened_camera()?;
+    if let Err(e) = provider.start_capture() {
+        eprintln!("Device 0 can't start capturing, skipping: {}", e);
+        return None;
+    }
+    Some(provider)
+}
+
 #[test]
 fn test_provider_creation() -> Result<()> {
     let provider = Provider::new()?;
@@ -15,6 +46,87 @@ fn test_provider_creation() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_attach_device_does_not_open() -> Result<()> {
+    let provider = Provider::attach_device(0)?;
+    assert!(!provider.is_opened());
+    assert!(!provider.is_started());
+
+    let provider = Provider::attach_device("ccap-test-missing-device")?;
+    assert!(!provider.is_opened());
+    assert!(Provider::attach_device("bad\0name").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_open_attached_missing_device_fails() -> Result<()> {
+    let mut provider = Provider::attach_device("ccap-test-missing-device")?;
+    assert!(provider.open().is_err());
+    assert!(!provider.is_opened());
+    Ok(())
+}
+
+#[test]
+fn test_open_timeout_reports_open_failure() -> Result<()> {
+    let mut provider = Provider::attach_device("ccap-test-missing-device")?;
+    provider.set_open_timeout(Duration::from_secs(10));
+    assert!(matches!(provider.open(), Err(CcapError::InvalidDevice(_))));
+    assert!(!provider.is_opened());
+    Ok(())
+}
+
+#[test]
+fn test_provider_builder_names_rejected_setting() {
+    let result = ProviderBuilder::new()
+        .device_name("ccap-test-missing-device")
+        .resolution(640, 480)
+        .frame_rate(0.0)
+        .open();
+    match result {
+        Err(CcapError::InvalidParameter(message)) => assert!(message.starts_with("frame rate")),
+        other => panic!("expected a frame rate error, got {:?}", other.err()),
+    }
+
+    let result = Provider::builder()
+        .device_name("ccap-test-missing-device")
+        .pixel_format(PixelFormat::Bgra32)
+        .open();
+    assert!(matches!(result, Err(CcapError::InvalidDevice(_))));
+}
+
+#[test]
+fn test_thumbnail_respects_max_dim() -> Result<()> {
+    let mut provider = Provider::attach_device(0)?;
+    assert!(matches!(
+        provider.thumbnail(0),
+        Err(CcapError::InvalidParameter(_))
+    ));
+
+    if skip_camera_tests() {
+        return Ok(());
+    }
+    // Without a camera the open or the grab fails; only a real frame is checked.
+    if let Ok(thumbnail) = provider.thumbnail(64) {
+        assert!(thumbnail.width().max(thumbnail.height()) <= 64);
+        assert_eq!(thumbnail.pixel_format(), PixelFormat::Rgba32);
+        assert!(!provider.is_opened());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_provider_raw_handle_round_trip() -> Result<()> {
+    let provider = Provider::new()?;
+    let handle = provider.into_raw();
+    assert!(!handle.is_null());
+
+    // SAFETY: the handle came from `into_raw` and nothing else owns it.
+    let provider = unsafe { Provider::from_raw(handle) }?;
+    assert!(!provider.is_opened());
+    drop(provider);
+    Ok(())
+}
+
 #[test]
 fn test_library_version() -> Result<()> {
     let version = ccap::version()?;
@@ -40,6 +152,87 @@ fn test_device_listing() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_open_with_index_opens_requested_device() -> Result<()> {
+    if skip_camera_tests() {
+        eprintln!("Skipping open_with_index due to CCAP_SKIP_CAMERA_TESTS");
+        return Ok(());
+    }
+    let devices = Provider::new()?.list_devices()?;
+    let mut provider = Provider::new()?;
+    for (index, name) in devices.iter().enumerate() {
+        // A camera in use by another process can't be opened; only check the ones that open.
+        if provider.open_with_index(index as i32, false).is_err() {
+            continue;
+        }
+        assert_eq!(
+            &provider.device_info()?.name,
+            name,
+            "device index {}",
+            index
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cached_devices_reuses_arc_until_refresh() -> Result<()> {
+    if skip_camera_tests() {
+        eprintln!("Skipping cached_devices due to CCAP_SKIP_CAMERA_TESTS");
+        return Ok(());
+    }
+    let first = ccap::cached_devices()?;
+    let second = ccap::cached_devices()?;
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let refreshed = ccap::refresh_devices()?;
+    assert!(!Arc::ptr_eq(&first, &refreshed));
+
+    let after_refresh = ccap::cached_devices()?;
+    assert!(Arc::ptr_eq(&refreshed, &after_refresh));
+    Ok(())
+}
+
+#[test]
+#[cfg(windows)]
+fn test_set_windows_backend_before_open() -> Result<()> {
+    let mut provider = Provider::new()?;
+    provider.set_windows_backend(ccap::WindowsBackend::MediaFoundation)?;
+    provider.set_windows_backend(ccap::WindowsBackend::DirectShow)?;
+    provider.set_windows_backend(ccap::WindowsBackend::Auto)?;
+    assert!(!provider.is_opened());
+    Ok(())
+}
+
+#[test]
+#[cfg(not(windows))]
+fn test_set_windows_backend_is_not_supported_elsewhere() -> Result<()> {
+    let mut provider = Provider::new()?;
+    assert!(matches!(
+        provider.set_windows_backend(ccap::WindowsBackend::MediaFoundation),
+        Err(CcapError::NotSupported)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_limit_constants_match_sys() {
+    const _: () = assert!(ccap::MAX_DEVICES > 0 && ccap::MAX_DEVICE_NAME_LENGTH > 0);
+    assert_eq!(ccap::MAX_DEVICES, ccap::sys::CCAP_MAX_DEVICES as usize);
+    assert_eq!(
+        ccap::MAX_DEVICE_NAME_LENGTH,
+        ccap::sys::CCAP_MAX_DEVICE_NAME_LENGTH as usize
+    );
+    assert_eq!(
+        ccap::MAX_PIXEL_FORMATS,
+        ccap::sys::CCAP_MAX_PIXEL_FORMATS as usize
+    );
+    assert_eq!(
+        ccap::MAX_RESOLUTIONS,
+        ccap::sys::CCAP_MAX_RESOLUTIONS as usize
+    );
+}
+
 #[test]
 fn test_pixel_format_conversion() {
     let format = PixelFormat::Rgb24;
@@ -55,6 +248,136 @@ fn test_error_types() {
     assert!(error_str.contains("No camera device found"));
 }
 
+#[test]
+fn test_describe_reports_version_and_device() -> Result<()> {
+    let report = Provider::new()?.describe();
+    assert!(report.contains(&ccap::version()?));
+    assert!(report.contains("frames: 0 captured, 0 dropped, 0 flipped upright"));
+
+    if let Some(provider) = opened_camera() {
+        if let Ok(info) = provider.device_info() {
+            assert!(provider.describe().contains(&info.name));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_typed_setters_reject_invalid_values() -> Result<()> {
+    // Validation happens before the FFI call, so no device is needed.
+    let mut provider = Provider::new()?;
+    for (width, height) in [(0, 0), (0, 480), (640, 0)] {
+        assert!(matches!(
+            provider.set_resolution(width, height),
+            Err(CcapError::InvalidParameter(_))
+        ));
+    }
+    for fps in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            provider.set_frame_rate(fps),
+            Err(CcapError::InvalidParameter(_))
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_supports_property_probe() -> Result<()> {
+    use ccap::PropertyName;
+
+    let provider = Provider::new()?;
+    let supported: Vec<PropertyName> = [
+        PropertyName::Width,
+        PropertyName::Height,
+        PropertyName::FrameRate,
+        PropertyName::PixelFormatInternal,
+        PropertyName::PixelFormatOutput,
+        PropertyName::FrameOrientation,
+    ]
+    .into_iter()
+    .filter(|&property| provider.supports_property(property))
+    .collect();
+    println!("Supported properties: {:?}", supported);
+    assert!(supported.contains(&PropertyName::Width));
+    assert!(supported.contains(&PropertyName::PixelFormatOutput));
+    assert!(!supported.contains(&PropertyName::FrameOrientation));
+    Ok(())
+}
+
+#[test]
+fn test_properties_snapshot_round_trip() -> Result<()> {
+    use ccap::PropertyName;
+
+    let mut provider = Provider::new()?;
+    provider.set_frame_rate(30.0)?;
+    let snapshot = provider.properties_snapshot()?;
+    assert_eq!(snapshot.get(PropertyName::FrameRate), Some(30.0));
+    assert_eq!(snapshot.get(PropertyName::FrameOrientation), None);
+
+    provider.set_frame_rate(15.0)?;
+    provider.apply_properties_snapshot(&snapshot)?;
+    assert_eq!(provider.frame_rate()?, 30.0);
+    Ok(())
+}
+
+#[test]
+fn test_grab_without_open_or_start() -> Result<()> {
+    let mut provider = Provider::new()?;
+    assert!(matches!(
+        provider.grab_frame(0),
+        Err(CcapError::DeviceNotOpened)
+    ));
+
+    let Some(mut provider) = opened_camera() else {
+        return Ok(());
+    };
+    assert!(!provider.is_started());
+    assert!(matches!(
+        provider.grab_frame(0),
+        Err(CcapError::CaptureNotStarted)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_grab_frame_timed_reports_wait_within_timeout() -> Result<()> {
+    let mut provider = Provider::new()?;
+    assert!(matches!(
+        provider.grab_frame_timed(0),
+        Err(CcapError::DeviceNotOpened)
+    ));
+
+    let Some(mut provider) = started_camera() else {
+        return Ok(());
+    };
+
+    let timeout_ms = 3000;
+    if let Some((frame, waited)) = provider.grab_frame_timed(timeout_ms)? {
+        assert!(waited < std::time::Duration::from_millis(timeout_ms as u64));
+        assert!(frame.width() > 0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_prefetch_toggle() -> Result<()> {
+    let mut provider = Provider::new()?;
+    assert!(!provider.is_prefetching());
+    provider.set_prefetch(true);
+    assert!(provider.is_prefetching());
+    assert!(matches!(
+        provider.grab_frame(0),
+        Err(CcapError::DeviceNotOpened)
+    ));
+    provider.set_prefetch(false);
+    assert!(!provider.is_prefetching());
+
+    // Dropping a provider with an active worker must stop the worker first.
+    provider.set_prefetch(true);
+    drop(provider);
+    Ok(())
+}
+
 #[test]
 fn test_provider_with_index() {
     if skip_camera_tests() {
@@ -72,6 +395,192 @@ fn test_provider_with_index() {
     }
 }
 
+#[test]
+fn test_open_twice_is_strict() {
+    // `with_device` opens the device; without a camera there is nothing to test
+    let Some(mut provider) = opened_camera() else {
+        return;
+    };
+    assert!(provider.is_opened());
+    assert!(matches!(
+        provider.open(),
+        Err(CcapError::DeviceAlreadyOpened)
+    ));
+    provider
+        .open_or_noop()
+        .expect("lenient open should succeed");
+    assert!(provider.is_opened());
+}
+
+#[test]
+fn test_open_and_configure_respects_max_resolution() {
+    let Some(mut provider) = opened_camera() else {
+        return;
+    };
+    let modes = match provider.modes() {
+        Ok(modes) => modes,
+        Err(e) => {
+            eprintln!("No device info for device 0, skipping: {}", e);
+            return;
+        }
+    };
+
+    let max = Resolution {
+        width: 640,
+        height: 480,
+    };
+    let settings = provider
+        .open_and_configure(CapturePreferences {
+            max_resolution: Some(max),
+            pixel_format: Some(PixelFormat::Rgb24),
+            ..Default::default()
+        })
+        .expect("Failed to configure device 0");
+    assert!(provider.is_started());
+    assert_eq!(settings.pixel_format, PixelFormat::Rgb24);
+    if modes
+        .iter()
+        .any(|mode| mode.width <= max.width && mode.height <= max.height)
+    {
+        assert!(settings.resolution.width <= max.width);
+        assert!(settings.resolution.height <= max.height);
+    }
+}
+
+#[test]
+fn test_set_mode_matches_advertised_resolution() {
+    let Some(mut provider) = opened_camera() else {
+        return;
+    };
+
+    let modes = match provider.modes() {
+        Ok(modes) => modes,
+        Err(e) => {
+            eprintln!("No device info for device 0, skipping: {}", e);
+            return;
+        }
+    };
+    for (index, mode) in modes.iter().enumerate() {
+        provider.set_mode(index).expect("Failed to set mode");
+        assert_eq!(
+            provider.resolution().expect("Failed to read resolution"),
+            (mode.width, mode.height)
+        );
+        assert_eq!(provider.current_mode_index().unwrap(), Some(index));
+    }
+    assert!(matches!(
+        provider.set_mode(modes.len()),
+        Err(CcapError::InvalidParameter(_))
+    ));
+}
+
+#[test]
+fn test_grab_frame_borrowed_in_sequence() {
+    // Borrowed grabs go through the same checks as `grab_frame`
+    let mut closed = Provider::new().expect("Failed to create provider");
+    assert!(matches!(
+        closed.grab_frame_borrowed(0),
+        Err(CcapError::DeviceNotOpened)
+    ));
+
+    let Some(mut provider) = started_camera() else {
+        return;
+    };
+
+    let mut indices = Vec::new();
+    for _ in 0..2 {
+        // Each borrowed frame is dropped before the next grab
+        let frame = provider
+            .grab_frame_borrowed(3000)
+            .expect("grab failed")
+            .expect("no frame within 3 seconds");
+        indices.push(frame.index());
+    }
+    assert!(indices[1] > indices[0], "indices: {:?}", indices);
+}
+
+#[test]
+fn test_with_next_frame_computes_average_luma() {
+    let Some(mut provider) = started_camera() else {
+        return;
+    };
+
+    let luma = provider
+        .with_next_frame(3000, |frame| {
+            let gray = frame.to_owned_frame()?.to_gray8()?;
+            Ok::<_, ccap::CcapError>(
+                gray.iter().map(|&y| y as f64).sum::<f64>() / gray.len() as f64,
+            )
+        })
+        .expect("grab failed");
+    if let Some(luma) = luma {
+        let luma = luma.expect("luma computation failed");
+        assert!((0.0..=255.0).contains(&luma));
+    }
+}
+
+#[test]
+fn test_capture_for_counts_callback_invocations() {
+    let Some(mut provider) = started_camera() else {
+        return;
+    };
+
+    let mut calls = 0u64;
+    let count = provider
+        .capture_for(Duration::from_millis(500), |frame| {
+            assert!(frame.width() > 0);
+            calls += 1;
+        })
+        .expect("capture failed");
+    assert_eq!(count, calls);
+}
+
+#[test]
+fn test_default_device_is_enumerated() {
+    if skip_camera_tests() {
+        eprintln!("Skipping default_device_is_enumerated due to CCAP_SKIP_CAMERA_TESTS");
+        return;
+    }
+    let devices = Provider::get_devices().expect("Failed to list devices");
+    let default = ccap::default_device().expect("Failed to resolve the default device");
+    assert_eq!(
+        default.map(|device| device.name),
+        devices.first().map(|device| device.name.clone())
+    );
+}
+
+#[test]
+fn test_owned_frame_callback_frames_outlive_callback() {
+    let Some(mut provider) = opened_camera() else {
+        return;
+    };
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = std::sync::Mutex::new(sender);
+    provider
+        .set_owned_frame_callback(move |frame| {
+            let _ = sender.lock().unwrap().send(frame);
+        })
+        .expect("Failed to set owned frame callback");
+    if let Err(e) = provider.start_capture() {
+        eprintln!("Device 0 can't start capturing, skipping: {}", e);
+        return;
+    }
+
+    let frame = receiver.recv_timeout(Duration::from_secs(3));
+    provider.stop_capture().expect("Failed to stop capture");
+    if let Ok(frame) = frame {
+        // The callback has long returned; the copy must still be intact.
+        assert!(frame.width() > 0 && frame.height() > 0);
+        let expected = frame
+            .pixel_format()
+            .plane_sizes(frame.width(), frame.height());
+        for (plane, size) in expected.into_iter().enumerate() {
+            assert!(frame.plane(plane).unwrap().len() >= size);
+        }
+    }
+}
+
 #[test]
 fn test_device_operations_without_camera() {
     if skip_camera_tests() {
@@ -88,3 +597,112 @@ fn test_device_operations_without_camera() {
     let version = Provider::version().expect("Failed to get version");
     assert!(!version.is_empty());
 }
+
+/// Counts how many closures capturing it have been dropped
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn counted_callback(drops: &Arc<AtomicUsize>) -> impl Fn(&ccap::VideoFrame) -> bool + Send + Sync {
+    let counter = DropCounter(Arc::clone(drops));
+    move |_frame| {
+        let _ = &counter;
+        true
+    }
+}
+
+#[test]
+fn test_frame_callback_lifecycle_frees_every_closure_once() -> Result<()> {
+    let drops = Arc::new(AtomicUsize::new(0));
+
+    let mut provider = Provider::new()?;
+    provider.set_new_frame_callback(counted_callback(&drops))?;
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    // Replacing a callback frees the previous closure exactly once
+    for installed in 1..=3 {
+        provider.set_new_frame_callback(counted_callback(&drops))?;
+        assert_eq!(drops.load(Ordering::SeqCst), installed);
+    }
+
+    provider.remove_new_frame_callback()?;
+    assert_eq!(drops.load(Ordering::SeqCst), 4);
+    provider.remove_new_frame_callback()?;
+    assert_eq!(drops.load(Ordering::SeqCst), 4);
+
+    // Dropping the provider frees the installed closure
+    provider.set_new_frame_callback(counted_callback(&drops))?;
+    drop(provider);
+    assert_eq!(drops.load(Ordering::SeqCst), 5);
+
+    // Detaching via into_raw frees the closure too
+    let mut provider = Provider::new()?;
+    provider.set_new_frame_callback(counted_callback(&drops))?;
+    let handle = provider.into_raw();
+    assert_eq!(drops.load(Ordering::SeqCst), 6);
+    // SAFETY: the handle came from `into_raw` and nothing else owns it.
+    drop(unsafe { Provider::from_raw(handle) }?);
+
+    if skip_camera_tests() {
+        return Ok(());
+    }
+    // Reopening keeps the callback whether the native provider is reused or recreated, and
+    // whether or not the open succeeds, so it is only freed with the provider.
+    let mut provider = Provider::new()?;
+    provider.set_new_frame_callback(counted_callback(&drops))?;
+    let _ = provider.open_with_index(0, false);
+    assert_eq!(drops.load(Ordering::SeqCst), 6);
+    drop(provider);
+    assert_eq!(drops.load(Ordering::SeqCst), 7);
+    Ok(())
+}
+
+#[test]
+fn test_repeated_device_switches_free_callback_once() -> Result<()> {
+    if skip_camera_tests() {
+        return Ok(());
+    }
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut provider = Provider::new()?;
+    provider.set_new_frame_callback(counted_callback(&drops))?;
+
+    for _ in 0..5 {
+        // Switching may fail without hardware; the closure stays installed either way.
+        let _ = provider.open_with_index(0, false);
+        let _ = provider.open_device(Some("ccap-test-missing-device"), false);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+    }
+    drop(provider);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn test_apply_capture_settings_to_all() -> Result<()> {
+    let mut providers = [Provider::new()?, Provider::new()?];
+    let invalid = CaptureSettings {
+        frame_rate: 0.0,
+        ..CaptureSettings::default()
+    };
+    assert!(Provider::apply_capture_settings_to_all(&mut providers, &invalid).is_err());
+
+    let settings = CaptureSettings {
+        pixel_format: PixelFormat::Bgr24,
+        ..CaptureSettings::default()
+    };
+    let skipped = Provider::apply_capture_settings_to_all(&mut providers, &settings)?;
+    assert_eq!(skipped.len(), providers.len());
+    for (provider, skipped) in providers.iter().zip(&skipped) {
+        if !skipped.contains(&SkippedSetting::PixelFormat(settings.pixel_format)) {
+            assert_eq!(provider.pixel_format()?, settings.pixel_format);
+        }
+        if !skipped.contains(&SkippedSetting::FrameRate(settings.frame_rate)) {
+            assert_eq!(provider.frame_rate()?, settings.frame_rate);
+        }
+    }
+    Ok(())
+}